@@ -14,16 +14,16 @@ fn main() {
     database.add_table("default",
         Table::new(
         vec![
-            Column { name: "Name".into(), col_type: ValueType::Text },
-            Column { name: "Height".into(), col_type: ValueType::Float }
+            Column { name: "Name".into(), col_type: ValueType::Text, nullable: true },
+            Column { name: "Height".into(), col_type: ValueType::Float, nullable: true }
         ],
         MemoryStorage::new()
     ));
 
     let table = database.get_table("default").unwrap();
 
-    table.insert(vec![Value::Text("Alice".into()), Value::Float(170.5)]);
-    table.insert(vec![Value::Text("Bob".into()), Value::Float(183.2)]);
+    table.insert(vec![Value::Text("Alice".into()), Value::Float(170.5)]).unwrap();
+    table.insert(vec![Value::Text("Bob".into()), Value::Float(183.2)]).unwrap();
 
     println!("Kirin DB initialized with MemoryStorage.");
 
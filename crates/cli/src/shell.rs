@@ -1,5 +1,5 @@
 use database::Database;
-use core::Column;
+use core::{Column, KirinError};
 
 use std::io::{stdin, Result, Write};
 
@@ -30,11 +30,9 @@ impl <'a> Shell<'a> {
 
                 cmd if cmd.starts_with(".") => self.execute_command(cmd)?,
 
-                cmd if cmd.starts_with("SELECT") => self.handle_select(cmd)?,
+                "" => (),
 
-                cmd if cmd.starts_with("INSERT") => self.handle_insert(cmd)?,
-
-                _ => (),
+                cmd => self.handle_statement(cmd)?,
             }
         }
 
@@ -72,7 +70,10 @@ impl <'a> Shell<'a> {
                 };
 
                 let column_text = table.columns.iter()
-                    .map(|Column{ name, col_type }| format!("{name} <{col_type}>"))
+                    .map(|Column{ name, col_type, nullable }| match nullable {
+                        true => format!("{name} <{col_type}>"),
+                        false => format!("{name} <{col_type}> NOT NULL"),
+                    })
                     .fold(String::from("|"), |acc, x| format!("{acc} {x} |"));
                 writeln!(self.writer, "{column_text}")
 
@@ -83,13 +84,53 @@ impl <'a> Shell<'a> {
         }
     }
 
+    pub fn handle_statement(&mut self, input: &str) -> Result<()> {
+        let result = sql::handler::handle_statement(&mut self.db, self.writer, input);
+        self.report(input, result)
+    }
+
     pub fn handle_select(&mut self, input: &str) -> Result<()> {
-        sql::handler::handle_select(&mut self.db, self.writer, input)
+        let result = sql::handler::handle_select(&mut self.db, self.writer, input);
+        self.report(input, result)
     }
 
 
     pub fn handle_insert(&mut self, input: &str) -> Result<()> {
-        sql::handler::handle_insert(&mut self.db, self.writer, input)
+        let result = sql::handler::handle_insert(&mut self.db, self.writer, input);
+        self.report(input, result)
+    }
+
+    /// Prints a failed statement's error, if any
+    fn report(&mut self, input: &str, result: core::error::Result<()>) -> Result<()> {
+        match result {
+            Ok(()) => Ok(()),
+            Err(error) => self.render_error(input, &error),
+        }
+    }
+
+    /// Prints an error, underlining the offending token when its position is known
+    ///
+    /// ```text
+    /// Error: Parse error: Expected FROM, found 'FRM' (line 1, column 10)
+    ///   SELECT * FRM users
+    ///            ^^^
+    /// ```
+    pub fn render_error(&mut self, input: &str, error: &KirinError) -> Result<()> {
+        writeln!(self.writer, "Error: {error}")?;
+
+        let Some(span) = error.span() else {
+            return Ok(())
+        };
+
+        let Some(line) = input.lines().nth(span.line - 1) else {
+            return Ok(())
+        };
+
+        let padding = " ".repeat(span.column - 1);
+        // A token spanning several lines is underlined to the end of its first
+        let caret = "^".repeat(span.len.min(line.chars().count().saturating_sub(span.column - 1)).max(1));
+        writeln!(self.writer, "  {line}")?;
+        writeln!(self.writer, "  {padding}{caret}")
     }
 
 
@@ -4,4 +4,6 @@ use crate::ValueType;
 pub struct Column {
     pub name: String,
     pub col_type: ValueType,
+    /// Whether the column accepts NULL
+    pub nullable: bool,
 }
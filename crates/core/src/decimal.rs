@@ -0,0 +1,100 @@
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive, Zero};
+
+use crate::error::Result;
+use crate::KirinError;
+
+/// The most digits a DECIMAL may hold
+pub const MAX_PRECISION: u32 = 1000;
+
+/// The precision of a DECIMAL declared without one
+pub const DEFAULT_PRECISION: u32 = 18;
+
+/// The fewest digits after the point kept by a division
+pub const DIVISION_SCALE: u32 = 16;
+
+/// The precision and scale of the smallest DECIMAL type that holds `decimal`,
+/// e.g. `(4, 2)` for 12.50
+pub fn shape(decimal: &BigDecimal) -> (u32, u32) {
+    let (_, scale) = decimal.as_bigint_and_exponent();
+    // A negative scale stands for trailing zeros before the point
+    let digits = decimal.digits() as i64 - scale.min(0);
+    let scale = scale.max(0);
+    (digits.max(scale).max(1) as u32, scale as u32)
+}
+
+/// The digits of `decimal` before the point, counting the zeros right after
+/// the point of a value below one as negative, e.g. 3 for 123.4 and -2 for 0.0012
+fn integer_digits(decimal: &BigDecimal) -> i64 {
+    let (_, scale) = decimal.as_bigint_and_exponent();
+    decimal.digits() as i64 - scale
+}
+
+/// Rounds `decimal` to `scale` digits after the point, halves away from zero
+pub fn round(decimal: &BigDecimal, scale: u32) -> BigDecimal {
+    decimal.with_scale_round(scale as i64, RoundingMode::HalfUp)
+}
+
+/// Rounds `decimal` to fit DECIMAL(precision, scale), if it has no more than
+/// `precision - scale` digits before the point
+pub fn fit(decimal: &BigDecimal, precision: u32, scale: u32) -> Option<BigDecimal> {
+    // Checked before rounding, which would otherwise build a power of ten as
+    // long as the exponent of a value such as 1e999999999 or 1e-999999999
+    let digits = integer_digits(decimal);
+    if decimal.is_zero() || digits < -(scale as i64) {
+        return Some(BigDecimal::zero().with_scale(scale as i64));
+    }
+    if digits > precision as i64 - scale as i64 {
+        return None;
+    }
+
+    let rounded = round(decimal, scale);
+    (shape(&rounded).0 <= precision).then_some(rounded)
+}
+
+/// `left / right`, keeping as many digits after the point as either side or
+/// [`DIVISION_SCALE`], whichever is most, up to [`MAX_PRECISION`]. Fails on dividing by zero, and on a
+/// quotient with more than [`MAX_PRECISION`] digits before the point
+pub fn divide(left: &BigDecimal, right: &BigDecimal) -> Result<BigDecimal> {
+    if right.is_zero() {
+        return Err(KirinError::DivisionByZero);
+    }
+    let scale = shape(left).1.max(shape(right).1).clamp(DIVISION_SCALE, MAX_PRECISION);
+
+    // The quotient has about as many digits before the point as the sides
+    // differ by, which bounds the power of ten it is computed with
+    let digits = integer_digits(left) - integer_digits(right);
+    if left.is_zero() || digits + 1 < -(scale as i64) {
+        return Ok(BigDecimal::zero().with_scale(scale as i64));
+    }
+    if digits > MAX_PRECISION as i64 {
+        return Err(KirinError::Overflow);
+    }
+
+    // Integer division truncates, so one more digit than kept is enough to round by
+    let (numerator, left_scale) = left.as_bigint_and_exponent();
+    let (denominator, right_scale) = right.as_bigint_and_exponent();
+    let shift = scale as i64 + 1 + right_scale - left_scale;
+    let power = BigInt::from(10).pow(shift.unsigned_abs() as u32);
+    let quotient = match shift >= 0 {
+        true => numerator * power / denominator,
+        false => numerator / (denominator * power),
+    };
+    Ok(round(&BigDecimal::new(quotient, scale as i64 + 1), scale))
+}
+
+/// The decimal a float stands for: a whole float exactly, and any other the
+/// shortest decimal that reads back as it, so 0.1 is 0.1 and not its binary
+/// approximation. NaN and the infinities have none
+pub fn from_float(float: f64) -> Option<BigDecimal> {
+    match float.is_finite() {
+        false => None,
+        true if float.fract() == 0.0 => BigDecimal::try_from(float).ok(),
+        true => float.to_string().parse().ok(),
+    }
+}
+
+/// The nearest float to `decimal`
+pub fn to_float(decimal: &BigDecimal) -> f64 {
+    decimal.to_f64().unwrap_or(f64::NAN)
+}
@@ -0,0 +1,167 @@
+use std::fmt::Display;
+
+use crate::ValueType;
+
+/// Location of a token within SQL source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number, counted in characters
+    pub column: usize,
+    /// Length of the token in characters
+    pub len: usize,
+}
+
+/// Every error that can be reported by Kirin
+#[derive(Debug)]
+pub enum KirinError {
+    /// The SQL text could not be parsed
+    Parse { message: String, span: Span },
+
+    /// A table name that does not exist in the database
+    UnknownTable(String),
+
+    /// A column name that does not exist in the queried tables
+    UnknownColumn(String),
+
+    /// An unqualified column name that matches columns of several tables
+    AmbiguousColumn(String),
+
+    /// A function name that is not defined
+    UnknownFunction(String),
+
+    /// A table with this name already exists
+    TableExists(String),
+
+    /// A column with this name already exists in the table
+    ColumnExists(String),
+
+    /// A value whose type does not match what the column or operator expects
+    TypeMismatch { column: String, expected: ValueType, actual: ValueType },
+
+    /// A value whose type could be converted to the column's, but not this
+    /// value, e.g. 2.5 for an INT column or 'soon' for a TIMESTAMP column
+    InvalidConversion { column: String, value: String, expected: ValueType },
+
+    /// A CAST between types that do not convert, or of a value that does not
+    /// read as the target type, e.g. `CAST('abc' AS INT)`
+    InvalidCast { value: String, from: ValueType, to: ValueType },
+
+    /// NULL stored in a column declared NOT NULL
+    NotNull(String),
+
+    /// The number of values does not match the number of columns
+    ArityMismatch { expected: usize, actual: usize },
+
+    /// An operator applied to values it is not defined for, e.g. `'a' - 1`
+    InvalidOperands { op: String, operands: Vec<ValueType> },
+
+    /// An aggregate function used where it is not allowed, or a column
+    /// outside GROUP BY referenced by an aggregate query
+    InvalidAggregate(String),
+
+    /// A window function used where it is not allowed, or called with
+    /// arguments or a frame it does not accept
+    InvalidWindow(String),
+
+    /// A subquery whose result does not fit where it is used, e.g. a scalar
+    /// subquery returning several rows
+    InvalidSubquery(String),
+
+    /// A column whose type differs between the queries combined by a set
+    /// operator; `position` counts from 1
+    IncompatibleColumn { operator: String, position: usize, left: ValueType, right: ValueType },
+
+    /// A recursive CTE still adding rows after the most rounds allowed
+    RecursionLimit { cte: String, limit: usize },
+
+    /// A value outside the range an operation accepts, e.g. a negative LIMIT
+    InvalidArgument(String),
+
+    /// Division or remainder by zero
+    DivisionByZero,
+
+    /// An arithmetic result that does not fit in its type
+    Overflow,
+
+    /// A valid statement that the engine cannot execute yet
+    Unsupported(String),
+
+    /// Reading or writing the underlying storage failed
+    Io(std::io::Error),
+}
+
+pub type Result<T, E = KirinError> = std::result::Result<T, E>;
+
+impl KirinError {
+    /// Source position of the error, if it refers to a specific token
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            KirinError::Parse { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+}
+
+impl Display for KirinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KirinError::Parse { message, span } => {
+                write!(f, "Parse error: {message} (line {}, column {})", span.line, span.column)
+            }
+            KirinError::UnknownTable(table) => write!(f, "Table '{table}' not found"),
+            KirinError::UnknownColumn(column) => write!(f, "Column '{column}' not found"),
+            KirinError::AmbiguousColumn(column) => write!(f, "Column '{column}' is ambiguous"),
+            KirinError::UnknownFunction(name) => write!(f, "Function '{name}' not found"),
+            KirinError::TableExists(table) => write!(f, "Table '{table}' already exists"),
+            KirinError::ColumnExists(column) => write!(f, "Column '{column}' already exists"),
+            KirinError::TypeMismatch { column, expected, actual } => {
+                write!(f, "Type mismatch for '{column}': expected {expected}, found {actual}")
+            }
+            KirinError::InvalidConversion { column, value, expected } => {
+                write!(f, "Value '{value}' for '{column}' cannot be converted to {expected} without losing information")
+            }
+            KirinError::InvalidCast { value, from, to } => {
+                write!(f, "Cannot cast '{value}' from {from} to {to}")
+            }
+            KirinError::NotNull(column) => write!(f, "Column '{column}' cannot be NULL"),
+            KirinError::ArityMismatch { expected, actual } => {
+                write!(f, "Expected {expected} values, found {actual}")
+            }
+            KirinError::InvalidOperands { op, operands } => {
+                let operands = operands.iter().map(ValueType::to_string).collect::<Vec<_>>().join(" and ");
+                write!(f, "Operator '{op}' cannot be applied to {operands}")
+            }
+            KirinError::InvalidAggregate(message) => write!(f, "Invalid aggregate query: {message}"),
+            KirinError::InvalidWindow(message) => write!(f, "Invalid window function: {message}"),
+            KirinError::InvalidSubquery(message) => write!(f, "Invalid subquery: {message}"),
+            KirinError::IncompatibleColumn { operator, position, left, right } => {
+                write!(f, "Column {position} of {operator} has incompatible types {left} and {right}")
+            }
+            KirinError::RecursionLimit { cte, limit } => {
+                write!(f, "Recursive query '{cte}' did not finish within {limit} rounds")
+            }
+            KirinError::InvalidArgument(message) => write!(f, "Invalid argument: {message}"),
+            KirinError::DivisionByZero => write!(f, "Division by zero"),
+            KirinError::Overflow => write!(f, "Numeric overflow"),
+            KirinError::Unsupported(feature) => write!(f, "Unsupported: {feature}"),
+            KirinError::Io(error) => write!(f, "Storage I/O error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for KirinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KirinError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for KirinError {
+    fn from(error: std::io::Error) -> Self {
+        KirinError::Io(error)
+    }
+}
@@ -0,0 +1,216 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+use chrono::{Days, Months, NaiveDateTime, TimeDelta};
+
+/// Microseconds in a day of 24 hours
+pub const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Days in a month, when months and days are compared or carried over
+pub const DAYS_PER_MONTH: i64 = 30;
+
+/// A span of time made of months, days and microseconds.
+///
+/// The parts are kept apart because months and days vary in length: adding
+/// a month moves to the same day of the next month, and adding a day to the
+/// same time of the next day. Intervals compare by their length, taking a
+/// month as 30 days and a day as 24 hours, so `1 mon` equals `30 days`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Interval { months, days, micros }
+    }
+
+    /// The whole days and the time from `start` to `end`, e.g. `1 day 02:00:00`
+    pub fn between(start: NaiveDateTime, end: NaiveDateTime) -> Option<Interval> {
+        let micros = end.signed_duration_since(start).num_microseconds()?;
+        let days = i32::try_from(micros / MICROS_PER_DAY).ok()?;
+        Some(Interval::new(0, days, micros % MICROS_PER_DAY))
+    }
+
+    pub fn checked_add(self, other: Interval) -> Option<Interval> {
+        Some(Interval::new(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.micros.checked_add(other.micros)?,
+        ))
+    }
+
+    pub fn checked_sub(self, other: Interval) -> Option<Interval> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Interval> {
+        Some(Interval::new(self.months.checked_neg()?, self.days.checked_neg()?, self.micros.checked_neg()?))
+    }
+
+    /// Scales every part by `factor`, carrying a fraction of a month over to
+    /// days and a fraction of a day over to microseconds
+    pub fn checked_mul(self, factor: f64) -> Option<Interval> {
+        carry(self.months as f64 * factor, self.days as f64 * factor, self.micros as f64 * factor)
+    }
+
+    /// Divides every part by `divisor`, carrying over fractions as
+    /// [`Interval::checked_mul`] does. Dividing by zero gives None
+    pub fn checked_div(self, divisor: f64) -> Option<Interval> {
+        match divisor == 0.0 {
+            true => None,
+            false => carry(self.months as f64 / divisor, self.days as f64 / divisor, self.micros as f64 / divisor),
+        }
+    }
+
+    /// Adds the interval to `datetime`: the months first, ending on the last
+    /// day of the month when the day does not exist in it, then the days and
+    /// the time
+    pub fn add_to(self, datetime: NaiveDateTime) -> Option<NaiveDateTime> {
+        let months = Months::new(self.months.unsigned_abs());
+        let datetime = match self.months >= 0 {
+            true => datetime.checked_add_months(months)?,
+            false => datetime.checked_sub_months(months)?,
+        };
+        let days = Days::new(self.days.unsigned_abs() as u64);
+        let datetime = match self.days >= 0 {
+            true => datetime.checked_add_days(days)?,
+            false => datetime.checked_sub_days(days)?,
+        };
+        datetime.checked_add_signed(TimeDelta::microseconds(self.micros))
+    }
+
+    /// The interval's length in microseconds, taking a month as 30 days
+    pub fn length(&self) -> i128 {
+        let days = self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128;
+        days * MICROS_PER_DAY as i128 + self.micros as i128
+    }
+
+    /// Reads an interval written as amounts with units, e.g. `1 year 2 mons`,
+    /// `-3 days` or `1.5 hours`, and an optional time `[-]hh:mm[:ss[.ffffff]]`.
+    ///
+    /// The units are year, month (or mon), week, day, hour, minute (or min),
+    /// second (or sec), millisecond and microsecond, each also in the plural
+    /// and in any case. Fractions carry over to the smaller parts, so
+    /// `1.5 years` is `1 year 6 mons`. This reads back what Display writes
+    pub fn parse(text: &str) -> Option<Interval> {
+        let mut words = text.split_whitespace().peekable();
+        words.peek()?;
+
+        let mut interval = Interval::default();
+        while let Some(word) = words.next() {
+            let part = match word.contains(':') {
+                true => Interval::new(0, 0, parse_time(word)?),
+                false => {
+                    let amount: f64 = word.parse().ok().filter(|amount: &f64| amount.is_finite())?;
+                    let unit = words.next()?.to_ascii_lowercase();
+                    match unit.strip_suffix('s').unwrap_or(&unit) {
+                        "year" => carry(amount * 12.0, 0.0, 0.0)?,
+                        "month" | "mon" => carry(amount, 0.0, 0.0)?,
+                        "week" => carry(0.0, amount * 7.0, 0.0)?,
+                        "day" => carry(0.0, amount, 0.0)?,
+                        "hour" => carry(0.0, 0.0, amount * 3_600_000_000.0)?,
+                        "minute" | "min" => carry(0.0, 0.0, amount * 60_000_000.0)?,
+                        "second" | "sec" => carry(0.0, 0.0, amount * 1_000_000.0)?,
+                        "millisecond" => carry(0.0, 0.0, amount * 1_000.0)?,
+                        "microsecond" => carry(0.0, 0.0, amount)?,
+                        _ => return None,
+                    }
+                }
+            };
+            interval = interval.checked_add(part)?;
+        }
+        Some(interval)
+    }
+}
+
+/// Builds an interval from fractional parts, carrying a fraction of a month
+/// over to days and a fraction of a day over to microseconds
+fn carry(months: f64, days: f64, micros: f64) -> Option<Interval> {
+    let days = days + months.fract() * DAYS_PER_MONTH as f64;
+    let micros = micros + days.fract() * MICROS_PER_DAY as f64;
+    let months = months.trunc();
+    let days = days.trunc();
+    let micros = micros.round();
+    let fits = |value: f64, min: f64, max: f64| (min..=max).contains(&value);
+    if !fits(months, i32::MIN as f64, i32::MAX as f64) || !fits(days, i32::MIN as f64, i32::MAX as f64)
+        || !fits(micros, i64::MIN as f64, i64::MAX as f64) {
+        return None;
+    }
+    Some(Interval::new(months as i32, days as i32, micros as i64))
+}
+
+/// Reads `[-]hh:mm[:ss[.ffffff]]` as microseconds
+fn parse_time(text: &str) -> Option<i64> {
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let mut parts = text.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next().map_or(Some(0.0), |seconds| seconds.parse().ok())?;
+    if parts.next().is_some() || !(0..60).contains(&minutes) || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    let micros = hours.checked_mul(3_600_000_000)?.checked_add(minutes * 60_000_000)?;
+    Some(sign * micros.checked_add((seconds * 1_000_000.0).round() as i64)?)
+}
+
+impl Display for Interval {
+    /// Writes the interval the way PostgreSQL does, e.g. `1 year 2 mons 3 days 04:05:06`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plural = |amount: i32| if amount.abs() == 1 { "" } else { "s" };
+        let mut parts = Vec::new();
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            parts.push(format!("{years} year{}", plural(years)));
+        }
+        if months != 0 {
+            parts.push(format!("{months} mon{}", plural(months)));
+        }
+        if self.days != 0 {
+            parts.push(format!("{} day{}", self.days, plural(self.days)));
+        }
+        if self.micros != 0 || parts.is_empty() {
+            let sign = if self.micros < 0 { "-" } else { "" };
+            let micros = self.micros.unsigned_abs();
+            let seconds = micros / 1_000_000 % 60;
+            let mut time = format!("{sign}{:02}:{:02}:{seconds:02}", micros / 3_600_000_000, micros / 60_000_000 % 60);
+            if !micros.is_multiple_of(1_000_000) {
+                time += format!(".{:06}", micros % 1_000_000).trim_end_matches('0');
+            }
+            parts.push(time);
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.length() == other.length()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.length().cmp(&other.length())
+    }
+}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.length().hash(state);
+    }
+}
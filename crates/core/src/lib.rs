@@ -3,11 +3,16 @@ pub mod column;
 pub mod value_type;
 pub mod row;
 pub mod value;
+pub mod decimal;
+pub mod interval;
 pub mod storage;
+pub mod error;
 
 pub use table::Table;
 pub use column::Column;
 pub use row::{Row, RowId};
 pub use value_type::ValueType;
-pub use value::Value;
-pub use storage::Storage;
\ No newline at end of file
+pub use interval::Interval;
+pub use value::{parse_date, parse_datetime, parse_datetime_in, parse_hex, parse_time, parse_time_zone, to_hex, Value};
+pub use storage::Storage;
+pub use error::{KirinError, Span};
\ No newline at end of file
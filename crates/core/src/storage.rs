@@ -1,7 +1,6 @@
 use crate::{Row, RowId};
 
 /// Generic storage trait
-
 pub trait Storage {
     /// Insert a row; returns a RowId for retrieval
     fn insert(&mut self, row: Row) -> RowId;
@@ -9,9 +8,15 @@ pub trait Storage {
     /// Get a row by RowId
     fn get(&self, row_id: RowId) -> Option<&Row>;
 
+    /// Replace the row stored under RowId; returns false if it does not exist
+    fn update(&mut self, row_id: RowId, row: Row) -> bool;
+
     /// Delete a row by RowId
     fn delete(&mut self, row_id: RowId) -> bool;
 
     /// Iterate over all rows
     fn iter(&self) -> Box<dyn Iterator<Item = &Row> + '_>;
+
+    /// Iterate over all rows together with their RowIds
+    fn iter_with_ids(&self) -> Box<dyn Iterator<Item = (RowId, &Row)> + '_>;
 }
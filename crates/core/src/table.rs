@@ -1,10 +1,18 @@
 use std::iter::zip;
 
-use crate::{Column, Row, RowId, Storage, Value, ValueType};
+use bigdecimal::ToPrimitive;
+use chrono::{FixedOffset, Offset, Utc};
+
+use crate::decimal;
+use crate::error::Result;
+use crate::value::{exact_float, exact_int};
+use crate::{Column, KirinError, Row, RowId, Storage, Value, ValueType};
 
 pub struct Table<S: Storage> {
     storage: S,
     pub columns: Vec<Column>,
+    /// The zone in which text and dates stored as timestamps with a time zone are read
+    time_zone: FixedOffset,
 }
 
 impl<S: Storage> Table<S> {
@@ -12,26 +20,62 @@ impl<S: Storage> Table<S> {
         Self {
             columns,
             storage,
+            time_zone: Utc.fix(),
         }
     }
 
-    /// Attempts to insert a logical row.
+    pub fn time_zone(&self) -> FixedOffset {
+        self.time_zone
+    }
+
+    /// Changes the zone in which values without one are read, UTC by default
+    pub fn set_time_zone(&mut self, time_zone: FixedOffset) {
+        self.time_zone = time_zone;
+    }
+
+    /// Attempts to insert a logical row, converting its values to the column
+    /// types as described for [`Table::coerce_values`].
     /// 
     /// Returns a row id for retrieval
-    pub fn insert(&mut self, values: Vec<Value>) -> Option<RowId> {
+    pub fn insert(&mut self, values: Vec<Value>) -> Result<RowId> {
+        let values = self.coerce_values(values)?;
+
+        let row = Row { values };
+        Ok(self.storage.insert(row))
+    }
+
+    /// Replaces the values of an existing row, with the same checks as [`Table::insert`].
+    ///
+    /// Returns false if no row has this id
+    pub fn update(&mut self, row_id: RowId, values: Vec<Value>) -> Result<bool> {
+        let values = self.coerce_values(values)?;
+
+        let row = Row { values };
+        Ok(self.storage.update(row_id, row))
+    }
+
+    /// Deletes a row, returning false if no row has this id
+    pub fn delete(&mut self, row_id: RowId) -> bool {
+        self.storage.delete(row_id)
+    }
+
+    /// Checks a logical row against the column count and types, converting
+    /// values where a column's type can hold them exactly.
+    ///
+    /// Integers become floats and text becomes a date, time, timestamp or
+    /// interval, while floats and decimals only become integers when they
+    /// are whole numbers. Decimals become the nearest float, and dates become
+    /// timestamps at midnight. Text and dates stored as timestamps with a time
+    /// zone are read in the table's [`Table::time_zone`]
+    pub fn coerce_values(&self, values: Vec<Value>) -> Result<Vec<Value>> {
 
         // Check if column counts match
         if values.len() != self.columns.len() {
-            return None;
+            return Err(KirinError::ArityMismatch { expected: self.columns.len(), actual: values.len() });
         }
 
         // Check if all column types match
-        if !zip(values.iter(), &self.columns).all(|(val, col)| ValueType::from(val) == col.col_type ) {
-            return None;
-        }
-
-        let row = Row { values };
-        Some(self.storage.insert(row))
+        zip(values, &self.columns).map(|(val, col)| coerce(col, val, self.time_zone)).collect()
     }
 
     /// Attempts to get a single row by row id
@@ -43,4 +87,113 @@ impl<S: Storage> Table<S> {
     pub fn iter(&self) -> impl Iterator<Item = &Row> {
         self.storage.iter()
     }
+
+    /// Iterate over all rows together with their row ids
+    pub fn iter_with_ids(&self) -> impl Iterator<Item = (RowId, &Row)> {
+        self.storage.iter_with_ids()
+    }
+
+    /// Finds the position of a column by name, ignoring case
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|col| col.name.eq_ignore_ascii_case(name))
+    }
+
+    fn expect_column_index(&self, name: &str) -> Result<usize> {
+        self.column_index(name).ok_or_else(|| KirinError::UnknownColumn(name.to_string()))
+    }
+
+    /// Appends a column, filling existing rows with `default`, or NULL when omitted
+    pub fn add_column(&mut self, column: Column, default: Option<Value>) -> Result<()> {
+        if self.column_index(&column.name).is_some() {
+            return Err(KirinError::ColumnExists(column.name));
+        }
+
+        let ids: Vec<RowId> = self.iter_with_ids().map(|(id, _)| id).collect();
+
+        // The default is only stored when there are rows to backfill
+        let mut default = default.unwrap_or(Value::Null);
+        if !ids.is_empty() || !default.is_null() {
+            default = coerce(&column, default, self.time_zone)?;
+        }
+
+        self.rewrite_rows(&ids, |values| values.push(default.clone()));
+
+        self.columns.push(column);
+        Ok(())
+    }
+
+    /// Removes a column and its value from every row
+    pub fn drop_column(&mut self, name: &str) -> Result<()> {
+        let index = self.expect_column_index(name)?;
+        if self.columns.len() == 1 {
+            return Err(KirinError::Unsupported(format!("dropping '{name}', the only column of a table")));
+        }
+
+        let ids: Vec<RowId> = self.iter_with_ids().map(|(id, _)| id).collect();
+        self.rewrite_rows(&ids, |values| {
+            values.remove(index);
+        });
+
+        self.columns.remove(index);
+        Ok(())
+    }
+
+    pub fn rename_column(&mut self, name: &str, new_name: impl Into<String>) -> Result<()> {
+        let index = self.expect_column_index(name)?;
+        let new_name = new_name.into();
+
+        if self.column_index(&new_name).is_some_and(|other| other != index) {
+            return Err(KirinError::ColumnExists(new_name));
+        }
+
+        self.columns[index].name = new_name;
+        Ok(())
+    }
+
+    /// Applies `f` to the values of each given row and stores the result
+    fn rewrite_rows(&mut self, ids: &[RowId], mut f: impl FnMut(&mut Vec<Value>)) {
+        for &id in ids {
+            let Some(row) = self.storage.get(id) else {
+                continue;
+            };
+
+            let mut row = row.clone();
+            f(&mut row.values);
+            self.storage.update(id, row);
+        }
+    }
+}
+
+/// Converts a value to the type of the column it is stored in, failing if
+/// the types differ and the value cannot be converted exactly. Numbers stored
+/// in a DECIMAL column are rounded to its scale, as CAST does, and decimals
+/// stored in a FLOAT column become the nearest float, as 0.1 has no exact one
+fn coerce(column: &Column, value: Value, time_zone: FixedOffset) -> Result<Value> {
+    if value.is_null() {
+        return match column.nullable {
+            true => Ok(value),
+            false => Err(KirinError::NotNull(column.name.clone())),
+        };
+    }
+
+    let actual = ValueType::from(&value);
+    let converted = match (&value, column.col_type) {
+        _ if actual == column.col_type => return Ok(value),
+        (Value::Int(int), ValueType::Float) => exact_float(*int).map(Value::Float),
+        (Value::Float(float), ValueType::Int) => exact_int(*float).map(Value::Int),
+        (Value::Text(_) | Value::Date(_), ValueType::DateTimeTz) => value.cast_in_zone(column.col_type, time_zone).ok(),
+        (Value::Text(_), ValueType::Date | ValueType::Time | ValueType::DateTime | ValueType::Interval)
+        | (Value::Date(_) | Value::DateTimeTz(_), ValueType::DateTime)
+        | (Value::DateTime(_), ValueType::DateTimeTz) => value.cast_to(column.col_type).ok(),
+        (Value::Int(_) | Value::Float(_) | Value::Decimal(_), ValueType::Decimal { .. }) => value.cast_to(column.col_type).ok(),
+        (Value::Decimal(decimal), ValueType::Int) => decimal.to_i64().filter(|_| decimal.is_integer()).map(Value::Int),
+        (Value::Decimal(decimal), ValueType::Float) => Some(decimal::to_float(decimal)).filter(|float| float.is_finite()).map(Value::Float),
+        _ => return Err(KirinError::TypeMismatch { column: column.name.clone(), expected: column.col_type, actual }),
+    };
+
+    converted.ok_or_else(|| KirinError::InvalidConversion {
+        column: column.name.clone(),
+        value: value.to_string(),
+        expected: column.col_type,
+    })
 }
@@ -1,30 +1,393 @@
+use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
 
+use crate::decimal;
+use crate::interval::Interval;
+use crate::error::Result;
+use crate::{KirinError, ValueType};
 
-#[derive(Debug, Clone, PartialEq)]
+/// A single SQL value.
+///
+/// Equality, hashing and ordering all follow [`Value::total_cmp`], so values
+/// can be used as map keys: `1` equals `1.0` and `1.00`, every NaN equals every
+/// other NaN, and `-0.0` equals `0.0`
+#[derive(Debug, Clone)]
 pub enum Value {
+    // Missing or unknown value
+    Null,
+
     // Text data types
     Text(String),
+
+    // Binary data types, shared so copies of a row do not copy the bytes
+    Blob(Arc<[u8]>),
     
     // Numeric data types
     Bool(bool),
     Int(i64),
     Float(f64),
+    Decimal(BigDecimal),
 
     // Date & Time data types
+    Date(NaiveDate),
+    Time(NaiveTime),
     DateTime(DateTime<Utc>),
+    /// An instant, shown in the time zone it was read or converted in
+    DateTimeTz(DateTime<FixedOffset>),
+    Interval(Interval),
 }
 
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Value::Null => write!(f, "NULL"),
             Value::Text(str) => write!(f, "{str}"),
+            Value::Blob(bytes) => write!(f, "X'{}'", to_hex(bytes)),
             Value::Bool(bool) => write!(f, "{bool}"),
             Value::Int(int) => write!(f, "{int}"),
             Value::Float(float) => write!(f, "{float}"),
+            Value::Decimal(decimal) => write!(f, "{}", decimal.to_plain_string()),
+            Value::Date(date) => write!(f, "{date}"),
+            Value::Time(time) => write!(f, "{time}"),
             Value::DateTime(datetime) => write!(f, "{}", datetime.to_rfc3339()),
+            Value::DateTimeTz(datetime) => write!(f, "{}", datetime.to_rfc3339()),
+            Value::Interval(interval) => write!(f, "{interval}"),
+        }
+    }
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Converts the value to `target`, as `CAST(value AS target)` does.
+    ///
+    /// NULL casts to NULL of any type and every value casts to its own type.
+    /// Otherwise, by source type:
+    ///
+    /// | from \ to   | Text           | Blob  | Int     | Float   | Decimal | Bool                 |
+    /// |-------------|----------------|-------|---------|---------|---------|----------------------|
+    /// | Text        | -              | UTF-8 | parsed  | parsed  | parsed  | parsed               |
+    /// | Blob        | UTF-8          | -     | error   | error   | error   | error                |
+    /// | Int         | decimal        | error | -       | nearest | exact   | `0` false, else true |
+    /// | Float       | decimal        | error | rounded | -       | rounded | error                |
+    /// | Decimal     | decimal        | error | rounded | nearest | rounded | error                |
+    /// | Bool        | `true`/`false` | error | `1`/`0` | error   | error   | -                    |
+    /// | Date & Time | ISO 8601       | error | error   | error   | error   | error                |
+    ///
+    /// and to the date and time types:
+    ///
+    /// | from \ to  | Date         | Time         | DateTime           | DateTimeTz         | Interval       |
+    /// |------------|--------------|--------------|--------------------|--------------------|----------------|
+    /// | Text       | parsed       | parsed       | [`parse_datetime`] | parsed in zone     | parsed         |
+    /// | Date       | -            | error        | midnight UTC       | midnight in zone   | error          |
+    /// | Time       | error        | -            | error              | error              | since midnight |
+    /// | DateTime   | UTC date     | UTC time     | -                  | same instant       | error          |
+    /// | DateTimeTz | date in zone | time in zone | same instant       | -                  | error          |
+    /// | Interval   | error        | error        | error              | error              | -              |
+    ///
+    /// Every other type fails to convert to them. Dates read as `YYYY-MM-DD`,
+    /// times as `hh:mm[:ss[.ffffff]]` and intervals as in [`Interval::parse`].
+    ///
+    /// Text and blobs convert through the UTF-8 encoding, failing for bytes
+    /// that are not valid UTF-8. Text is trimmed before it is parsed. It reads
+    /// as a Bool when it is `true`/`false`, `t`/`f`, `yes`/`no`, `on`/`off` or
+    /// `1`/`0`, in any case. Floats round half away from zero, and fail when
+    /// NaN, infinite or out of range. Decimals round half away from zero to
+    /// the target's scale, and fail with more digits before the point than it
+    /// allows; a float becomes the shortest decimal that reads back as it.
+    /// Pairs marked error fail for every value, as reported by
+    /// [`ValueType::can_cast_to`]; the others fail only for values that do
+    /// not convert. Both return [`KirinError::InvalidCast`].
+    ///
+    /// Timestamps with a time zone are read and shown in UTC; see
+    /// [`Value::cast_in_zone`] for other zones
+    pub fn cast_to(&self, target: ValueType) -> Result<Value> {
+        self.cast_in_zone(target, Utc.fix())
+    }
+
+    /// Converts the value to `target` as [`Value::cast_to`] does, reading
+    /// timestamps without an offset as local times in `zone` and showing
+    /// timestamps with a time zone in it
+    pub fn cast_in_zone(&self, target: ValueType, zone: FixedOffset) -> Result<Value> {
+        let from = ValueType::from(self);
+        if from == target || self.is_null() {
+            return Ok(self.clone());
+        }
+
+        let cast = match (self, target) {
+            (Value::Blob(bytes), ValueType::Text) => std::str::from_utf8(bytes).ok().map(|text| Value::Text(text.to_owned())),
+            (value, ValueType::Text) => Some(Value::Text(value.to_string())),
+            (Value::Text(text), ValueType::Blob) => Some(Value::Blob(text.as_bytes().into())),
+            (Value::Text(text), ValueType::Int) => text.trim().parse().ok().map(Value::Int),
+            (Value::Text(text), ValueType::Float) => text.trim().parse().ok().map(Value::Float),
+            (Value::Text(text), ValueType::Bool) => parse_bool(text).map(Value::Bool),
+            (Value::Text(text), ValueType::Date) => parse_date(text).map(Value::Date),
+            (Value::Text(text), ValueType::Time) => parse_time(text).map(Value::Time),
+            (Value::Text(text), ValueType::DateTime) => parse_datetime(text).map(Value::DateTime),
+            (Value::Text(text), ValueType::DateTimeTz) => parse_datetime_in(text, zone).map(Value::DateTimeTz),
+            (Value::Text(text), ValueType::Interval) => Interval::parse(text).map(Value::Interval),
+            (Value::Date(date), ValueType::DateTime) => Some(Value::DateTime(date.and_time(NaiveTime::MIN).and_utc())),
+            (Value::Date(date), ValueType::DateTimeTz) => zone.from_local_datetime(&date.and_time(NaiveTime::MIN)).single().map(Value::DateTimeTz),
+            (Value::Time(time), ValueType::Interval) => {
+                let micros = time.signed_duration_since(NaiveTime::MIN).num_microseconds();
+                micros.map(|micros| Value::Interval(Interval::new(0, 0, micros)))
+            }
+            (Value::DateTime(datetime), ValueType::Date) => Some(Value::Date(datetime.date_naive())),
+            (Value::DateTime(datetime), ValueType::Time) => Some(Value::Time(datetime.time())),
+            (Value::DateTime(datetime), ValueType::DateTimeTz) => Some(Value::DateTimeTz(datetime.with_timezone(&zone))),
+            (Value::DateTimeTz(datetime), ValueType::Date) => Some(Value::Date(datetime.with_timezone(&zone).date_naive())),
+            (Value::DateTimeTz(datetime), ValueType::Time) => Some(Value::Time(datetime.with_timezone(&zone).time())),
+            (Value::DateTimeTz(datetime), ValueType::DateTime) => Some(Value::DateTime(datetime.to_utc())),
+            (Value::Int(int), ValueType::Float) => Some(Value::Float(*int as f64)),
+            (Value::Int(int), ValueType::Bool) => Some(Value::Bool(*int != 0)),
+            (Value::Float(float), ValueType::Int) => exact_int(float.round()).map(Value::Int),
+            (Value::Bool(bool), ValueType::Int) => Some(Value::Int(*bool as i64)),
+            (Value::Decimal(decimal), ValueType::Int) => decimal::round(decimal, 0).to_i64().map(Value::Int),
+            (Value::Decimal(decimal), ValueType::Float) => Some(Value::Float(decimal::to_float(decimal))),
+            (value, ValueType::Decimal { precision, scale }) => {
+                let decimal = match value {
+                    Value::Text(text) => text.trim().parse().ok(),
+                    Value::Int(int) => Some(BigDecimal::from(*int)),
+                    Value::Float(float) => decimal::from_float(*float),
+                    Value::Decimal(decimal) => Some(decimal.clone()),
+                    _ => None,
+                };
+                decimal.and_then(|decimal| decimal::fit(&decimal, precision, scale)).map(Value::Decimal)
+            }
+            _ => None,
+        };
+
+        cast.ok_or_else(|| KirinError::InvalidCast { value: self.to_string(), from, to: target })
+    }
+
+    /// A total ordering over all values, used for sorting.
+    ///
+    /// Values of different types are ordered by type:
+    /// `Null < Bool < Int/Float/Decimal < Text < Blob < Date < DateTime/DateTimeTz < Time < Interval`.
+    /// Numbers compare by numeric value, so `1`, `1.0` and `1.00` are equal;
+    /// a decimal and a float compare as described in [`decimal::from_float`].
+    /// Floats follow IEEE order except that `-0.0` equals `0.0` and every NaN
+    /// is equal to every other NaN and greater than all numbers. Timestamps
+    /// with and without a time zone compare by the instant they stand for.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Int(l), Value::Int(r)) => l.cmp(r),
+            (Value::Int(l), Value::Float(r)) => cmp_int_float(*l, *r),
+            (Value::Float(l), Value::Int(r)) => cmp_int_float(*r, *l).reverse(),
+            (Value::Float(l), Value::Float(r)) => cmp_float(*l, *r),
+            (Value::Decimal(l), Value::Decimal(r)) => l.cmp(r),
+            (Value::Decimal(l), Value::Int(r)) => l.cmp(&BigDecimal::from(*r)),
+            (Value::Int(l), Value::Decimal(r)) => BigDecimal::from(*l).cmp(r),
+            (Value::Decimal(l), Value::Float(r)) => cmp_decimal_float(l, *r),
+            (Value::Float(l), Value::Decimal(r)) => cmp_decimal_float(r, *l).reverse(),
+            (Value::Text(l), Value::Text(r)) => l.cmp(r),
+            (Value::Blob(l), Value::Blob(r)) => l.cmp(r),
+            (Value::Bool(l), Value::Bool(r)) => l.cmp(r),
+            (Value::Date(l), Value::Date(r)) => l.cmp(r),
+            (Value::Time(l), Value::Time(r)) => l.cmp(r),
+            (Value::DateTime(l), Value::DateTime(r)) => l.cmp(r),
+            (Value::DateTimeTz(l), Value::DateTimeTz(r)) => l.cmp(r),
+            (Value::DateTime(l), Value::DateTimeTz(r)) => l.cmp(&r.to_utc()),
+            (Value::DateTimeTz(l), Value::DateTime(r)) => l.to_utc().cmp(r),
+            (Value::Interval(l), Value::Interval(r)) => l.cmp(r),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+
+    /// Position of the value's type in the cross-type ordering
+    fn type_rank(&self) -> u8 {
+        match self {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Int(_) | Value::Float(_) | Value::Decimal(_) => 2,
+            Value::Text(_) => 3,
+            Value::Blob(_) => 4,
+            Value::Date(_) => 5,
+            Value::DateTime(_) | Value::DateTimeTz(_) => 6,
+            Value::Time(_) => 7,
+            Value::Interval(_) => 8,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_rank().hash(state);
+        match self {
+            Value::Null => {}
+            Value::Bool(bool) => bool.hash(state),
+            Value::Int(int) => int.hash(state),
+            // Integral floats hash like the equal integer, which also covers -0.0
+            Value::Float(float) if float.fract() == 0.0 && (-I64_END..I64_END).contains(float) => (*float as i64).hash(state),
+            Value::Float(float) if float.is_nan() => f64::NAN.to_bits().hash(state),
+            Value::Float(float) => float.to_bits().hash(state),
+            // A decimal equal to an integer or a float hashes like it
+            Value::Decimal(decimal) => match decimal.to_i64() {
+                Some(int) if decimal.is_integer() => int.hash(state),
+                _ => match decimal::to_float(decimal) {
+                    float if decimal::from_float(float).as_ref() == Some(decimal) => float.to_bits().hash(state),
+                    _ => decimal.hash(state),
+                },
+            },
+            Value::Text(text) => text.hash(state),
+            Value::Blob(bytes) => bytes.hash(state),
+            Value::Date(date) => date.hash(state),
+            Value::Time(time) => time.hash(state),
+            // Equal instants hash alike whatever their time zone
+            Value::DateTime(datetime) => datetime.hash(state),
+            Value::DateTimeTz(datetime) => datetime.to_utc().hash(state),
+            Value::Interval(interval) => interval.hash(state),
         }
     }
+}
+
+/// Reads a timestamp written as RFC 3339, e.g. `2026-01-01T12:00:00+02:00`.
+///
+/// The `T` may be a space, the seconds may be left out, and without an
+/// offset the time is taken as UTC. A date alone stands for its midnight
+pub fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    parse_datetime_in(text, Utc.fix()).map(|datetime| datetime.to_utc())
+}
+
+/// Reads a timestamp as [`parse_datetime`] does, but taking a time without
+/// an offset as a local time in `zone`, and gives it in `zone`
+pub fn parse_datetime_in(text: &str, zone: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if let Ok(datetime) = text.parse::<DateTime<FixedOffset>>() {
+        return Some(datetime.with_timezone(&zone));
+    }
+
+    let naive = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| parse_date(text).map(|date| date.and_time(NaiveTime::MIN)))?;
+    zone.from_local_datetime(&naive).single()
+}
+
+/// Reads a date written as `YYYY-MM-DD`
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()
+}
+
+/// Reads a time of day written as `hh:mm[:ss[.ffffff]]`
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    let text = text.trim();
+    NaiveTime::parse_from_str(text, "%H:%M:%S%.f").or_else(|_| NaiveTime::parse_from_str(text, "%H:%M")).ok()
+}
+
+/// Reads a time zone written as `UTC`, or as an offset from it such as
+/// `+02:00`, `-0530` or `+2`
+pub fn parse_time_zone(text: &str) -> Option<FixedOffset> {
+    let text = text.trim();
+    if ["UTC", "GMT", "Z"].iter().any(|utc| text.eq_ignore_ascii_case(utc)) {
+        return Some(Utc.fix());
+    }
+
+    let (sign, offset) = match text.as_bytes().first()? {
+        b'+' => (1, &text[1..]),
+        b'-' => (-1, &text[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+    let hours: i32 = hours.parse().ok().filter(|hours| (0..24).contains(hours))?;
+    let minutes: i32 = minutes.parse().ok().filter(|minutes| (0..60).contains(minutes))?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Reads the usual spellings of a boolean, ignoring case
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "true" | "t" | "yes" | "on" | "1" => Some(true),
+        "false" | "f" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Writes bytes as hex digits, two to a byte, e.g. `DEADBEEF`
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
+/// Reads bytes written as hex digits in either case, two to a byte
+pub fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+}
+
+/// 2^63, the first float above every i64
+const I64_END: f64 = 9_223_372_036_854_775_808.0;
+
+/// The float equal to `int`, if there is one; above 2^53 not every integer has one
+pub(crate) fn exact_float(int: i64) -> Option<f64> {
+    let float = int as f64;
+    (float < I64_END && float as i64 == int).then_some(float)
+}
+
+/// The integer equal to `float`, if it is a whole number within the range of i64
+pub(crate) fn exact_int(float: f64) -> Option<i64> {
+    (float.fract() == 0.0 && (-I64_END..I64_END).contains(&float)).then_some(float as i64)
+}
+
+fn cmp_float(left: f64, right: f64) -> Ordering {
+    match (left.is_nan(), right.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        // Neither is NaN, and partial_cmp already treats -0.0 as equal to 0.0
+        (false, false) => left.partial_cmp(&right).expect("Non-NaN floats are ordered"),
+    }
+}
+
+fn cmp_decimal_float(decimal: &BigDecimal, float: f64) -> Ordering {
+    match decimal::from_float(float) {
+        Some(float) => decimal.cmp(&float),
+        None if float == f64::NEG_INFINITY => Ordering::Greater,
+        // NaN and infinity are greater than every number
+        None => Ordering::Less,
+    }
+}
+
+/// Compares an integer with a float exactly, without rounding the integer to f64
+fn cmp_int_float(int: i64, float: f64) -> Ordering {
+    if float.is_nan() || float >= I64_END {
+        return Ordering::Less;
+    }
+    if float < -I64_END {
+        return Ordering::Greater;
+    }
+
+    let whole = float.trunc();
+    int.cmp(&(whole as i64)).then_with(|| cmp_float(0.0, float - whole))
 }
\ No newline at end of file
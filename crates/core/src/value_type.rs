@@ -1,41 +1,93 @@
 use std::fmt::Display;
 
-use crate::Value;
+use crate::{decimal, Value};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ValueType {
+    // Type of the NULL literal, which fits any nullable column
+    Null,
+
     // Text data types
     Text,
+
+    // Binary data types
+    Blob,
     
     // Numeric data types
     Bool,
     Int,
     Float,
+    /// Exact decimal of at most `precision` digits, `scale` of them after the point
+    Decimal { precision: u32, scale: u32 },
 
     // Date & Time data types
+    Date,
+    Time,
+    /// A UTC instant
     DateTime,
+    /// An instant shown in the session's time zone
+    DateTimeTz,
+    Interval,
 }
 
 impl From<&Value> for ValueType {
     fn from(value: &Value) -> Self {
         match value {
+            Value::Null => ValueType::Null,
             Value::Text(_) => ValueType::Text,
+            Value::Blob(_) => ValueType::Blob,
             Value::Bool(_) => ValueType::Bool,
             Value::Int(_) => ValueType::Int,
             Value::Float(_) => ValueType::Float,
+            Value::Decimal(decimal) => {
+                let (precision, scale) = decimal::shape(decimal);
+                ValueType::Decimal { precision, scale }
+            }
+            Value::Date(_) => ValueType::Date,
+            Value::Time(_) => ValueType::Time,
             Value::DateTime(_) => ValueType::DateTime,
+            Value::DateTimeTz(_) => ValueType::DateTimeTz,
+            Value::Interval(_) => ValueType::Interval,
         }
     }
 }
 
+impl ValueType {
+    /// Whether CAST can convert values of this type to `target`, for at
+    /// least some values; see [`Value::cast_to`] for the full matrix
+    pub fn can_cast_to(self, target: ValueType) -> bool {
+        use ValueType::*;
+        matches!(
+            (self, target),
+            (Null, _)
+                | (Text, _)
+                | (Blob, Text | Blob)
+                | (Int, Text | Int | Float | Decimal { .. } | Bool)
+                | (Float | Decimal { .. }, Text | Int | Float | Decimal { .. })
+                | (Bool, Text | Int | Bool)
+                | (Date, Text | Date | DateTime | DateTimeTz)
+                | (Time, Text | Time | Interval)
+                | (DateTime | DateTimeTz, Text | Date | Time | DateTime | DateTimeTz)
+                | (Interval, Text | Interval)
+        )
+    }
+}
+
 impl Display for ValueType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match self {
+            ValueType::Decimal { precision, scale } => return write!(f, "Decimal({precision},{scale})"),
+            ValueType::Null => "Null",
             ValueType::Text => "Text",
+            ValueType::Blob => "Blob",
             ValueType::Bool => "Boolean",
             ValueType::Int => "Integer",
             ValueType::Float => "Float",
+            ValueType::Date => "Date",
+            ValueType::Time => "Time",
             ValueType::DateTime => "Date/Time",
+            ValueType::DateTimeTz => "Date/Time with time zone",
+            ValueType::Interval => "Interval",
         };
         f.write_str(text)
     }
@@ -1,27 +1,109 @@
+use chrono::{FixedOffset, Offset, Utc};
 use storage::MemoryStorage;
-use core::Table;
+use core::error::Result;
+use core::{Column, KirinError, Table};
 use std::collections::HashMap;
 
 pub struct Database {
-    tables: HashMap<String, Table<MemoryStorage>>
+    tables: HashMap<String, Table<MemoryStorage>>,
+    /// The session's time zone, in which timestamps with a time zone are read and shown
+    time_zone: FixedOffset,
 }
 
 impl Database {
 
     pub fn new() -> Self {
-        Self { tables: HashMap::new() }
+        Self { tables: HashMap::new(), time_zone: Utc.fix() }
     }
 
-    pub fn add_table(&mut self, name: impl Into<String>, table: Table<MemoryStorage>) {
+    pub fn time_zone(&self) -> FixedOffset {
+        self.time_zone
+    }
+
+    /// Changes the session's time zone, as `SET TIME ZONE` does, along with
+    /// the zone every table reads new values in
+    pub fn set_time_zone(&mut self, time_zone: FixedOffset) {
+        self.time_zone = time_zone;
+        for table in self.tables.values_mut() {
+            table.set_time_zone(time_zone);
+        }
+    }
+
+    /// Adds a table, which reads new values in the session's time zone
+    pub fn add_table(&mut self, name: impl Into<String>, mut table: Table<MemoryStorage>) {
+        table.set_time_zone(self.time_zone);
         self.tables.insert(name.into(), table);
     }
 
+    /// Creates an empty table backed by the database's storage backend
+    pub fn create_table(&mut self, name: impl Into<String>, columns: Vec<Column>) -> Result<()> {
+        let name = name.into();
+        if self.has_table(&name) {
+            return Err(KirinError::TableExists(name));
+        }
+
+        // Column names are case-insensitive, so reject names differing only in case
+        for (i, column) in columns.iter().enumerate() {
+            if columns[..i].iter().any(|other| other.name.eq_ignore_ascii_case(&column.name)) {
+                return Err(KirinError::ColumnExists(column.name.clone()));
+            }
+        }
+
+        self.add_table(name, Table::new(columns, MemoryStorage::new()));
+        Ok(())
+    }
+
+    /// Removes a table and all of its rows
+    pub fn drop_table(&mut self, name: &str) -> Result<()> {
+        let name = self.resolve_name(name).ok_or_else(|| KirinError::UnknownTable(name.to_string()))?;
+        self.tables.remove(&name);
+        Ok(())
+    }
+
+    pub fn rename_table(&mut self, name: &str, new_name: impl Into<String>) -> Result<()> {
+        let name = self.resolve_name(name).ok_or_else(|| KirinError::UnknownTable(name.to_string()))?;
+        let new_name = new_name.into();
+
+        if self.resolve_name(&new_name).is_some_and(|other| other != name) {
+            return Err(KirinError::TableExists(new_name));
+        }
+
+        let table = self.tables.remove(&name).expect("Resolved table exists");
+        self.tables.insert(new_name, table);
+        Ok(())
+    }
+
+    pub fn has_table(&self, name: &str) -> bool {
+        self.resolve_name(name).is_some()
+    }
+
+    /// Looks up a table by name, falling back to a case-insensitive match
     pub fn get_table(&mut self, name: impl Into<String>) -> Option<&mut Table<MemoryStorage>> {
-        self.tables.get_mut(&name.into())
+        let name = self.resolve_name(&name.into())?;
+        self.tables.get_mut(&name)
+    }
+
+    /// Read-only version of [`Database::get_table`]
+    pub fn table(&self, name: impl Into<String>) -> Option<&Table<MemoryStorage>> {
+        let name = self.resolve_name(&name.into())?;
+        self.tables.get(&name)
     }
 
     pub fn get_table_names(&self) -> impl Iterator<Item = &String>{
         self.tables.keys()
     }
 
-}
\ No newline at end of file
+    /// Finds the stored name of a table, preferring an exact match
+    fn resolve_name(&self, name: &str) -> Option<String> {
+        if self.tables.contains_key(name) {
+            return Some(name.to_string());
+        }
+        self.tables.keys().find(|key| key.eq_ignore_ascii_case(name)).cloned()
+    }
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self::new()
+    }
+}
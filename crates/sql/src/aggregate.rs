@@ -0,0 +1,243 @@
+use bigdecimal::BigDecimal;
+use core::error::Result;
+use core::{decimal, KirinError, Value, ValueType};
+use std::collections::BTreeMap;
+
+use crate::ast::{BinaryOp, ColumnRef, Expr, FunctionCall};
+use crate::eval::{binary, decimal_type, evaluate, infer_type, Scope, ScopeColumn};
+
+/// Digits a sum can gain over its arguments: adding up to `i64::MAX` values
+/// carries at most 19 digits
+const SUM_DIGITS: u32 = 19;
+
+/// Whether `name` is one of the aggregate functions
+pub fn is_aggregate(name: &str) -> bool {
+    matches!(name.to_ascii_uppercase().as_str(), "COUNT" | "SUM" | "AVG" | "MIN" | "MAX")
+}
+
+/// Whether an aggregate function is called anywhere inside `expr`
+pub fn contains_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::Function(call) if is_aggregate(&call.name) && call.over.is_none() => true,
+        _ => expr.children().into_iter().any(contains_aggregate),
+    }
+}
+
+/// Checks that `expr` only reads columns through GROUP BY keys or aggregate calls
+pub fn check_grouped(expr: &Expr, scope: &Scope, group_by: &[Expr]) -> Result<()> {
+    if group_by.contains(expr) {
+        return Ok(());
+    }
+
+    match expr {
+        Expr::Function(call) if is_aggregate(&call.name) && call.over.is_none() => Ok(()),
+
+        Expr::Column(column) => {
+            // Columns of an enclosing query are constant within a subquery
+            let index = scope.resolve(column)?;
+            if scope.columns[index].depth > 0 {
+                return Ok(());
+            }
+
+            let grouped = group_by.iter()
+                .any(|key| matches!(key, Expr::Column(key) if scope.resolve(key).ok() == Some(index)));
+
+            match grouped {
+                true => Ok(()),
+                false => Err(KirinError::InvalidAggregate(format!(
+                    "column '{column}' must appear in GROUP BY or be used in an aggregate function"
+                ))),
+            }
+        }
+
+        _ => expr.children().into_iter().try_for_each(|child| check_grouped(child, scope, group_by)),
+    }
+}
+
+/// The aggregate calls of a query, each computed once per group
+#[derive(Debug, Default)]
+pub struct Aggregates {
+    calls: Vec<FunctionCall>,
+}
+
+impl Aggregates {
+    /// Copies `expr`, replacing every aggregate call with a reference to the
+    /// column that will hold its result in the grouped scope
+    pub fn extract(&mut self, expr: &Expr) -> Result<Expr> {
+        let mut expr = expr.clone();
+        self.replace_calls(&mut expr)?;
+        Ok(expr)
+    }
+
+    fn replace_calls(&mut self, expr: &mut Expr) -> Result<()> {
+        if let Expr::Function(call) = expr && is_aggregate(&call.name) && call.over.is_none() {
+            if call.args.iter().any(contains_aggregate) {
+                return Err(KirinError::InvalidAggregate(format!("aggregate calls cannot be nested, as in {call}")));
+            }
+
+            let name = call.to_string();
+            if !self.calls.iter().any(|other| other.to_string() == name) {
+                self.calls.push(call.clone());
+            }
+            *expr = Expr::Column(ColumnRef { table: None, name });
+            return Ok(());
+        }
+
+        expr.children_mut().into_iter().try_for_each(|child| self.replace_calls(child))
+    }
+
+    /// Splits `rows` into groups sharing the same `group_by` values and computes
+    /// every aggregate for each group.
+    ///
+    /// The grouped scope has one column per aggregate followed by the source
+    /// columns, which hold the values of the group's first row. Without GROUP BY
+    /// all rows form a single group, even when there are none
+    pub fn group<'a>(&self, scope: &Scope<'a>, rows: Vec<Vec<Value>>, group_by: &[Expr]) -> Result<(Scope<'a>, Vec<Vec<Value>>)> {
+        let mut columns = Vec::with_capacity(self.calls.len() + scope.columns.len());
+        for call in &self.calls {
+            let col_type = result_type(call, scope)?;
+            columns.push(ScopeColumn { table: None, name: call.to_string(), col_type, hidden: false, depth: 0 });
+        }
+        columns.extend(scope.columns.iter().cloned());
+
+        for key in group_by {
+            infer_type(key, scope)?;
+        }
+
+        // Groups come out ordered by key
+        let mut groups: BTreeMap<Vec<Value>, Vec<Vec<Value>>> = BTreeMap::new();
+        if group_by.is_empty() {
+            groups.insert(Vec::new(), rows);
+        } else {
+            for row in rows {
+                let key = group_by.iter()
+                    .map(|expr| evaluate(expr, scope, &row))
+                    .collect::<Result<Vec<_>>>()?;
+                groups.entry(key).or_default().push(row);
+            }
+        }
+
+        let mut grouped = Vec::with_capacity(groups.len());
+        for rows in groups.into_values() {
+            let mut values = Vec::with_capacity(columns.len());
+            for call in &self.calls {
+                values.push(compute(call, scope, rows.iter().map(Vec::as_slice))?);
+            }
+            match rows.first() {
+                Some(first) => values.extend(first.iter().cloned()),
+                None => values.extend(scope.columns.iter().map(|_| Value::Null)),
+            }
+            grouped.push(values);
+        }
+
+        Ok((Scope { columns, context: scope.context }, grouped))
+    }
+}
+
+/// Works out the type of an aggregate, checking its arguments
+pub(crate) fn result_type(call: &FunctionCall, scope: &Scope) -> Result<ValueType> {
+    let name = call.name.to_ascii_uppercase();
+    if call.wildcard {
+        return match name.as_str() {
+            "COUNT" => Ok(ValueType::Int),
+            _ => Err(KirinError::InvalidAggregate(format!("{name}(*) is not allowed, only COUNT(*)"))),
+        };
+    }
+
+    let [arg] = call.args.as_slice() else {
+        return Err(KirinError::ArityMismatch { expected: 1, actual: call.args.len() });
+    };
+    let arg_type = infer_type(arg, scope)?;
+    let invalid = || KirinError::InvalidOperands { op: name.clone(), operands: vec![arg_type] };
+
+    match name.as_str() {
+        "COUNT" => Ok(ValueType::Int),
+        // Sums and averages of decimals stay exact
+        "SUM" => match arg_type {
+            ValueType::Int | ValueType::Float | ValueType::Null => Ok(arg_type),
+            ValueType::Decimal { precision, scale } => Ok(decimal_type(precision + SUM_DIGITS, scale)),
+            _ => Err(invalid()),
+        },
+        "AVG" => match arg_type {
+            ValueType::Int | ValueType::Float | ValueType::Null => Ok(ValueType::Float),
+            ValueType::Decimal { precision, scale } => {
+                let average_scale = scale.max(decimal::DIVISION_SCALE);
+                Ok(decimal_type(precision - scale + average_scale, average_scale))
+            }
+            _ => Err(invalid()),
+        },
+        _ => Ok(arg_type),
+    }
+}
+
+/// Computes an aggregate over the rows of one group or window frame; NULL
+/// arguments are ignored
+pub(crate) fn compute<'r>(call: &FunctionCall, scope: &Scope, rows: impl Iterator<Item = &'r [Value]>) -> Result<Value> {
+    if call.wildcard {
+        return Ok(Value::Int(rows.count() as i64));
+    }
+
+    let arg_type = infer_type(&call.args[0], scope)?;
+    let mut values = Vec::new();
+    for row in rows {
+        let value = evaluate(&call.args[0], scope, row)?;
+        if !value.is_null() {
+            values.push(value);
+        }
+    }
+
+    if call.distinct {
+        values.sort();
+        values.dedup();
+    }
+
+    // The argument's type, not the values seen, decides how SUM and AVG add
+    // up, so every value is brought to that type first
+    let name = call.name.to_ascii_uppercase();
+    match name.as_str() {
+        "COUNT" => Ok(Value::Int(values.len() as i64)),
+        "SUM" | "AVG" if values.is_empty() => Ok(Value::Null),
+        "SUM" => match arg_type {
+            ValueType::Decimal { .. } => Ok(Value::Decimal(decimal_total(&name, values)?)),
+            ValueType::Float => Ok(Value::Float(float_total(&name, &values)?)),
+            _ => sum(values),
+        },
+        "AVG" => {
+            let count = values.len();
+            match arg_type {
+                ValueType::Decimal { .. } => {
+                    binary(BinaryOp::Div, Value::Decimal(decimal_total(&name, values)?), Value::Int(count as i64))
+                }
+                _ => Ok(Value::Float(float_total(&name, &values)? / count as f64)),
+            }
+        }
+        "MIN" => Ok(values.into_iter().min().unwrap_or(Value::Null)),
+        _ => Ok(values.into_iter().max().unwrap_or(Value::Null)),
+    }
+}
+
+/// The exact sum of integers and decimals
+fn decimal_total(name: &str, values: Vec<Value>) -> Result<BigDecimal> {
+    values.into_iter().try_fold(BigDecimal::from(0), |total, value| match value {
+        Value::Int(int) => Ok(total + BigDecimal::from(int)),
+        Value::Decimal(decimal) => Ok(total + decimal),
+        value => Err(KirinError::InvalidOperands { op: name.to_string(), operands: vec![ValueType::from(&value)] }),
+    })
+}
+
+/// The sum of numbers as floats
+fn float_total(name: &str, values: &[Value]) -> Result<f64> {
+    values.iter().try_fold(0.0, |total, value| match value {
+        Value::Int(int) => Ok(total + *int as f64),
+        Value::Float(float) => Ok(total + float),
+        Value::Decimal(decimal) => Ok(total + decimal::to_float(decimal)),
+        value => Err(KirinError::InvalidOperands { op: name.to_string(), operands: vec![ValueType::from(value)] }),
+    })
+}
+
+fn sum(values: Vec<Value>) -> Result<Value> {
+    values.into_iter().try_fold(Value::Null, |total, value| match total {
+        Value::Null => Ok(value),
+        total => binary(BinaryOp::Add, total, value),
+    })
+}
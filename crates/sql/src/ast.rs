@@ -0,0 +1,735 @@
+use chrono::FixedOffset;
+use core::{Column, Value, ValueType};
+use std::fmt::Display;
+
+/// A single parsed SQL statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Select(Box<SelectStmt>),
+    /// `EXPLAIN <select>`, describing how the query is executed
+    Explain(Box<SelectStmt>),
+    Insert(InsertStmt),
+    Update(UpdateStmt),
+    Delete(DeleteStmt),
+    CreateTable(CreateTableStmt),
+    DropTable(DropTableStmt),
+    AlterTable(AlterTableStmt),
+    /// `SET TIME ZONE '<zone>'`, changing the session's time zone to UTC or a fixed offset from it
+    SetTimeZone(FixedOffset),
+    /// `SHOW TIME ZONE`
+    ShowTimeZone,
+}
+
+/// `[WITH ...] SELECT [DISTINCT [ON (...)]] <projection> [FROM <table>] [WHERE <selection>] [GROUP BY ...]
+/// [HAVING <having>] [UNION|INTERSECT|EXCEPT [ALL] SELECT ...] [ORDER BY ...] [LIMIT n [OFFSET m]]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStmt {
+    pub with: Option<With>,
+    pub distinct: Option<Distinct>,
+    pub projection: Vec<SelectItem>,
+    pub from: Option<TableExpr>,
+    pub selection: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    /// Queries combined with this one, applied left to right. ORDER BY,
+    /// LIMIT and OFFSET then apply to the combined rows
+    pub compound: Vec<Compound>,
+    pub order_by: Vec<OrderByExpr>,
+    pub limit: Option<Expr>,
+    pub offset: Option<Expr>,
+}
+
+/// Which rows SELECT DISTINCT treats as duplicates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distinct {
+    /// `DISTINCT`: rows with equal output values
+    Rows,
+    /// `DISTINCT ON (<expr>, ...)`: rows with equal values of the expressions,
+    /// keeping the first in ORDER BY order
+    On(Vec<Expr>),
+}
+
+/// `WITH [RECURSIVE] <cte>, ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct With {
+    pub recursive: bool,
+    pub ctes: Vec<Cte>,
+}
+
+/// A common table expression: `name [(column, ...)] AS (<query>)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cte {
+    pub name: String,
+    /// Renames the query's columns; empty to keep their names
+    pub columns: Vec<String>,
+    pub query: Box<SelectStmt>,
+}
+
+/// `<operator> [ALL] SELECT ...`, without its own WITH, ORDER BY or LIMIT
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compound {
+    pub operator: SetOperator,
+    /// Keeps duplicate rows
+    pub all: bool,
+    pub select: SelectStmt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperator {
+    /// Rows of either query
+    Union,
+    /// Rows of the left query that the right one also returns
+    Intersect,
+    /// Rows of the left query that the right one does not return
+    Except,
+}
+
+/// A sort key: `<expr> [ASC|DESC] [NULLS FIRST|LAST]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderByExpr {
+    pub expr: Expr,
+    pub descending: bool,
+    /// Explicit NULLS FIRST / NULLS LAST; by default NULLs sort as the smallest value
+    pub nulls_first: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectItem {
+    /// `*`
+    Wildcard,
+    /// `table.*`
+    QualifiedWildcard(String),
+    /// `expr [AS alias]`
+    Expr { expr: Expr, alias: Option<String> },
+}
+
+/// The source of rows in a FROM clause
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableExpr {
+    Table(TableRef),
+    Join(Box<Join>),
+    /// `(SELECT ...) [AS] alias`
+    Subquery { query: Box<SelectStmt>, alias: String },
+}
+
+/// A table named in a FROM clause: `<name> [[AS] alias]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRef {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// `<left> [kind] JOIN <right> [ON <expr> | USING (columns)]`, or `<left>, <right>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Join {
+    pub left: TableExpr,
+    pub right: TableExpr,
+    pub kind: JoinKind,
+    pub constraint: JoinConstraint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JoinConstraint {
+    /// `ON <expr>`
+    On(Expr),
+    /// `USING (column, ...)`
+    Using(Vec<String>),
+    /// CROSS JOIN and comma joins
+    None,
+}
+
+/// `INSERT INTO <table> [(columns)] VALUES (...), (...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertStmt {
+    pub table: String,
+    pub columns: Option<Vec<String>>,
+    pub rows: Vec<Vec<Expr>>,
+}
+
+/// `UPDATE <table> SET <column> = <expr>, ... [WHERE <selection>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateStmt {
+    pub table: String,
+    pub assignments: Vec<Assignment>,
+    pub selection: Option<Expr>,
+}
+
+/// `<column> = <expr>` inside UPDATE
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub column: String,
+    pub value: Expr,
+}
+
+/// `DELETE FROM <table> [WHERE <selection>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteStmt {
+    pub table: String,
+    pub selection: Option<Expr>,
+}
+
+/// `CREATE TABLE [IF NOT EXISTS] <table> (column type, ...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateTableStmt {
+    pub name: String,
+    pub if_not_exists: bool,
+    pub columns: Vec<ColumnDef>,
+}
+
+/// A column definition inside CREATE TABLE: `<name> <type> [NOT NULL]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDef {
+    pub name: String,
+    pub data_type: ValueType,
+    pub nullable: bool,
+}
+
+impl From<ColumnDef> for Column {
+    fn from(def: ColumnDef) -> Self {
+        Column { name: def.name, col_type: def.data_type, nullable: def.nullable }
+    }
+}
+
+/// `DROP TABLE [IF EXISTS] <table>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropTableStmt {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+/// `ALTER TABLE <table> <operation>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlterTableStmt {
+    pub name: String,
+    pub operation: AlterTableOp,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterTableOp {
+    /// `RENAME TO <new_name>`
+    RenameTable { new_name: String },
+    /// `ADD [COLUMN] <column> <type> [DEFAULT <expr>]`
+    AddColumn { column: ColumnDef, default: Option<Expr> },
+    /// `DROP [COLUMN] <column>`
+    DropColumn { name: String },
+    /// `RENAME [COLUMN] <column> TO <new_name>`
+    RenameColumn { name: String, new_name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Column(ColumnRef),
+    Unary { op: UnaryOp, expr: Box<Expr> },
+    Binary { left: Box<Expr>, op: BinaryOp, right: Box<Expr> },
+    /// `expr IS [NOT] NULL`
+    IsNull { expr: Box<Expr>, negated: bool },
+    Function(FunctionCall),
+    /// A parenthesised expression, kept so it can be displayed as written
+    Nested(Box<Expr>),
+    /// `expr [NOT] IN (item, ...)`
+    InList { expr: Box<Expr>, list: Vec<Expr>, negated: bool },
+    /// `expr [NOT] IN (SELECT ...)`
+    InSubquery { expr: Box<Expr>, subquery: Box<SelectStmt>, negated: bool },
+    /// `EXISTS (SELECT ...)`
+    Exists(Box<SelectStmt>),
+    /// `(SELECT ...)` used as a single value
+    Subquery(Box<SelectStmt>),
+    /// `expr [NOT] LIKE|ILIKE|GLOB|REGEXP pattern [ESCAPE escape]`
+    Match { expr: Box<Expr>, op: MatchOp, pattern: Box<Expr>, escape: Option<Box<Expr>>, negated: bool },
+    /// `EXTRACT(field FROM expr)`, reading a part such as YEAR of a timestamp
+    Extract { field: String, expr: Box<Expr> },
+    /// `CAST(expr AS type)` or `expr::type`, converting a value to another type
+    Cast { expr: Box<Expr>, data_type: ValueType },
+    /// `CASE [operand] WHEN <condition> THEN <result> ... [ELSE <result>] END`.
+    /// With an operand each WHEN holds a value compared to it instead of a condition
+    Case { operand: Option<Box<Expr>>, branches: Vec<(Expr, Expr)>, else_result: Option<Box<Expr>> },
+}
+
+/// How the pattern of an [`Expr::Match`] is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchOp {
+    /// `%` matches any text and `_` any one character
+    Like,
+    /// LIKE ignoring case
+    ILike,
+    /// Shell wildcards: `*`, `?` and `[...]`
+    Glob,
+    /// A regular expression, found anywhere in the text; also written `~`
+    Regexp,
+}
+
+/// `name(arg, ...)`, `name(DISTINCT arg, ...)` or `name(*)`, optionally
+/// followed by `OVER (...)` to call it as a window function
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCall {
+    pub name: String,
+    pub args: Vec<Expr>,
+    /// Only distinct argument values are aggregated
+    pub distinct: bool,
+    /// Called as `name(*)`, e.g. `COUNT(*)`
+    pub wildcard: bool,
+    pub over: Option<Box<WindowSpec>>,
+}
+
+/// `OVER ([PARTITION BY ...] [ORDER BY ...] [<frame>])`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowSpec {
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    pub frame: Option<WindowFrame>,
+}
+
+/// `ROWS|RANGE BETWEEN <start> AND <end>`, or `ROWS|RANGE <start>` ending at the current row
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowFrame {
+    pub units: FrameUnits,
+    pub start: FrameBound,
+    pub end: FrameBound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameUnits {
+    /// Offsets count rows
+    Rows,
+    /// Offsets are distances from the current row's ORDER BY value, and the
+    /// current row includes its peers
+    Range,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(Box<Expr>),
+    CurrentRow,
+    Following(Box<Expr>),
+    UnboundedFollowing,
+}
+
+/// A possibly qualified column reference, e.g. `name` or `users.name`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnRef {
+    pub table: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    // Logical
+    And,
+    Or,
+
+    // Comparison
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+
+    // Arithmetic
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+
+    // Text
+    Concat,
+}
+
+impl Expr {
+    /// The expressions directly nested inside this one, including the
+    /// PARTITION BY and ORDER BY keys of a window function call.
+    ///
+    /// Subqueries are separate queries, so their expressions are not included
+    pub fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Literal(_) | Expr::Column(_) | Expr::Exists(_) | Expr::Subquery(_) => Vec::new(),
+            Expr::Unary { expr, .. } | Expr::IsNull { expr, .. } | Expr::Nested(expr) => vec![expr],
+            Expr::InSubquery { expr, .. } | Expr::Extract { expr, .. } | Expr::Cast { expr, .. } => vec![expr],
+            Expr::Binary { left, right, .. } => vec![left, right],
+            Expr::Match { expr, pattern, escape, .. } => [&**expr, &**pattern].into_iter().chain(escape.as_deref()).collect(),
+            Expr::Function(call) => {
+                let window = call.over.iter().flat_map(|over| over.partition_by.iter().chain(over.order_by.iter().map(|key| &key.expr)));
+                call.args.iter().chain(window).collect()
+            }
+            Expr::InList { expr, list, .. } => std::iter::once(&**expr).chain(list).collect(),
+            Expr::Case { operand, branches, else_result } => operand.as_deref().into_iter()
+                .chain(branches.iter().flat_map(|(when, then)| [when, then]))
+                .chain(else_result.as_deref())
+                .collect(),
+        }
+    }
+
+    /// Mutable version of [`Expr::children`]
+    pub fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Expr::Literal(_) | Expr::Column(_) | Expr::Exists(_) | Expr::Subquery(_) => Vec::new(),
+            Expr::Unary { expr, .. } | Expr::IsNull { expr, .. } | Expr::Nested(expr) => vec![expr],
+            Expr::InSubquery { expr, .. } | Expr::Extract { expr, .. } | Expr::Cast { expr, .. } => vec![expr],
+            Expr::Binary { left, right, .. } => vec![left, right],
+            Expr::Match { expr, pattern, escape, .. } => [&mut **expr, &mut **pattern].into_iter().chain(escape.as_deref_mut()).collect(),
+            Expr::Function(call) => {
+                let window = call.over.iter_mut().flat_map(|over| over.partition_by.iter_mut().chain(over.order_by.iter_mut().map(|key| &mut key.expr)));
+                call.args.iter_mut().chain(window).collect()
+            }
+            Expr::InList { expr, list, .. } => std::iter::once(&mut **expr).chain(list).collect(),
+            Expr::Case { operand, branches, else_result } => operand.as_deref_mut().into_iter()
+                .chain(branches.iter_mut().flat_map(|(when, then)| [when, then]))
+                .chain(else_result.as_deref_mut())
+                .collect(),
+        }
+    }
+}
+
+/// Displays items separated by commas, as in a select list
+pub(crate) fn comma_separated<T: Display>(items: &[T]) -> String {
+    items.iter().map(T::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Renders the query back to SQL, used when displaying subqueries
+impl Display for SelectStmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(with) = &self.with {
+            write!(f, "{with} ")?;
+        }
+        match &self.distinct {
+            Some(Distinct::Rows) => write!(f, "SELECT DISTINCT ")?,
+            Some(Distinct::On(exprs)) => write!(f, "SELECT DISTINCT ON ({}) ", comma_separated(exprs))?,
+            None => write!(f, "SELECT ")?,
+        }
+        write!(f, "{}", comma_separated(&self.projection))?;
+        if let Some(from) = &self.from {
+            write!(f, " FROM {from}")?;
+        }
+        if let Some(selection) = &self.selection {
+            write!(f, " WHERE {selection}")?;
+        }
+        if !self.group_by.is_empty() {
+            write!(f, " GROUP BY {}", comma_separated(&self.group_by))?;
+        }
+        if let Some(having) = &self.having {
+            write!(f, " HAVING {having}")?;
+        }
+        for compound in &self.compound {
+            write!(f, " {compound}")?;
+        }
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY {}", comma_separated(&self.order_by))?;
+        }
+        if let Some(limit) = &self.limit {
+            write!(f, " LIMIT {limit}")?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, " OFFSET {offset}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for With {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.recursive {
+            true => write!(f, "WITH RECURSIVE {}", comma_separated(&self.ctes)),
+            false => write!(f, "WITH {}", comma_separated(&self.ctes)),
+        }
+    }
+}
+
+impl Display for Cte {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.columns.is_empty() {
+            true => write!(f, "{} AS ({})", self.name, self.query),
+            false => write!(f, "{} ({}) AS ({})", self.name, self.columns.join(", "), self.query),
+        }
+    }
+}
+
+impl Display for Compound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.all {
+            true => write!(f, "{} ALL {}", self.operator, self.select),
+            false => write!(f, "{} {}", self.operator, self.select),
+        }
+    }
+}
+
+impl Display for SetOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            SetOperator::Union => "UNION",
+            SetOperator::Intersect => "INTERSECT",
+            SetOperator::Except => "EXCEPT",
+        };
+        f.write_str(text)
+    }
+}
+
+impl Display for TableExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableExpr::Table(table) => write!(f, "{table}"),
+            TableExpr::Join(join) => match join.kind {
+                JoinKind::Cross => write!(f, "{} CROSS JOIN {}", join.left, join.right),
+                kind => write!(f, "{} {kind} JOIN {} {}", join.left, join.right, join.constraint),
+            },
+            TableExpr::Subquery { query, alias } => write!(f, "({query}) AS {alias}"),
+        }
+    }
+}
+
+impl Display for SelectItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectItem::Wildcard => write!(f, "*"),
+            SelectItem::QualifiedWildcard(table) => write!(f, "{table}.*"),
+            SelectItem::Expr { expr, alias: Some(alias) } => write!(f, "{expr} AS {alias}"),
+            SelectItem::Expr { expr, alias: None } => write!(f, "{expr}"),
+        }
+    }
+}
+
+impl Display for OrderByExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expr)?;
+        if self.descending {
+            write!(f, " DESC")?;
+        }
+        match self.nulls_first {
+            Some(true) => write!(f, " NULLS FIRST"),
+            Some(false) => write!(f, " NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for TableRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.alias {
+            Some(alias) => write!(f, "{} AS {alias}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl Display for JoinKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            JoinKind::Inner => "INNER",
+            JoinKind::Left => "LEFT",
+            JoinKind::Right => "RIGHT",
+            JoinKind::Full => "FULL",
+            JoinKind::Cross => "CROSS",
+        };
+        f.write_str(text)
+    }
+}
+
+impl Display for JoinConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinConstraint::On(expr) => write!(f, "ON {expr}"),
+            JoinConstraint::Using(columns) => write!(f, "USING ({})", columns.join(", ")),
+            JoinConstraint::None => Ok(()),
+        }
+    }
+}
+
+impl Display for ColumnRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.table {
+            Some(table) => write!(f, "{table}.{}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl Display for FunctionCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let args = match self.wildcard {
+            true => String::from("*"),
+            false => self.args.iter().map(Expr::to_string).collect::<Vec<_>>().join(", "),
+        };
+        let distinct = if self.distinct { "DISTINCT " } else { "" };
+        write!(f, "{}({distinct}{args})", self.name.to_ascii_uppercase())?;
+        if let Some(over) = &self.over {
+            write!(f, " OVER ({over})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for WindowSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut clauses = Vec::new();
+        if !self.partition_by.is_empty() {
+            clauses.push(format!("PARTITION BY {}", comma_separated(&self.partition_by)));
+        }
+        if !self.order_by.is_empty() {
+            clauses.push(format!("ORDER BY {}", comma_separated(&self.order_by)));
+        }
+        if let Some(frame) = &self.frame {
+            clauses.push(frame.to_string());
+        }
+        write!(f, "{}", clauses.join(" "))
+    }
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let units = match self.units {
+            FrameUnits::Rows => "ROWS",
+            FrameUnits::Range => "RANGE",
+        };
+        write!(f, "{units} BETWEEN {} AND {}", self.start, self.end)
+    }
+}
+
+impl Display for FrameBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(offset) => write!(f, "{offset} PRECEDING"),
+            FrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            FrameBound::Following(offset) => write!(f, "{offset} FOLLOWING"),
+            FrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+impl Display for MatchOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            MatchOp::Like => "LIKE",
+            MatchOp::ILike => "ILIKE",
+            MatchOp::Glob => "GLOB",
+            MatchOp::Regexp => "REGEXP",
+        };
+        f.write_str(text)
+    }
+}
+
+impl Display for UnaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            UnaryOp::Plus => "+",
+            UnaryOp::Minus => "-",
+            UnaryOp::Not => "NOT",
+        };
+        f.write_str(text)
+    }
+}
+
+impl Display for BinaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            BinaryOp::And => "AND",
+            BinaryOp::Or => "OR",
+            BinaryOp::Eq => "=",
+            BinaryOp::NotEq => "<>",
+            BinaryOp::Lt => "<",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+            BinaryOp::Concat => "||",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Renders the expression back to SQL, used for output column headers
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Literal(Value::Text(str)) => write!(f, "'{}'", str.replace('\'', "''")),
+            Expr::Literal(Value::Date(date)) => write!(f, "DATE '{date}'"),
+            Expr::Literal(Value::Time(time)) => write!(f, "TIME '{time}'"),
+            Expr::Literal(Value::DateTime(datetime)) => write!(f, "TIMESTAMP '{}'", datetime.to_rfc3339()),
+            Expr::Literal(Value::DateTimeTz(datetime)) => write!(f, "TIMESTAMP WITH TIME ZONE '{}'", datetime.to_rfc3339()),
+            Expr::Literal(Value::Interval(interval)) => write!(f, "INTERVAL '{interval}'"),
+            Expr::Literal(value) => write!(f, "{value}"),
+            Expr::Column(column) => write!(f, "{column}"),
+            Expr::Unary { op: UnaryOp::Not, expr } => write!(f, "NOT {expr}"),
+            Expr::Unary { op, expr } => write!(f, "{op}{expr}"),
+            Expr::Binary { left, op, right } => write!(f, "{left} {op} {right}"),
+            Expr::IsNull { expr, negated: false } => write!(f, "{expr} IS NULL"),
+            Expr::IsNull { expr, negated: true } => write!(f, "{expr} IS NOT NULL"),
+            Expr::Function(call) => write!(f, "{call}"),
+            Expr::Nested(expr) => write!(f, "({expr})"),
+            Expr::InList { expr, list, negated } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{expr} {not}IN ({})", comma_separated(list))
+            }
+            Expr::InSubquery { expr, subquery, negated } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{expr} {not}IN ({subquery})")
+            }
+            Expr::Exists(subquery) => write!(f, "EXISTS ({subquery})"),
+            Expr::Subquery(subquery) => write!(f, "({subquery})"),
+            Expr::Match { expr, op, pattern, escape, negated } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{expr} {not}{op} {pattern}")?;
+                match escape {
+                    Some(escape) => write!(f, " ESCAPE {escape}"),
+                    None => Ok(()),
+                }
+            }
+            Expr::Extract { field, expr } => write!(f, "EXTRACT({field} FROM {expr})"),
+            Expr::Cast { expr, data_type } => write!(f, "CAST({expr} AS {})", type_name(*data_type)),
+            Expr::Case { operand, branches, else_result } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {operand}")?;
+                }
+                for (when, then) in branches {
+                    write!(f, " WHEN {when} THEN {then}")?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {else_result}")?;
+                }
+                write!(f, " END")
+            }
+        }
+    }
+}
+
+/// The SQL name of a type, as accepted in column definitions and CAST
+fn type_name(data_type: ValueType) -> String {
+    let name = match data_type {
+        ValueType::Decimal { precision, scale } => return format!("DECIMAL({precision}, {scale})"),
+        ValueType::Null => "NULL",
+        ValueType::Text => "TEXT",
+        ValueType::Blob => "BLOB",
+        ValueType::Bool => "BOOLEAN",
+        ValueType::Int => "INTEGER",
+        ValueType::Float => "FLOAT",
+        ValueType::Date => "DATE",
+        ValueType::Time => "TIME",
+        ValueType::DateTime => "TIMESTAMP",
+        ValueType::DateTimeTz => "TIMESTAMP WITH TIME ZONE",
+        ValueType::Interval => "INTERVAL",
+    };
+    name.to_string()
+}
@@ -0,0 +1,321 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+use core::error::Result;
+use core::interval::MICROS_PER_DAY;
+use core::{decimal, Interval, KirinError, Value, ValueType};
+use std::fmt::Display;
+
+use crate::ast::BinaryOp;
+
+/// A part of a timestamp, named in EXTRACT, DATE_TRUNC, DATE_ADD and DATE_DIFF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Year,
+    Quarter,
+    Month,
+    /// ISO 8601 week, starting on Monday
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    /// Day of the week, from 0 for Sunday to 6 for Saturday
+    DayOfWeek,
+    /// Day of the year, from 1
+    DayOfYear,
+    /// Seconds since 1970-01-01 00:00:00 UTC
+    Epoch,
+}
+
+impl DateField {
+    /// Reads a field name, ignoring case; units may also be written in the plural, e.g. `days`
+    pub fn parse(name: &str) -> Result<Self> {
+        let upper = name.trim().to_ascii_uppercase();
+        let singular = upper.strip_suffix('S').unwrap_or(&upper);
+
+        let field = match (upper.as_str(), singular) {
+            ("DOW", _) => DateField::DayOfWeek,
+            ("DOY", _) => DateField::DayOfYear,
+            ("EPOCH", _) => DateField::Epoch,
+            (_, "YEAR") => DateField::Year,
+            (_, "QUARTER") => DateField::Quarter,
+            (_, "MONTH") => DateField::Month,
+            (_, "WEEK") => DateField::Week,
+            (_, "DAY") => DateField::Day,
+            (_, "HOUR") => DateField::Hour,
+            (_, "MINUTE") => DateField::Minute,
+            (_, "SECOND") => DateField::Second,
+            _ => return Err(KirinError::InvalidArgument(format!("unknown date field '{name}'"))),
+        };
+        Ok(field)
+    }
+
+    /// The length of the unit in seconds, for units of fixed length
+    fn seconds(self) -> Option<i64> {
+        match self {
+            DateField::Week => Some(7 * 86_400),
+            DateField::Day => Some(86_400),
+            DateField::Hour => Some(3_600),
+            DateField::Minute => Some(60),
+            DateField::Second => Some(1),
+            _ => None,
+        }
+    }
+
+    /// The length of the unit in months, for calendar units
+    fn months(self) -> Option<u32> {
+        match self {
+            DateField::Year => Some(12),
+            DateField::Quarter => Some(3),
+            DateField::Month => Some(1),
+            _ => None,
+        }
+    }
+
+    fn not_a_unit(self, operation: &str) -> KirinError {
+        KirinError::InvalidArgument(format!("{operation} does not accept the field {self:?}"))
+    }
+}
+
+/// `EXTRACT(field FROM datetime)`, with the fields as seen in the timestamp's time zone
+pub fn extract<Tz: TimeZone>(field: DateField, datetime: &DateTime<Tz>) -> i64 {
+    match field {
+        DateField::Year => datetime.year() as i64,
+        DateField::Quarter => (datetime.month0() / 3 + 1) as i64,
+        DateField::Month => datetime.month() as i64,
+        DateField::Week => datetime.iso_week().week() as i64,
+        DateField::Day => datetime.day() as i64,
+        DateField::Hour => datetime.hour() as i64,
+        DateField::Minute => datetime.minute() as i64,
+        DateField::Second => datetime.second() as i64,
+        DateField::DayOfWeek => datetime.weekday().num_days_from_sunday() as i64,
+        DateField::DayOfYear => datetime.ordinal() as i64,
+        DateField::Epoch => datetime.timestamp(),
+    }
+}
+
+/// `EXTRACT(field FROM time)`, where the epoch counts seconds since midnight
+pub fn extract_time(field: DateField, time: &NaiveTime) -> Result<i64> {
+    match field {
+        DateField::Hour => Ok(time.hour() as i64),
+        DateField::Minute => Ok(time.minute() as i64),
+        DateField::Second => Ok(time.second() as i64),
+        DateField::Epoch => Ok(time.num_seconds_from_midnight() as i64),
+        field => Err(field.not_a_unit("EXTRACT from TIME")),
+    }
+}
+
+/// `EXTRACT(field FROM interval)`: one part of the interval, e.g. 2 for the
+/// hours of `1 day 02:30:00`, or its whole length in seconds for the epoch
+pub fn extract_interval(field: DateField, interval: &Interval) -> Result<i64> {
+    let micros = interval.micros;
+    match field {
+        DateField::Year => Ok(interval.months as i64 / 12),
+        DateField::Month => Ok(interval.months as i64 % 12),
+        DateField::Day => Ok(interval.days as i64),
+        DateField::Hour => Ok(micros / 3_600_000_000),
+        DateField::Minute => Ok(micros / 60_000_000 % 60),
+        DateField::Second => Ok(micros / 1_000_000 % 60),
+        DateField::Epoch => i64::try_from(interval.length() / 1_000_000).map_err(|_| KirinError::Overflow),
+        field => Err(field.not_a_unit("EXTRACT from INTERVAL")),
+    }
+}
+
+/// The type of `left op right` when a date, time, timestamp or interval is
+/// involved, or None when the operator does not combine them
+pub fn arithmetic_type(op: BinaryOp, left: ValueType, right: ValueType) -> Option<ValueType> {
+    use ValueType::*;
+    let is_number = |value_type| matches!(value_type, Int | Float | Decimal { .. });
+    let is_temporal = |value_type| matches!(value_type, Date | Time | DateTime | DateTimeTz | Interval);
+
+    match (op, left, right) {
+        (_, Null, other) | (_, other, Null) if is_temporal(other) => Some(other),
+        (BinaryOp::Add, Date, Int) | (BinaryOp::Add, Int, Date) | (BinaryOp::Sub, Date, Int) => Some(Date),
+        (BinaryOp::Sub, Date, Date) => Some(Int),
+        (BinaryOp::Add, Date, Time | Interval) | (BinaryOp::Add, Time | Interval, Date) | (BinaryOp::Sub, Date, Interval) => Some(DateTime),
+        (BinaryOp::Add, timestamp @ (DateTime | DateTimeTz), Interval)
+        | (BinaryOp::Add, Interval, timestamp @ (DateTime | DateTimeTz))
+        | (BinaryOp::Sub, timestamp @ (DateTime | DateTimeTz), Interval) => Some(timestamp),
+        (BinaryOp::Sub, DateTime | DateTimeTz, DateTime | DateTimeTz) => Some(Interval),
+        (BinaryOp::Add, Time, Interval) | (BinaryOp::Add, Interval, Time) | (BinaryOp::Sub, Time, Interval) => Some(Time),
+        (BinaryOp::Sub, Time, Time) | (BinaryOp::Add | BinaryOp::Sub, Interval, Interval) => Some(Interval),
+        (BinaryOp::Mul, Interval, number) | (BinaryOp::Mul, number, Interval) | (BinaryOp::Div, Interval, number) if is_number(number) => Some(Interval),
+        _ => None,
+    }
+}
+
+/// `left op right` for the operands [`arithmetic_type`] accepts, or None for
+/// any others. Adding days or an interval to a date gives a timestamp at its
+/// midnight, subtracting dates counts the days between them and subtracting
+/// timestamps gives the days and time between them. Times wrap around midnight
+pub fn arithmetic(op: BinaryOp, left: &Value, right: &Value) -> Option<Result<Value>> {
+    let midnight = |date: &NaiveDate| date.and_time(NaiveTime::MIN);
+    let result = match (op, left, right) {
+        (BinaryOp::Add, Value::Date(date), Value::Int(days)) | (BinaryOp::Add, Value::Int(days), Value::Date(date)) => add_days(date, *days),
+        (BinaryOp::Sub, Value::Date(date), Value::Int(days)) => days.checked_neg().and_then(|days| add_days(date, days)),
+        (BinaryOp::Sub, Value::Date(l), Value::Date(r)) => Some(Value::Int(l.signed_duration_since(*r).num_days())),
+        (BinaryOp::Add, Value::Date(date), Value::Time(time)) | (BinaryOp::Add, Value::Time(time), Value::Date(date)) => {
+            Some(Value::DateTime(date.and_time(*time).and_utc()))
+        }
+
+        (BinaryOp::Add | BinaryOp::Sub, Value::Date(date), Value::Interval(interval)) | (BinaryOp::Add, Value::Interval(interval), Value::Date(date)) => {
+            signed(op, interval).and_then(|interval| interval.add_to(midnight(date))).map(|datetime| Value::DateTime(datetime.and_utc()))
+        }
+        (BinaryOp::Add | BinaryOp::Sub, Value::DateTime(datetime), Value::Interval(interval)) | (BinaryOp::Add, Value::Interval(interval), Value::DateTime(datetime)) => {
+            signed(op, interval).and_then(|interval| interval.add_to(datetime.naive_utc())).map(|datetime| Value::DateTime(datetime.and_utc()))
+        }
+        // Days and months move the wall-clock time in the timestamp's own time zone
+        (BinaryOp::Add | BinaryOp::Sub, Value::DateTimeTz(datetime), Value::Interval(interval)) | (BinaryOp::Add, Value::Interval(interval), Value::DateTimeTz(datetime)) => {
+            signed(op, interval).and_then(|interval| interval.add_to(datetime.naive_local()))
+                .and_then(|local| datetime.offset().from_local_datetime(&local).single())
+                .map(Value::DateTimeTz)
+        }
+        (BinaryOp::Sub, Value::DateTime(_) | Value::DateTimeTz(_), Value::DateTime(_) | Value::DateTimeTz(_)) => {
+            Interval::between(instant(right), instant(left)).map(Value::Interval)
+        }
+
+        (BinaryOp::Add | BinaryOp::Sub, Value::Time(time), Value::Interval(interval)) | (BinaryOp::Add, Value::Interval(interval), Value::Time(time)) => {
+            let micros = interval.micros % MICROS_PER_DAY;
+            let micros = if op == BinaryOp::Sub { -micros } else { micros };
+            Some(Value::Time(time.overflowing_add_signed(Duration::microseconds(micros)).0))
+        }
+        (BinaryOp::Sub, Value::Time(l), Value::Time(r)) => {
+            l.signed_duration_since(*r).num_microseconds().map(|micros| Value::Interval(Interval::new(0, 0, micros)))
+        }
+
+        (BinaryOp::Add, Value::Interval(l), Value::Interval(r)) => l.checked_add(*r).map(Value::Interval),
+        (BinaryOp::Sub, Value::Interval(l), Value::Interval(r)) => l.checked_sub(*r).map(Value::Interval),
+        (BinaryOp::Mul, Value::Interval(interval), number) | (BinaryOp::Mul, number, Value::Interval(interval)) if as_f64(number).is_some() => {
+            as_f64(number).and_then(|factor| interval.checked_mul(factor)).map(Value::Interval)
+        }
+        (BinaryOp::Div, Value::Interval(_), number) if as_f64(number) == Some(0.0) => return Some(Err(KirinError::DivisionByZero)),
+        (BinaryOp::Div, Value::Interval(interval), number) if as_f64(number).is_some() => {
+            as_f64(number).and_then(|divisor| interval.checked_div(divisor)).map(Value::Interval)
+        }
+        _ => return None,
+    };
+    Some(result.ok_or(KirinError::Overflow))
+}
+
+fn add_days(date: &NaiveDate, days: i64) -> Option<Value> {
+    date.checked_add_signed(Duration::try_days(days)?).map(Value::Date)
+}
+
+/// The interval to add for `op`, negated for a subtraction
+fn signed(op: BinaryOp, interval: &Interval) -> Option<Interval> {
+    match op {
+        BinaryOp::Sub => interval.checked_neg(),
+        _ => Some(*interval),
+    }
+}
+
+/// The UTC date and time of a timestamp with or without a time zone
+fn instant(value: &Value) -> NaiveDateTime {
+    match value {
+        Value::DateTime(datetime) => datetime.naive_utc(),
+        Value::DateTimeTz(datetime) => datetime.naive_utc(),
+        _ => unreachable!("Only called on timestamps"),
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(int) => Some(*int as f64),
+        Value::Float(float) => Some(*float),
+        Value::Decimal(decimal) => Some(decimal::to_float(decimal)),
+        _ => None,
+    }
+}
+
+/// `DATE_TRUNC(field, datetime)`: the start of the year, month, hour and so
+/// on that `datetime` falls in, as seen in the timestamp's time zone
+pub fn truncate<Tz: TimeZone>(field: DateField, datetime: &DateTime<Tz>) -> Result<DateTime<Tz>> {
+    let date = datetime.date_naive();
+    let (date, time) = match field {
+        DateField::Year => (date.with_ordinal(1), (0, 0, 0)),
+        DateField::Quarter => (NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1), (0, 0, 0)),
+        DateField::Month => (date.with_day(1), (0, 0, 0)),
+        DateField::Week => (date.checked_sub_signed(Duration::days(date.weekday().num_days_from_monday() as i64)), (0, 0, 0)),
+        DateField::Day => (Some(date), (0, 0, 0)),
+        DateField::Hour => (Some(date), (datetime.hour(), 0, 0)),
+        DateField::Minute => (Some(date), (datetime.hour(), datetime.minute(), 0)),
+        DateField::Second => (Some(date), (datetime.hour(), datetime.minute(), datetime.second())),
+        field => return Err(field.not_a_unit("DATE_TRUNC")),
+    };
+
+    let (hour, minute, second) = time;
+    date.and_then(|date| date.and_hms_opt(hour, minute, second))
+        .and_then(|naive| datetime.timezone().from_local_datetime(&naive).single())
+        .ok_or(KirinError::Overflow)
+}
+
+/// `DATE_ADD(datetime, amount, unit)`. Adding months keeps the day of the
+/// month in the timestamp's time zone, moving back to the month's last day
+/// where it has fewer days
+pub fn add<Tz: TimeZone>(datetime: &DateTime<Tz>, amount: i64, unit: DateField) -> Result<DateTime<Tz>> {
+    let datetime = datetime.clone();
+    if let Some(seconds) = unit.seconds() {
+        let duration = amount.checked_mul(seconds).and_then(Duration::try_seconds).ok_or(KirinError::Overflow)?;
+        return datetime.checked_add_signed(duration).ok_or(KirinError::Overflow);
+    }
+
+    let months = unit.months().ok_or_else(|| unit.not_a_unit("DATE_ADD"))?;
+    let months = amount.checked_mul(months as i64)
+        .and_then(|months| u32::try_from(months.unsigned_abs()).ok())
+        .ok_or(KirinError::Overflow)?;
+    match amount < 0 {
+        true => datetime.checked_sub_months(Months::new(months)),
+        false => datetime.checked_add_months(Months::new(months)),
+    }
+    .ok_or(KirinError::Overflow)
+}
+
+/// `DATE_DIFF(unit, start, end)`: the number of whole units from `start` to
+/// `end`, negative when `end` comes first. Months are counted in the time
+/// zone of the timestamps
+pub fn diff<Tz: TimeZone>(unit: DateField, start: &DateTime<Tz>, end: &DateTime<Tz>) -> Result<i64> {
+    if let Some(seconds) = unit.seconds() {
+        return Ok(end.clone().signed_duration_since(start).num_seconds() / seconds);
+    }
+
+    let per_unit = unit.months().ok_or_else(|| unit.not_a_unit("DATE_DIFF"))? as i64;
+    let months = (end.year() as i64 - start.year() as i64) * 12 + end.month() as i64 - start.month() as i64;
+
+    // A month is only complete once the end reaches the start's day and time of month
+    let within_month = |datetime: &DateTime<Tz>| (datetime.day(), datetime.time());
+    let months = match months {
+        months if months > 0 && within_month(end) < within_month(start) => months - 1,
+        months if months < 0 && within_month(end) > within_month(start) => months + 1,
+        months => months,
+    };
+    Ok(months / per_unit)
+}
+
+/// Checks a `strftime` format string, e.g. `%Y-%m-%d`
+fn format_items(format: &str) -> Result<Vec<Item<'_>>> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    match items.contains(&Item::Error) {
+        true => Err(KirinError::InvalidArgument(format!("invalid date format '{format}'"))),
+        false => Ok(items),
+    }
+}
+
+/// `STRFTIME(format, datetime)`, writing the timestamp in its time zone
+pub fn format<Tz: TimeZone>(format: &str, datetime: &DateTime<Tz>) -> Result<String>
+where
+    Tz::Offset: Display,
+{
+    let items = format_items(format)?;
+    Ok(datetime.format_with_items(items.iter()).to_string())
+}
+
+/// `STRPTIME(text, format)`. Without an offset in the format the time is
+/// taken as UTC, and without a time it is midnight
+pub fn parse(text: &str, format: &str) -> Result<DateTime<Utc>> {
+    format_items(format)?;
+
+    DateTime::parse_from_str(text, format).map(|datetime| datetime.to_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(text, format).map(|naive| naive.and_utc()))
+        .or_else(|_| NaiveDate::parse_from_str(text, format).map(|date| date.and_time(Default::default()).and_utc()))
+        .map_err(|_| KirinError::InvalidArgument(format!("'{text}' does not match the date format '{format}'")))
+}
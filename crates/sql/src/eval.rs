@@ -0,0 +1,674 @@
+use core::error::Result;
+use core::{decimal, Column, KirinError, Value, ValueType};
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{FixedOffset, NaiveTime, Offset, Utc};
+use database::Database;
+
+use crate::aggregate::is_aggregate;
+use crate::ast::{BinaryOp, ColumnRef, Expr, FunctionCall, SelectStmt, UnaryOp};
+use crate::datetime::{self, DateField};
+use crate::executor::{self, Context, ResultSet};
+use crate::functions;
+use crate::pattern;
+use crate::subquery::SubqueryRows;
+use crate::window::is_window_function;
+
+/// A column visible to expressions, optionally qualified by its table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeColumn {
+    pub table: Option<String>,
+    pub name: String,
+    pub col_type: ValueType,
+    /// Left out of `*`, like the per-table copies of a USING column
+    pub hidden: bool,
+    /// How many queries out the column comes from; 0 for the current query's
+    /// own columns, 1 for those of the query enclosing a subquery, and so on
+    pub depth: usize,
+}
+
+/// The columns of the rows an expression is evaluated against
+#[derive(Clone, Default)]
+pub struct Scope<'a> {
+    pub columns: Vec<ScopeColumn>,
+    /// What subqueries read from; without it they cannot be evaluated
+    pub context: Option<Context<'a>>,
+}
+
+impl Debug for Scope<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scope").field("columns", &self.columns).finish_non_exhaustive()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// A scope without columns, for constant expressions
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// A scope over every column of a table
+    pub fn from_table(table: &str, columns: &[Column]) -> Self {
+        let columns = columns.iter()
+            .map(|col| ScopeColumn {
+                table: Some(table.to_string()),
+                name: col.name.clone(),
+                col_type: col.col_type,
+                hidden: false,
+                depth: 0,
+            })
+            .collect();
+        Self { columns, context: None }
+    }
+
+    /// Lets subqueries in the scope's expressions read from `database`
+    pub fn with_database(self, database: &'a Database) -> Self {
+        self.with_context(Context::new(database))
+    }
+
+    /// Lets subqueries in the scope's expressions read from `context`, including its CTEs
+    pub fn with_context(mut self, context: Context<'a>) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Appends the columns of an enclosing query, so correlated subqueries can
+    /// refer to them. They are shadowed by the scope's own columns and left out of `*`
+    pub fn with_outer(mut self, outer: &Scope) -> Self {
+        let outer_columns = outer.columns.iter()
+            .map(|col| ScopeColumn { hidden: true, depth: col.depth + 1, ..col.clone() });
+        self.columns.extend(outer_columns);
+        self
+    }
+
+    /// Finds the row position of a column reference, ignoring case.
+    ///
+    /// Columns of the innermost query win over those of enclosing queries.
+    /// Within a query, columns without a table, such as output aliases, shadow
+    /// table columns; a name matching columns of several tables is ambiguous
+    pub fn resolve(&self, column: &ColumnRef) -> Result<usize> {
+        let matches: Vec<_> = self.columns.iter()
+            .enumerate()
+            .filter(|(_, col)| {
+                let table_matches = match (&column.table, &col.table) {
+                    (None, _) => true,
+                    (Some(wanted), Some(table)) => wanted.eq_ignore_ascii_case(table),
+                    (Some(_), None) => false,
+                };
+                table_matches && col.name.eq_ignore_ascii_case(&column.name)
+            })
+            .collect();
+
+        let depth = matches.iter().map(|(_, col)| col.depth).min()
+            .ok_or_else(|| KirinError::UnknownColumn(column.to_string()))?;
+        let mut innermost = matches.iter().filter(|(_, col)| col.depth == depth);
+
+        let (index, first) = innermost.next().expect("At least one column matches");
+        if first.table.is_some() && innermost.next().is_some() {
+            return Err(KirinError::AmbiguousColumn(column.to_string()));
+        }
+        Ok(*index)
+    }
+}
+
+/// Evaluates an expression against a single row of `scope`
+pub fn evaluate(expr: &Expr, scope: &Scope, row: &[Value]) -> Result<Value> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Column(column) => Ok(row[scope.resolve(column)?].clone()),
+        Expr::Nested(expr) => evaluate(expr, scope, row),
+        Expr::Unary { op, expr } => unary(*op, evaluate(expr, scope, row)?),
+
+        // AND / OR short-circuit, so the right side may never be evaluated
+        Expr::Binary { left, op: op @ (BinaryOp::And | BinaryOp::Or), right } => {
+            let left = truth_value(*op, evaluate(left, scope, row)?)?;
+            match (op, left) {
+                (BinaryOp::And, Some(false)) => Ok(Value::Bool(false)),
+                (BinaryOp::Or, Some(true)) => Ok(Value::Bool(true)),
+                _ => {
+                    let right = truth_value(*op, evaluate(right, scope, row)?)?;
+                    Ok(logical(*op, left, right))
+                }
+            }
+        }
+
+        Expr::Binary { left, op, right } => {
+            binary(*op, evaluate(left, scope, row)?, evaluate(right, scope, row)?)
+        }
+
+        Expr::IsNull { expr, negated } => {
+            let is_null = evaluate(expr, scope, row)?.is_null();
+            Ok(Value::Bool(is_null != *negated))
+        }
+
+        Expr::Function(call) => call_function(call, scope, row),
+
+        Expr::InList { expr, list, negated } => {
+            let value = evaluate(expr, scope, row)?;
+            let candidates = list.iter()
+                .map(|item| evaluate(item, scope, row))
+                .collect::<Result<Vec<_>>>()?;
+            in_list(value, candidates, *negated)
+        }
+
+        Expr::InSubquery { expr, subquery, negated } => {
+            let value = evaluate(expr, scope, row)?;
+            let rows = run_subquery(subquery, scope, row)?;
+            expect_single_column(&rows.result)?;
+            Ok(rows.contains(&value, *negated))
+        }
+
+        Expr::Exists(subquery) => Ok(Value::Bool(!run_subquery(subquery, scope, row)?.result.rows.is_empty())),
+
+        // A scalar subquery yields its only value, or NULL when it finds no rows
+        Expr::Subquery(subquery) => {
+            let rows = run_subquery(subquery, scope, row)?;
+            expect_single_column(&rows.result)?;
+            match rows.result.rows.as_slice() {
+                [row] => Ok(row[0].clone()),
+                [] => Ok(Value::Null),
+                rows => Err(KirinError::InvalidSubquery(format!("expected at most one row, found {}", rows.len()))),
+            }
+        }
+
+        Expr::Match { expr, op, pattern, escape, negated } => {
+            let text = evaluate(expr, scope, row)?;
+            let pattern = evaluate(pattern, scope, row)?;
+            let escape = escape.as_ref().map(|escape| evaluate(escape, scope, row)).transpose()?;
+
+            match (text, pattern, escape) {
+                (Value::Null, _, _) | (_, Value::Null, _) | (_, _, Some(Value::Null)) => Ok(Value::Null),
+                (Value::Text(text), Value::Text(pattern), escape) => {
+                    let escape = match escape {
+                        Some(Value::Text(escape)) => Some(pattern::escape_char(&escape)?),
+                        Some(other) => return Err(invalid_operands("ESCAPE", &[&other])),
+                        None => None,
+                    };
+                    let cache = scope.context.and_then(|context| context.patterns());
+                    let matched = pattern::is_match(*op, &text, &pattern, escape, cache)?;
+                    Ok(Value::Bool(matched != *negated))
+                }
+                (text, pattern, _) => Err(invalid_operands(op, &[&text, &pattern])),
+            }
+        }
+
+        Expr::Extract { field, expr } => match evaluate(expr, scope, row)? {
+            Value::Null => Ok(Value::Null),
+            Value::Date(date) => Ok(Value::Int(datetime::extract(DateField::parse(field)?, &date.and_time(NaiveTime::MIN).and_utc()))),
+            Value::Time(time) => datetime::extract_time(DateField::parse(field)?, &time).map(Value::Int),
+            Value::DateTime(datetime) => Ok(Value::Int(datetime::extract(DateField::parse(field)?, &datetime))),
+            Value::DateTimeTz(datetime) => {
+                let local = datetime.with_timezone(&time_zone(scope));
+                Ok(Value::Int(datetime::extract(DateField::parse(field)?, &local)))
+            }
+            Value::Interval(interval) => datetime::extract_interval(DateField::parse(field)?, &interval).map(Value::Int),
+            other => Err(invalid_operands("EXTRACT", &[&other])),
+        },
+
+        Expr::Cast { expr, data_type } => evaluate(expr, scope, row)?.cast_in_zone(*data_type, time_zone(scope)),
+
+        // The first matching branch wins, and only its result is evaluated
+        Expr::Case { operand, branches, else_result } => {
+            let operand = operand.as_ref().map(|operand| evaluate(operand, scope, row)).transpose()?;
+            for (when, then) in branches {
+                let matched = match &operand {
+                    Some(operand) => binary(BinaryOp::Eq, operand.clone(), evaluate(when, scope, row)?)? == Value::Bool(true),
+                    None => evaluate_predicate(when, scope, row)?,
+                };
+                if matched {
+                    return evaluate(then, scope, row);
+                }
+            }
+            match else_result {
+                Some(else_result) => evaluate(else_result, scope, row),
+                None => Ok(Value::Null),
+            }
+        }
+    }
+}
+
+/// The session's time zone, or UTC without a database to read it from
+fn time_zone(scope: &Scope) -> FixedOffset {
+    scope.context.map_or(Utc.fix(), |context| context.database.time_zone())
+}
+
+/// `value IN (candidates)`: NULL rather than false when no candidate matches
+/// but some comparison was unknown
+fn in_list(value: Value, candidates: Vec<Value>, negated: bool) -> Result<Value> {
+    if candidates.is_empty() {
+        return Ok(Value::Bool(negated));
+    }
+
+    let mut unknown = false;
+    for candidate in candidates {
+        match binary(BinaryOp::Eq, value.clone(), candidate)? {
+            Value::Bool(true) => return Ok(Value::Bool(!negated)),
+            Value::Null => unknown = true,
+            _ => {}
+        }
+    }
+
+    match unknown {
+        true => Ok(Value::Null),
+        false => Ok(Value::Bool(negated)),
+    }
+}
+
+fn subquery_context<'a>(scope: &Scope<'a>) -> Result<Context<'a>> {
+    scope.context.ok_or_else(|| KirinError::Unsupported("subqueries outside of a query".into()))
+}
+
+/// Runs a subquery for the current row, which correlated subqueries can refer
+/// to. Other subqueries run once per statement when the context has a cache
+fn run_subquery(subquery: &SelectStmt, scope: &Scope, row: &[Value]) -> Result<Rc<SubqueryRows>> {
+    let context = subquery_context(scope)?;
+    let run_correlated = || executor::execute_subquery(context, subquery, scope, row);
+    match context.subqueries() {
+        Some(cache) => cache.rows(context, subquery, run_correlated),
+        None => run_correlated().map(|result| Rc::new(SubqueryRows::new(result))),
+    }
+}
+
+/// The type of the single column a subquery produces
+fn subquery_type(subquery: &SelectStmt, scope: &Scope) -> Result<ValueType> {
+    let columns = executor::describe_subquery(subquery_context(scope)?, subquery, scope)?;
+    match columns.as_slice() {
+        [column] => Ok(column.col_type),
+        _ => Err(KirinError::InvalidSubquery(format!("expected one column, found {}", columns.len()))),
+    }
+}
+
+fn expect_single_column(result: &ResultSet) -> Result<()> {
+    match result.columns.len() {
+        1 => Ok(()),
+        count => Err(KirinError::InvalidSubquery(format!("expected one column, found {count}"))),
+    }
+}
+
+fn call_function(call: &FunctionCall, scope: &Scope, row: &[Value]) -> Result<Value> {
+    match functions::lookup(&call.name) {
+        Some(function) if call.over.is_none() => function.call(call, scope, row),
+        _ => Err(unknown_function(call)),
+    }
+}
+
+/// Error for a call that is not a scalar function.
+///
+/// Aggregate and window calls are replaced before evaluation, so one reaching
+/// here is misplaced
+fn unknown_function(call: &FunctionCall) -> KirinError {
+    if call.over.is_some() || is_window_function(&call.name) {
+        return KirinError::InvalidWindow(format!("{call} is not allowed here"));
+    }
+
+    match is_aggregate(&call.name) {
+        true => KirinError::InvalidAggregate(format!("{call} is not allowed here")),
+        false => KirinError::UnknownFunction(call.name.clone()),
+    }
+}
+
+/// Finds a type both sides can be represented as, e.g. for the arguments of COALESCE.
+/// Decimals stay exact alongside integers, but give way to floats, and
+/// timestamps without a time zone take on one
+pub fn common_type(left: ValueType, right: ValueType) -> Option<ValueType> {
+    match (left, right) {
+        (ValueType::Null, other) | (other, ValueType::Null) => Some(other),
+        (left, right) if left == right => Some(left),
+        (ValueType::DateTime, ValueType::DateTimeTz) | (ValueType::DateTimeTz, ValueType::DateTime) => Some(ValueType::DateTimeTz),
+        (ValueType::Decimal { .. }, ValueType::Int | ValueType::Decimal { .. }) | (ValueType::Int, ValueType::Decimal { .. }) => {
+            let ((left_precision, left_scale), (right_precision, right_scale)) = (decimal_shape(left), decimal_shape(right));
+            let scale = left_scale.max(right_scale);
+            Some(decimal_type((left_precision - left_scale).max(right_precision - right_scale) + scale, scale))
+        }
+        (ValueType::Int | ValueType::Float | ValueType::Decimal { .. }, ValueType::Int | ValueType::Float | ValueType::Decimal { .. }) => {
+            Some(ValueType::Float)
+        }
+        _ => None,
+    }
+}
+
+/// The precision and scale of a numeric type, counting an integer as DECIMAL(19, 0)
+fn decimal_shape(value_type: ValueType) -> (u32, u32) {
+    match value_type {
+        ValueType::Decimal { precision, scale } => (precision, scale),
+        ValueType::Int => (19, 0),
+        _ => (1, 0),
+    }
+}
+
+/// A DECIMAL type, keeping within the most digits a decimal may have
+pub(crate) fn decimal_type(precision: u32, scale: u32) -> ValueType {
+    let precision = precision.min(decimal::MAX_PRECISION);
+    ValueType::Decimal { precision, scale: scale.min(precision) }
+}
+
+/// The DECIMAL type of `left op right`, with room for every digit the
+/// operation can produce; only a division rounds
+fn arithmetic_decimal_type(op: BinaryOp, left: ValueType, right: ValueType) -> ValueType {
+    let ((left_precision, left_scale), (right_precision, right_scale)) = (decimal_shape(left), decimal_shape(right));
+    let (left_digits, right_digits) = (left_precision - left_scale, right_precision - right_scale);
+
+    match op {
+        BinaryOp::Add | BinaryOp::Sub => {
+            let scale = left_scale.max(right_scale);
+            decimal_type(left_digits.max(right_digits) + scale + 1, scale)
+        }
+        BinaryOp::Mul => decimal_type(left_precision + right_precision, left_scale + right_scale),
+        BinaryOp::Div => {
+            let scale = left_scale.max(right_scale).max(decimal::DIVISION_SCALE);
+            decimal_type(left_digits + right_scale + scale, scale)
+        }
+        _ => {
+            let scale = left_scale.max(right_scale);
+            decimal_type(left_digits.min(right_digits) + scale, scale)
+        }
+    }
+}
+
+/// Works out the type an expression produces without evaluating it
+pub fn infer_type(expr: &Expr, scope: &Scope) -> Result<ValueType> {
+    match expr {
+        Expr::Literal(value) => Ok(ValueType::from(value)),
+        Expr::Column(column) => Ok(scope.columns[scope.resolve(column)?].col_type),
+        Expr::Nested(expr) => infer_type(expr, scope),
+        Expr::IsNull { expr, .. } => infer_type(expr, scope).map(|_| ValueType::Bool),
+
+        Expr::Unary { op, expr } => {
+            let operand = infer_type(expr, scope)?;
+            match (op, operand) {
+                (UnaryOp::Plus | UnaryOp::Minus, ValueType::Int | ValueType::Float | ValueType::Decimal { .. } | ValueType::Interval | ValueType::Null) => Ok(operand),
+                (UnaryOp::Not, ValueType::Bool | ValueType::Null) => Ok(ValueType::Bool),
+                _ => Err(KirinError::InvalidOperands { op: op.to_string(), operands: vec![operand] }),
+            }
+        }
+
+        Expr::Binary { left, op, right } => {
+            let (left, right) = (infer_type(left, scope)?, infer_type(right, scope)?);
+            let invalid = || KirinError::InvalidOperands { op: op.to_string(), operands: vec![left, right] };
+
+            match op {
+                BinaryOp::And | BinaryOp::Or => match (left, right) {
+                    (ValueType::Bool | ValueType::Null, ValueType::Bool | ValueType::Null) => Ok(ValueType::Bool),
+                    _ => Err(invalid()),
+                },
+                BinaryOp::Eq | BinaryOp::NotEq | BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+                    common_type(left, right).map(|_| ValueType::Bool).ok_or_else(invalid)
+                }
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                    if let Some(result) = datetime::arithmetic_type(*op, left, right) {
+                        return Ok(result);
+                    }
+                    match common_type(left, right) {
+                        Some(ValueType::Decimal { .. }) => Ok(arithmetic_decimal_type(*op, left, right)),
+                        Some(numeric @ (ValueType::Int | ValueType::Float | ValueType::Null)) => Ok(numeric),
+                        _ => Err(invalid()),
+                    }
+                }
+                // Blobs join only with other blobs, while anything else joins as text
+                BinaryOp::Concat => match (left, right) {
+                    (ValueType::Blob, ValueType::Blob | ValueType::Null) | (ValueType::Null, ValueType::Blob) => Ok(ValueType::Blob),
+                    (ValueType::Blob, _) | (_, ValueType::Blob) => Err(invalid()),
+                    _ => Ok(ValueType::Text),
+                },
+            }
+        }
+
+        Expr::InList { expr, list, .. } => {
+            let left = infer_type(expr, scope)?;
+            for item in list {
+                let right = infer_type(item, scope)?;
+                common_type(left, right).ok_or_else(|| KirinError::InvalidOperands { op: "IN".into(), operands: vec![left, right] })?;
+            }
+            Ok(ValueType::Bool)
+        }
+
+        Expr::InSubquery { expr, subquery, .. } => {
+            let (left, right) = (infer_type(expr, scope)?, subquery_type(subquery, scope)?);
+            common_type(left, right).ok_or_else(|| KirinError::InvalidOperands { op: "IN".into(), operands: vec![left, right] })?;
+            Ok(ValueType::Bool)
+        }
+
+        Expr::Exists(subquery) => {
+            executor::describe_subquery(subquery_context(scope)?, subquery, scope).map(|_| ValueType::Bool)
+        }
+
+        Expr::Subquery(subquery) => subquery_type(subquery, scope),
+
+        Expr::Match { expr, op, pattern, escape, .. } => {
+            let mut operands = vec![infer_type(expr, scope)?, infer_type(pattern, scope)?];
+            if let Some(escape) = escape {
+                operands.push(infer_type(escape, scope)?);
+            }
+            match operands.iter().all(|operand| matches!(operand, ValueType::Text | ValueType::Null)) {
+                true => Ok(ValueType::Bool),
+                false => Err(KirinError::InvalidOperands { op: op.to_string(), operands }),
+            }
+        }
+
+        Expr::Extract { field, expr } => {
+            DateField::parse(field)?;
+            match infer_type(expr, scope)? {
+                ValueType::Date | ValueType::Time | ValueType::DateTime | ValueType::DateTimeTz | ValueType::Interval | ValueType::Null => {
+                    Ok(ValueType::Int)
+                }
+                operand => Err(KirinError::InvalidOperands { op: "EXTRACT".into(), operands: vec![operand] }),
+            }
+        }
+
+        // Pairs of types that never convert fail before any row is read
+        Expr::Cast { expr, data_type } => match infer_type(expr, scope)? {
+            from if from.can_cast_to(*data_type) => Ok(*data_type),
+            from => Err(KirinError::InvalidCast { value: expr.to_string(), from, to: *data_type }),
+        },
+
+        Expr::Case { operand, branches, else_result } => {
+            let operand = operand.as_ref().map(|operand| infer_type(operand, scope)).transpose()?;
+            let mut result = ValueType::Null;
+            for (when, then) in branches {
+                let when_type = infer_type(when, scope)?;
+                let matches = match operand {
+                    Some(operand) => common_type(operand, when_type).is_some(),
+                    None => matches!(when_type, ValueType::Bool | ValueType::Null),
+                };
+                if !matches {
+                    let operands = operand.into_iter().chain([when_type]).collect();
+                    return Err(KirinError::InvalidOperands { op: "CASE WHEN".into(), operands });
+                }
+
+                let then_type = infer_type(then, scope)?;
+                result = common_type(result, then_type)
+                    .ok_or_else(|| KirinError::InvalidOperands { op: "CASE THEN".into(), operands: vec![result, then_type] })?;
+            }
+            match else_result {
+                Some(else_result) => {
+                    let else_type = infer_type(else_result, scope)?;
+                    common_type(result, else_type)
+                        .ok_or_else(|| KirinError::InvalidOperands { op: "CASE ELSE".into(), operands: vec![result, else_type] })
+                }
+                None => Ok(result),
+            }
+        }
+
+        Expr::Function(call) => match functions::lookup(&call.name) {
+            Some(function) if call.over.is_none() => {
+                let args = call.args.iter().map(|arg| infer_type(arg, scope)).collect::<Result<Vec<_>>>()?;
+                function.return_type(call, &args)
+            }
+            _ => Err(unknown_function(call)),
+        },
+    }
+}
+
+/// Evaluates a predicate such as a WHERE clause; NULL counts as false
+pub fn evaluate_predicate(expr: &Expr, scope: &Scope, row: &[Value]) -> Result<bool> {
+    match evaluate(expr, scope, row)? {
+        Value::Bool(bool) => Ok(bool),
+        Value::Null => Ok(false),
+        other => Err(KirinError::TypeMismatch {
+            column: expr.to_string(),
+            expected: ValueType::Bool,
+            actual: ValueType::from(&other),
+        }),
+    }
+}
+
+fn invalid_operands(op: impl ToString, operands: &[&Value]) -> KirinError {
+    let operands = operands.iter().map(|value| ValueType::from(*value)).collect();
+    KirinError::InvalidOperands { op: op.to_string(), operands }
+}
+
+/// Reads a boolean operand, where NULL is the unknown truth value
+fn truth_value(op: BinaryOp, value: Value) -> Result<Option<bool>> {
+    match value {
+        Value::Bool(bool) => Ok(Some(bool)),
+        Value::Null => Ok(None),
+        other => Err(invalid_operands(op, &[&other])),
+    }
+}
+
+/// Three-valued AND / OR
+fn logical(op: BinaryOp, left: Option<bool>, right: Option<bool>) -> Value {
+    let result = match (op, left, right) {
+        (BinaryOp::And, Some(false), _) | (BinaryOp::And, _, Some(false)) => Some(false),
+        (BinaryOp::And, Some(true), Some(true)) => Some(true),
+        (BinaryOp::Or, Some(true), _) | (BinaryOp::Or, _, Some(true)) => Some(true),
+        (BinaryOp::Or, Some(false), Some(false)) => Some(false),
+        _ => None,
+    };
+    result.map_or(Value::Null, Value::Bool)
+}
+
+fn unary(op: UnaryOp, value: Value) -> Result<Value> {
+    match (op, value) {
+        (_, Value::Null) => Ok(Value::Null),
+        (UnaryOp::Plus, value @ (Value::Int(_) | Value::Float(_) | Value::Decimal(_) | Value::Interval(_))) => Ok(value),
+        (UnaryOp::Minus, Value::Int(int)) => int.checked_neg().map(Value::Int).ok_or(KirinError::Overflow),
+        (UnaryOp::Minus, Value::Float(float)) => Ok(Value::Float(-float)),
+        (UnaryOp::Minus, Value::Decimal(decimal)) => Ok(Value::Decimal(-decimal)),
+        (UnaryOp::Minus, Value::Interval(interval)) => interval.checked_neg().map(Value::Interval).ok_or(KirinError::Overflow),
+        (UnaryOp::Not, Value::Bool(bool)) => Ok(Value::Bool(!bool)),
+        (op, value) => Err(invalid_operands(op, &[&value])),
+    }
+}
+
+pub(crate) fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value> {
+    if let BinaryOp::And | BinaryOp::Or = op {
+        return Ok(logical(op, truth_value(op, left)?, truth_value(op, right)?));
+    }
+
+    // Every other operator yields NULL when either side is NULL
+    if left.is_null() || right.is_null() {
+        return Ok(Value::Null);
+    }
+
+    match op {
+        BinaryOp::Eq | BinaryOp::NotEq | BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+            let ordering = compare(&left, &right).ok_or_else(|| invalid_operands(op, &[&left, &right]))?;
+            let result = match op {
+                BinaryOp::Eq => ordering == Ordering::Equal,
+                BinaryOp::NotEq => ordering != Ordering::Equal,
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::LtEq => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            };
+            Ok(Value::Bool(result))
+        }
+
+        BinaryOp::Concat => match (left, right) {
+            (Value::Blob(left), Value::Blob(right)) => Ok(Value::Blob([&left[..], &right[..]].concat().into())),
+            (left @ Value::Blob(_), right) | (left, right @ Value::Blob(_)) => Err(invalid_operands(op, &[&left, &right])),
+            (left, right) => Ok(Value::Text(format!("{left}{right}"))),
+        },
+
+        _ => arithmetic(op, left, right),
+    }
+}
+
+/// Compares two values of compatible types, with numbers compared by value
+/// and timestamps by the instant they stand for
+pub fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Int(_) | Value::Float(_) | Value::Decimal(_), Value::Int(_) | Value::Float(_) | Value::Decimal(_))
+        | (Value::Text(_), Value::Text(_))
+        | (Value::Blob(_), Value::Blob(_))
+        | (Value::Bool(_), Value::Bool(_))
+        | (Value::Date(_), Value::Date(_))
+        | (Value::Time(_), Value::Time(_))
+        | (Value::DateTime(_) | Value::DateTimeTz(_), Value::DateTime(_) | Value::DateTimeTz(_))
+        | (Value::Interval(_), Value::Interval(_)) => Some(left.total_cmp(right)),
+        _ => None,
+    }
+}
+
+fn arithmetic(op: BinaryOp, left: Value, right: Value) -> Result<Value> {
+    match (&left, &right) {
+        (Value::Int(l), Value::Int(r)) => {
+            let (l, r) = (*l, *r);
+            let result = match op {
+                BinaryOp::Add => l.checked_add(r),
+                BinaryOp::Sub => l.checked_sub(r),
+                BinaryOp::Mul => l.checked_mul(r),
+                BinaryOp::Div | BinaryOp::Mod if r == 0 => return Err(KirinError::DivisionByZero),
+                BinaryOp::Div => l.checked_div(r),
+                _ => l.checked_rem(r),
+            };
+            result.map(Value::Int).ok_or(KirinError::Overflow)
+        }
+
+        (Value::Decimal(_), Value::Int(_) | Value::Decimal(_)) | (Value::Int(_), Value::Decimal(_)) => {
+            let (l, r) = (as_decimal(&left), as_decimal(&right));
+            let result = match op {
+                BinaryOp::Add => l + r,
+                BinaryOp::Sub => l - r,
+                // A product has the digits after the point of both sides
+                BinaryOp::Mul => {
+                    let scale = l.fractional_digit_count() + r.fractional_digit_count();
+                    (l * r).with_scale(scale)
+                }
+                BinaryOp::Div | BinaryOp::Mod if r.is_zero() => return Err(KirinError::DivisionByZero),
+                BinaryOp::Div => decimal::divide(&l, &r)?,
+                _ => l % r,
+            };
+            match decimal::shape(&result).0 <= decimal::MAX_PRECISION {
+                true => Ok(Value::Decimal(result)),
+                false => Err(KirinError::Overflow),
+            }
+        }
+
+        (Value::Int(_) | Value::Float(_) | Value::Decimal(_), Value::Int(_) | Value::Float(_) | Value::Decimal(_)) => {
+            let (l, r) = (as_f64(&left), as_f64(&right));
+            let result = match op {
+                BinaryOp::Add => l + r,
+                BinaryOp::Sub => l - r,
+                BinaryOp::Mul => l * r,
+                BinaryOp::Div | BinaryOp::Mod if r == 0.0 => return Err(KirinError::DivisionByZero),
+                BinaryOp::Div => l / r,
+                _ => l % r,
+            };
+            Ok(Value::Float(result))
+        }
+
+        _ => datetime::arithmetic(op, &left, &right).unwrap_or_else(|| Err(invalid_operands(op, &[&left, &right]))),
+    }
+}
+
+fn as_f64(value: &Value) -> f64 {
+    match value {
+        Value::Int(int) => *int as f64,
+        Value::Float(float) => *float,
+        Value::Decimal(decimal) => decimal::to_float(decimal),
+        _ => unreachable!("Only called on numeric values"),
+    }
+}
+
+fn as_decimal(value: &Value) -> BigDecimal {
+    match value {
+        Value::Int(int) => BigDecimal::from(*int),
+        Value::Decimal(decimal) => decimal.clone(),
+        _ => unreachable!("Only called on integers and decimals"),
+    }
+}
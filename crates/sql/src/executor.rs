@@ -0,0 +1,999 @@
+use chrono::{DateTime, Utc};
+use core::error::Result;
+use core::{KirinError, Table, Value, ValueType};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::iter::zip;
+
+use database::Database;
+use storage::MemoryStorage;
+
+use crate::aggregate::{check_grouped, contains_aggregate, Aggregates};
+use crate::ast::{comma_separated, ColumnRef, Cte, DeleteStmt, Distinct, Expr, JoinConstraint, OrderByExpr, SelectItem, SelectStmt, SetOperator, TableExpr, UpdateStmt};
+use crate::eval::{common_type, evaluate, evaluate_predicate, infer_type, Scope, ScopeColumn};
+use crate::join::{join, Relation};
+use crate::pattern::PatternCache;
+use crate::subquery::SubqueryCache;
+use crate::window::{window_calls, Windows};
+
+/// A column of a query result
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultColumn {
+    pub name: String,
+    pub col_type: ValueType,
+}
+
+/// The rows produced by a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSet {
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Looks up a table, failing with [`KirinError::UnknownTable`]
+pub fn get_table<'a>(db: &'a Database, name: &str) -> Result<&'a Table<MemoryStorage>> {
+    db.table(name).ok_or_else(|| KirinError::UnknownTable(name.to_string()))
+}
+
+/// Looks up a table for modification, failing with [`KirinError::UnknownTable`]
+pub fn get_table_mut<'a>(db: &'a mut Database, name: &str) -> Result<&'a mut Table<MemoryStorage>> {
+    db.get_table(name).ok_or_else(|| KirinError::UnknownTable(name.to_string()))
+}
+
+/// Checks a row against an optional WHERE clause
+fn is_selected(selection: Option<&Expr>, scope: &Scope, row: &[Value]) -> Result<bool> {
+    match selection {
+        Some(selection) => evaluate_predicate(selection, scope, row),
+        None => Ok(true),
+    }
+}
+
+/// Runs a SELECT statement, collecting its output rows
+pub fn execute_select(db: &Database, select: &SelectStmt) -> Result<ResultSet> {
+    let (patterns, subqueries) = (PatternCache::default(), SubqueryCache::default());
+    run_select(Context::new(db).with_patterns(&patterns).with_subqueries(&subqueries), select, None, true, &mut Plan::default())
+}
+
+/// Runs a subquery for one row of the enclosing query, whose columns it can refer to
+pub fn execute_subquery(context: Context, select: &SelectStmt, outer_scope: &Scope, outer_row: &[Value]) -> Result<ResultSet> {
+    run_select(context, select, Some((outer_scope, outer_row)), true, &mut Plan::default())
+}
+
+/// Works out the columns a subquery produces without reading any table rows
+pub fn describe_subquery(context: Context, select: &SelectStmt, outer_scope: &Scope) -> Result<Vec<ResultColumn>> {
+    let outer_row = vec![Value::Null; outer_scope.columns.len()];
+    let result = run_select(context, select, Some((outer_scope, &outer_row)), false, &mut Plan::default())?;
+    Ok(result.columns)
+}
+
+/// Describes how a SELECT statement is executed, one plan step per line.
+///
+/// The query is planned and type checked without reading any rows, so
+/// nothing is evaluated
+pub fn explain_select(db: &Database, select: &SelectStmt) -> Result<Vec<String>> {
+    let mut plan = Plan::default();
+    let patterns = PatternCache::default();
+    run_select(Context::new(db).with_patterns(&patterns), select, None, false, &mut plan)?;
+    Ok(plan.lines)
+}
+
+/// The most rounds a recursive CTE may take before the query is abandoned
+pub const RECURSION_LIMIT: usize = 1000;
+
+/// What a query reads from: the tables of the database and the CTEs in scope
+#[derive(Clone, Copy)]
+pub struct Context<'a> {
+    pub database: &'a Database,
+    /// The innermost CTE, which links to those defined before it
+    ctes: Option<&'a CteTable<'a>>,
+    /// Where LIKE and similar patterns are kept once compiled
+    patterns: Option<&'a PatternCache>,
+    /// Where the rows of subqueries that do not read the enclosing row are kept
+    subqueries: Option<&'a SubqueryCache>,
+    /// When the statement started, which NOW() gives for all of it
+    now: DateTime<Utc>,
+}
+
+impl<'a> Context<'a> {
+    /// The context of a statement starting now
+    pub fn new(database: &'a Database) -> Self {
+        Self { database, ctes: None, patterns: None, subqueries: None, now: Utc::now() }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    /// A context that keeps compiled patterns in `patterns`, usually one per statement
+    pub fn with_patterns(self, patterns: &'a PatternCache) -> Self {
+        Self { patterns: Some(patterns), ..self }
+    }
+
+    pub fn patterns(&self) -> Option<&'a PatternCache> {
+        self.patterns
+    }
+
+    /// A context that keeps subquery rows in `subqueries`, one per statement
+    pub fn with_subqueries(self, subqueries: &'a SubqueryCache) -> Self {
+        Self { subqueries: Some(subqueries), ..self }
+    }
+
+    pub fn subqueries(&self) -> Option<&'a SubqueryCache> {
+        self.subqueries
+    }
+
+    /// Identifies the CTEs in scope, see [`SubqueryCache::new_scope`]
+    pub fn cte_scope(&self) -> usize {
+        self.ctes.map_or(0, |cte| cte.scope)
+    }
+
+    fn new_cte_scope(&self) -> usize {
+        self.subqueries.map_or(0, SubqueryCache::new_scope)
+    }
+
+    /// Finds a CTE by name, ignoring case; later definitions shadow earlier ones
+    fn cte(&self, name: &str) -> Option<&'a CteTable<'a>> {
+        let mut next = self.ctes;
+        while let Some(cte) = next {
+            if cte.name.eq_ignore_ascii_case(name) {
+                return Some(cte);
+            }
+            next = cte.outer;
+        }
+        None
+    }
+
+    /// A context that also sees `cte`
+    fn with_cte(&self, cte: &'a CteTable<'a>) -> Context<'a> {
+        Context { ctes: Some(cte), ..*self }
+    }
+}
+
+/// The rows of a CTE, computed once for the query that defines it
+struct CteTable<'a> {
+    name: String,
+    /// Tells this CTE apart from others of the same name, see [`Context::cte_scope`]
+    scope: usize,
+    result: ResultSet,
+    /// Columns its rows are sorted by, see [`output_ordering`]
+    sorted_by: Vec<usize>,
+    outer: Option<&'a CteTable<'a>>,
+}
+
+/// The steps a query was executed with, for EXPLAIN
+#[derive(Debug, Default)]
+struct Plan {
+    lines: Vec<String>,
+    /// Nesting level of the next step; a step's inputs are one level deeper
+    depth: usize,
+}
+
+impl Plan {
+    /// Adds a step at the current depth, returning its line number
+    fn push(&mut self, step: impl Display) -> usize {
+        self.lines.push(format!("{}{step}", "  ".repeat(self.depth)));
+        self.lines.len() - 1
+    }
+
+    /// Replaces a step added with [`Plan::push`], once more is known about it
+    fn replace(&mut self, line: usize, step: impl Display) {
+        let indent = self.lines[line].len() - self.lines[line].trim_start().len();
+        self.lines[line] = format!("{}{step}", " ".repeat(indent));
+    }
+}
+
+/// Output rows paired with the values of their ORDER BY keys
+type SortedRows = Vec<(Vec<Value>, Vec<Value>)>;
+
+/// Runs a query, optionally as a subquery of a row of an enclosing query.
+///
+/// Without `read_rows` the tables are treated as empty, which is enough to
+/// work out the result columns
+fn run_select(
+    context: Context,
+    select: &SelectStmt,
+    outer: Option<(&Scope, &[Value])>,
+    read_rows: bool,
+    plan: &mut Plan,
+) -> Result<ResultSet> {
+    match &select.with {
+        Some(with) => run_with(context, select, &with.ctes, outer, read_rows, plan),
+        None => run_query(context, select, outer, read_rows, plan),
+    }
+}
+
+/// Computes the CTEs of a query one at a time, each seeing those before it,
+/// then runs the query with all of them in scope
+fn run_with(
+    context: Context,
+    select: &SelectStmt,
+    ctes: &[Cte],
+    outer: Option<(&Scope, &[Value])>,
+    read_rows: bool,
+    plan: &mut Plan,
+) -> Result<ResultSet> {
+    let Some((cte, rest)) = ctes.split_first() else {
+        return run_query(context, select, outer, read_rows, plan);
+    };
+
+    let recursive = select.with.as_ref().is_some_and(|with| with.recursive) && reads_table(&cte.query, &cte.name);
+    plan.push(format!("{}CTE: {}", if recursive { "Recursive " } else { "" }, cte.name));
+    plan.depth += 1;
+    let result = match recursive {
+        true => run_recursive(context, cte, read_rows, plan).map(|result| (result, Vec::new())),
+        false => run_select(context, &cte.query, None, read_rows, plan).and_then(|result| {
+            let sorted_by = output_ordering(&cte.query, &result.columns);
+            Ok((ResultSet { columns: cte_columns(cte, result.columns)?, rows: result.rows }, sorted_by))
+        }),
+    };
+    plan.depth -= 1;
+
+    let (result, sorted_by) = result?;
+    let table = CteTable { name: cte.name.clone(), scope: context.new_cte_scope(), result, sorted_by, outer: context.ctes };
+    run_with(context.with_cte(&table), select, rest, outer, read_rows, plan)
+}
+
+/// Applies the column names listed for a CTE to the columns of its query
+fn cte_columns(cte: &Cte, mut columns: Vec<ResultColumn>) -> Result<Vec<ResultColumn>> {
+    if cte.columns.is_empty() {
+        return Ok(columns);
+    }
+    if cte.columns.len() != columns.len() {
+        return Err(KirinError::ArityMismatch { expected: cte.columns.len(), actual: columns.len() });
+    }
+
+    for (column, name) in columns.iter_mut().zip(&cte.columns) {
+        column.name = name.clone();
+    }
+    Ok(columns)
+}
+
+/// Computes a recursive CTE. The first query of its UNION gives the starting
+/// rows; the others are re-run against the rows added by the previous round
+/// until a round adds none
+fn run_recursive(context: Context, cte: &Cte, read_rows: bool, plan: &mut Plan) -> Result<ResultSet> {
+    let query = &cte.query;
+    if let Some(compound) = query.compound.iter().find(|compound| compound.operator != SetOperator::Union) {
+        return Err(KirinError::Unsupported(format!("{} in a recursive CTE", compound.operator)));
+    }
+    let distinct = query.compound.iter().any(|compound| !compound.all);
+
+    let (columns, start) = run_core(context, query, &[], None, read_rows, plan)?;
+    let mut columns = cte_columns(cte, columns)?;
+
+    let mut seen = HashSet::new();
+    let mut added: Vec<_> = start.into_iter()
+        .map(|(_, row)| row)
+        .filter(|row| !distinct || seen.insert(row.clone()))
+        .collect();
+    let mut rows = added.clone();
+
+    // EXPLAIN shows only the first round, which runs even without rows to
+    // plan and type the recursive queries
+    let mut later_rounds = Plan::default();
+    let mut rounds = 0;
+    while !added.is_empty() || (!read_rows && rounds == 0) {
+        rounds += 1;
+        if rounds > RECURSION_LIMIT {
+            return Err(KirinError::RecursionLimit { cte: cte.name.clone(), limit: RECURSION_LIMIT });
+        }
+
+        let previous = CteTable {
+            name: cte.name.clone(),
+            scope: context.new_cte_scope(),
+            result: ResultSet { columns: columns.clone(), rows: added },
+            sorted_by: Vec::new(),
+            outer: context.ctes,
+        };
+        let context = context.with_cte(&previous);
+
+        let round_plan = if rounds == 1 { &mut *plan } else { &mut later_rounds };
+        let mut next = Vec::new();
+        for compound in &query.compound {
+            let (compound_columns, compound_rows) = run_core(context, &compound.select, &[], None, read_rows, round_plan)?;
+            merge_columns(compound.operator, &mut columns, &compound_columns)?;
+            next.extend(compound_rows.into_iter().map(|(_, row)| row));
+        }
+
+        added = next.into_iter()
+            .filter(|row| !distinct || seen.insert(row.clone()))
+            .collect();
+        rows.extend(added.iter().cloned());
+    }
+
+    let rows = conform_rows(rows, &columns)?;
+    Ok(ResultSet { columns, rows })
+}
+
+/// Whether a query reads `table` in a FROM clause, directly or through a
+/// derived table or set operation
+fn reads_table(select: &SelectStmt, table: &str) -> bool {
+    fn from_reads(from: &TableExpr, table: &str) -> bool {
+        match from {
+            TableExpr::Table(table_ref) => table_ref.name.eq_ignore_ascii_case(table),
+            TableExpr::Join(join) => from_reads(&join.left, table) || from_reads(&join.right, table),
+            TableExpr::Subquery { query, .. } => reads_table(query, table),
+        }
+    }
+
+    select.from.as_ref().is_some_and(|from| from_reads(from, table))
+        || select.compound.iter().any(|compound| reads_table(&compound.select, table))
+}
+
+/// Checks that a query combined by a set operator has columns compatible with
+/// those before it, widening their types to fit both
+fn merge_columns(operator: SetOperator, columns: &mut [ResultColumn], other: &[ResultColumn]) -> Result<()> {
+    if columns.len() != other.len() {
+        return Err(KirinError::ArityMismatch { expected: columns.len(), actual: other.len() });
+    }
+
+    for (position, (column, other)) in columns.iter_mut().zip(other).enumerate() {
+        column.col_type = common_type(column.col_type, other.col_type).ok_or_else(|| KirinError::IncompatibleColumn {
+            operator: operator.to_string(),
+            position: position + 1,
+            left: column.col_type,
+            right: other.col_type,
+        })?;
+    }
+    Ok(())
+}
+
+/// Converts a value to the type of the result column it is in, where that type
+/// is wider than the value's own: an integer in a column that also holds
+/// floats or decimals, a decimal in one that also holds floats, or a
+/// timestamp in one that also holds timestamps with a time zone
+fn conform(value: Value, col_type: ValueType) -> Result<Value> {
+    match (&value, col_type) {
+        (Value::Int(_) | Value::Decimal(_), ValueType::Float)
+        | (Value::Int(_), ValueType::Decimal { .. })
+        | (Value::DateTime(_), ValueType::DateTimeTz) => value.cast_to(col_type),
+        _ => Ok(value),
+    }
+}
+
+/// [`conform`]s every value of the rows to the types of `columns`, after
+/// set operators have widened them
+fn conform_rows(rows: Vec<Vec<Value>>, columns: &[ResultColumn]) -> Result<Vec<Vec<Value>>> {
+    rows.into_iter()
+        .map(|row| zip(row, columns).map(|(value, column)| conform(value, column.col_type)).collect())
+        .collect()
+}
+
+/// Combines the rows of two queries with a set operator. Without ALL the
+/// result has no duplicate rows; with it, INTERSECT and EXCEPT match rows one
+/// for one, so a row appearing twice on the right cancels two on the left
+fn combine(operator: SetOperator, all: bool, left: Vec<Vec<Value>>, right: Vec<Vec<Value>>) -> Vec<Vec<Value>> {
+    let mut seen = HashSet::new();
+    let mut is_new = |row: &Vec<Value>| all || seen.insert(row.clone());
+
+    if operator == SetOperator::Union {
+        return left.into_iter().chain(right).filter(|row| is_new(row)).collect();
+    }
+
+    let mut counts: HashMap<Vec<Value>, usize> = HashMap::new();
+    for row in right {
+        *counts.entry(row).or_default() += 1;
+    }
+
+    left.into_iter()
+        .filter(|row| {
+            if !is_new(row) {
+                return false;
+            }
+            let matched = match counts.get_mut(row) {
+                Some(count) if *count > 0 => {
+                    if all {
+                        *count -= 1;
+                    }
+                    true
+                }
+                _ => false,
+            };
+            matched == (operator == SetOperator::Intersect)
+        })
+        .collect()
+}
+
+/// Runs a query whose CTEs are already in `context`
+fn run_query(
+    context: Context,
+    select: &SelectStmt,
+    outer: Option<(&Scope, &[Value])>,
+    read_rows: bool,
+    plan: &mut Plan,
+) -> Result<ResultSet> {
+    let depth = plan.depth;
+    for step in limit_steps(select) {
+        plan.push(step);
+        plan.depth += 1;
+    }
+
+    let result = match select.compound.is_empty() {
+        true => run_core(context, select, &select.order_by, outer, read_rows, plan),
+        false => run_compound(context, select, outer, read_rows, plan),
+    };
+    plan.depth = depth;
+    let (columns, mut output) = result?;
+    sort_rows(&mut output, &select.order_by);
+
+    let offset = evaluate_row_count("OFFSET", select.offset.as_ref())?.unwrap_or(0);
+    let limit = evaluate_row_count("LIMIT", select.limit.as_ref())?.unwrap_or(usize::MAX);
+    let rows = output.into_iter().skip(offset).take(limit).map(|(_, values)| values).collect();
+
+    Ok(ResultSet { columns, rows })
+}
+
+/// Runs a query made of several combined by set operators. Its ORDER BY
+/// applies to the combined rows, so it can only refer to output columns
+fn run_compound(
+    context: Context,
+    select: &SelectStmt,
+    outer: Option<(&Scope, &[Value])>,
+    read_rows: bool,
+    plan: &mut Plan,
+) -> Result<(Vec<ResultColumn>, SortedRows)> {
+    // Operators apply left to right, so the last one is the outermost step
+    for compound in select.compound.iter().rev() {
+        match compound.all {
+            true => plan.push(format!("{} ALL", compound.operator)),
+            false => plan.push(compound.operator),
+        };
+        plan.depth += 1;
+    }
+
+    let (mut columns, first) = run_core(context, select, &[], outer, read_rows, plan)?;
+    let mut rows: Vec<Vec<Value>> = first.into_iter().map(|(_, row)| row).collect();
+
+    for compound in &select.compound {
+        let (compound_columns, compound_rows) = run_core(context, &compound.select, &[], outer, read_rows, plan)?;
+        plan.depth -= 1;
+        merge_columns(compound.operator, &mut columns, &compound_columns)?;
+
+        let compound_rows = compound_rows.into_iter().map(|(_, row)| row).collect();
+        rows = combine(compound.operator, compound.all, rows, compound_rows);
+    }
+    let rows = conform_rows(rows, &columns)?;
+
+    let sort_scope = result_scope(None, &columns, context);
+    let sort_keys = select.order_by.iter()
+        .map(|key| resolve_sort_key(key, columns.len(), &sort_scope))
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = rows.into_iter()
+        .map(|row| {
+            let keys = sort_keys.iter().map(|expr| evaluate(expr, &sort_scope, &row)).collect::<Result<Vec<_>>>()?;
+            Ok((keys, row))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((columns, output))
+}
+
+/// Runs the `SELECT ... HAVING` part of a query, leaving out its WITH, set
+/// operators and LIMIT. Each output row comes with its `order_by` key values
+fn run_core(
+    context: Context,
+    select: &SelectStmt,
+    order_by: &[OrderByExpr],
+    outer: Option<(&Scope, &[Value])>,
+    read_rows: bool,
+    plan: &mut Plan,
+) -> Result<(Vec<ResultColumn>, SortedRows)> {
+    let depth = plan.depth;
+    for step in core_steps(select, order_by) {
+        plan.push(step);
+        plan.depth += 1;
+    }
+
+    // Without a FROM clause the select list is evaluated once, against no columns
+    let relation = match &select.from {
+        Some(from) => scan(context, from, read_rows, plan),
+        None if read_rows => Ok(Relation { scope: Scope::empty(), rows: vec![Vec::new()], sorted_by: Vec::new() }),
+        None => Ok(Relation::default()),
+    };
+    plan.depth = depth;
+    let Relation { scope, rows: mut source_rows, .. } = relation?;
+
+    // A correlated subquery sees the enclosing row after its own columns
+    let mut scope = scope.with_context(context);
+    if let Some((outer_scope, outer_row)) = outer {
+        scope = scope.with_outer(outer_scope);
+        for row in &mut source_rows {
+            row.extend_from_slice(outer_row);
+        }
+    }
+
+    let mut projection = expand_projection(&select.projection, &scope)?;
+
+    if let Some(selection) = &select.selection {
+        infer_type(selection, &scope)?;
+    }
+
+    let mut rows = Vec::new();
+    for row in source_rows {
+        if is_selected(select.selection.as_ref(), &scope, &row)? {
+            rows.push(row);
+        }
+    }
+
+    // Aggregate queries continue over one row per group, with aggregate calls
+    // replaced by references to their results
+    let mut having = select.having.clone();
+    let mut order_by = order_by.to_vec();
+
+    // DISTINCT ON keys are resolved like sort keys, and their values follow the sort key values
+    let sort_width = order_by.len();
+    if let Some(Distinct::On(exprs)) = &select.distinct {
+        order_by.extend(exprs.iter().map(|expr| OrderByExpr { expr: expr.clone(), descending: false, nulls_first: None }));
+    }
+    let (scope, rows) = if is_aggregate_query(select, &order_by) {
+        let group_by = select.group_by.iter()
+            .map(|key| resolve_group_key(key, &projection, &scope))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (_, expr) in &projection {
+            check_grouped(expr, &scope, &group_by)?;
+        }
+        if let Some(having) = &having {
+            check_grouped(having, &scope, &group_by)?;
+        }
+        for key in &order_by {
+            if !names_output_column(&key.expr, &projection) {
+                check_grouped(&key.expr, &scope, &group_by)?;
+            }
+        }
+
+        let mut aggregates = Aggregates::default();
+        for (_, expr) in &mut projection {
+            *expr = aggregates.extract(expr)?;
+        }
+        having = having.map(|having| aggregates.extract(&having)).transpose()?;
+        for key in &mut order_by {
+            key.expr = aggregates.extract(&key.expr)?;
+        }
+
+        aggregates.group(&scope, rows, &group_by)?
+    } else {
+        (scope, rows)
+    };
+    // The single group of an empty input is not evaluated either when no rows are read
+    let rows = match read_rows {
+        true => rows,
+        false => Vec::new(),
+    };
+
+    if let Some(having) = &having {
+        infer_type(having, &scope)?;
+    }
+    let mut kept = Vec::with_capacity(rows.len());
+    for row in rows {
+        if is_selected(having.as_ref(), &scope, &row)? {
+            kept.push(row);
+        }
+    }
+
+    // Window calls are computed over the remaining rows and read like columns
+    let mut windows = Windows::default();
+    for (_, expr) in &mut projection {
+        *expr = windows.extract(expr)?;
+    }
+    for key in &mut order_by {
+        key.expr = windows.extract(&key.expr)?;
+    }
+    let (scope, rows) = windows.compute(&scope, kept)?;
+
+    let columns = projection.iter()
+        .map(|(name, expr)| Ok(ResultColumn { name: name.clone(), col_type: infer_type(expr, &scope)? }))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Sort keys see the output columns first, then the source columns
+    let mut sort_scope = result_scope(None, &columns, context);
+    sort_scope.columns.extend(scope.columns.iter().cloned());
+    let sort_keys = order_by.iter()
+        .map(|key| resolve_sort_key(key, columns.len(), &sort_scope))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut output = Vec::new();
+    for row in rows {
+        let values = projection.iter()
+            .zip(&columns)
+            .map(|((_, expr), column)| conform(evaluate(expr, &scope, &row)?, column.col_type))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut keys = Vec::with_capacity(sort_keys.len());
+        if !sort_keys.is_empty() {
+            let combined: Vec<Value> = values.iter().chain(&row).cloned().collect();
+            for expr in &sort_keys {
+                keys.push(evaluate(expr, &sort_scope, &combined)?);
+            }
+        }
+        output.push((keys, values));
+    }
+
+    if let Some(distinct) = &select.distinct {
+        // Sorting first makes DISTINCT ON keep the first row of each group in ORDER BY order
+        sort_rows(&mut output, &order_by[..sort_width]);
+
+        let mut seen = HashSet::new();
+        output.retain(|(keys, values)| match distinct {
+            Distinct::Rows => seen.insert(values.clone()),
+            Distinct::On(_) => seen.insert(keys[sort_width..].to_vec()),
+        });
+    }
+
+    Ok((columns, output))
+}
+
+/// Sorts output rows by their ORDER BY key values. The sort is stable, so rows
+/// with equal keys keep their storage order
+fn sort_rows(output: &mut SortedRows, order_by: &[OrderByExpr]) {
+    output.sort_by(|(left, _), (right, _)| compare_rows(order_by, left, right));
+}
+
+/// Orders two rows of sort key values, one value per key in `order_by`
+pub(crate) fn compare_rows(order_by: &[OrderByExpr], left: &[Value], right: &[Value]) -> Ordering {
+    order_by.iter().zip(left.iter().zip(right))
+        .map(|(key, (l, r))| compare_sort_values(key, l, r))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// The LIMIT and ORDER BY steps of a query, outermost first
+fn limit_steps(select: &SelectStmt) -> Vec<String> {
+    let mut steps = Vec::new();
+
+    match (&select.limit, &select.offset) {
+        (Some(limit), Some(offset)) => steps.push(format!("Limit: {limit} OFFSET {offset}")),
+        (Some(limit), None) => steps.push(format!("Limit: {limit}")),
+        (None, Some(offset)) => steps.push(format!("Offset: {offset}")),
+        (None, None) => {}
+    }
+    if !select.order_by.is_empty() {
+        steps.push(format!("Sort: {}", comma_separated(&select.order_by)));
+    }
+
+    steps
+}
+
+/// The steps of a query between its ORDER BY and its FROM clause, outermost first
+fn core_steps(select: &SelectStmt, order_by: &[OrderByExpr]) -> Vec<String> {
+    let mut steps = Vec::new();
+
+    match &select.distinct {
+        Some(Distinct::Rows) => steps.push(String::from("Distinct")),
+        Some(Distinct::On(exprs)) => steps.push(format!("Distinct On: {}", comma_separated(exprs))),
+        None => {}
+    }
+    steps.push(format!("Project: {}", comma_separated(&select.projection)));
+
+    let calls = select.projection.iter()
+        .filter_map(|item| match item {
+            SelectItem::Expr { expr, .. } => Some(expr),
+            _ => None,
+        })
+        .chain(order_by.iter().map(|key| &key.expr))
+        .flat_map(window_calls)
+        .map(ToString::to_string)
+        .fold(Vec::new(), |mut calls, call| {
+            if !calls.contains(&call) {
+                calls.push(call);
+            }
+            calls
+        });
+    if !calls.is_empty() {
+        steps.push(format!("Window: {}", calls.join(", ")));
+    }
+
+    if let Some(having) = &select.having {
+        steps.push(format!("Filter: {having}"));
+    }
+    if is_aggregate_query(select, order_by) {
+        match select.group_by.is_empty() {
+            true => steps.push(String::from("Aggregate")),
+            false => steps.push(format!("Aggregate: GROUP BY {}", comma_separated(&select.group_by))),
+        }
+    }
+    if let Some(selection) = &select.selection {
+        steps.push(format!("Filter: {selection}"));
+    }
+
+    steps
+}
+
+/// A scope over the columns of a query result, qualified by `table` if given
+fn result_scope<'a>(table: Option<&str>, columns: &[ResultColumn], context: Context<'a>) -> Scope<'a> {
+    let columns = columns.iter()
+        .map(|col| ScopeColumn {
+            table: table.map(str::to_string),
+            name: col.name.clone(),
+            col_type: col.col_type,
+            hidden: false,
+            depth: 0,
+        })
+        .collect();
+    Scope { columns, context: Some(context) }
+}
+
+/// Reads the rows of a FROM clause, joining tables as needed
+fn scan<'a>(context: Context<'a>, from: &TableExpr, read_rows: bool, plan: &mut Plan) -> Result<Relation<'a>> {
+    match from {
+        TableExpr::Table(table_ref) => {
+            let name = table_ref.alias.as_ref().unwrap_or(&table_ref.name);
+
+            if let Some(cte) = context.cte(&table_ref.name) {
+                plan.push(format!("CTE Scan: {table_ref}"));
+                let rows = match read_rows {
+                    true => cte.result.rows.clone(),
+                    false => Vec::new(),
+                };
+                let scope = result_scope(Some(name), &cte.result.columns, context);
+                return Ok(Relation { scope, rows, sorted_by: cte.sorted_by.clone() });
+            }
+
+            let table = get_table(context.database, &table_ref.name)?;
+            plan.push(format!("Scan: {table_ref}"));
+
+            let rows = match read_rows {
+                true => table.iter().map(|row| row.values.clone()).collect(),
+                false => Vec::new(),
+            };
+            // Tables keep no order of their own
+            Ok(Relation { scope: Scope::from_table(name, &table.columns).with_context(context), rows, sorted_by: Vec::new() })
+        }
+
+        TableExpr::Join(join_expr) => {
+            // The strategy is only known once both inputs are planned
+            let line = plan.push("");
+            plan.depth += 1;
+            let left = scan(context, &join_expr.left, read_rows, plan)?;
+            let right = scan(context, &join_expr.right, read_rows, plan)?;
+            plan.depth -= 1;
+
+            let (relation, strategy) = join(left, right, join_expr.kind, &join_expr.constraint)?;
+            match &join_expr.constraint {
+                JoinConstraint::None => plan.replace(line, format!("{strategy} ({})", join_expr.kind)),
+                constraint => plan.replace(line, format!("{strategy} ({}) {constraint}", join_expr.kind)),
+            }
+            Ok(relation)
+        }
+
+        TableExpr::Subquery { query, alias } => {
+            plan.push(format!("Subquery: {alias}"));
+            plan.depth += 1;
+            let result = run_select(context, query, None, read_rows, plan);
+            plan.depth -= 1;
+
+            let result = result?;
+            let sorted_by = output_ordering(query, &result.columns);
+            Ok(Relation { scope: result_scope(Some(alias), &result.columns, context), rows: result.rows, sorted_by })
+        }
+    }
+}
+
+/// The output columns a query's rows are sorted by, ascending with NULLs
+/// first: its leading ORDER BY keys that name an output column or position
+fn output_ordering(select: &SelectStmt, columns: &[ResultColumn]) -> Vec<usize> {
+    select.order_by.iter()
+        .map_while(|key| {
+            if key.descending || key.nulls_first == Some(false) {
+                return None;
+            }
+            match &key.expr {
+                Expr::Column(ColumnRef { table: None, name }) => {
+                    let mut named = (0..columns.len()).filter(|index| columns[*index].name.eq_ignore_ascii_case(name));
+                    named.next().filter(|_| named.next().is_none())
+                }
+                Expr::Literal(Value::Int(position)) => {
+                    usize::try_from(*position).ok().filter(|position| (1..=columns.len()).contains(position)).map(|position| position - 1)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Whether a query computes one row per group rather than one per source row
+fn is_aggregate_query(select: &SelectStmt, order_by: &[OrderByExpr]) -> bool {
+    let projects_aggregate = select.projection.iter()
+        .any(|item| matches!(item, SelectItem::Expr { expr, .. } if contains_aggregate(expr)));
+
+    !select.group_by.is_empty()
+        || select.having.is_some()
+        || projects_aggregate
+        || order_by.iter().any(|key| contains_aggregate(&key.expr))
+}
+
+/// Resolves a GROUP BY key, which may also name an output column by alias or position
+fn resolve_group_key(key: &Expr, projection: &[(String, Expr)], scope: &Scope) -> Result<Expr> {
+    let output = match key {
+        Expr::Literal(Value::Int(position)) => {
+            let index = usize::try_from(*position).ok().filter(|index| (1..=projection.len()).contains(index));
+            let Some(index) = index else {
+                return Err(KirinError::InvalidArgument(format!("GROUP BY position {position} is not in the select list")));
+            };
+            Some(&projection[index - 1].1)
+        }
+        Expr::Column(column) if column.table.is_none() && scope.resolve(column).is_err() => {
+            projection.iter().find(|(name, _)| name.eq_ignore_ascii_case(&column.name)).map(|(_, expr)| expr)
+        }
+        _ => None,
+    };
+
+    let key = output.unwrap_or(key);
+    if contains_aggregate(key) {
+        return Err(KirinError::InvalidAggregate(format!("GROUP BY cannot contain aggregates, as in {key}")));
+    }
+    Ok(key.clone())
+}
+
+/// Whether an ORDER BY key refers to an output column rather than an expression
+fn names_output_column(key: &Expr, projection: &[(String, Expr)]) -> bool {
+    match key {
+        Expr::Literal(Value::Int(_)) => true,
+        Expr::Column(ColumnRef { table: None, name }) => projection.iter().any(|(output, _)| output.eq_ignore_ascii_case(name)),
+        _ => false,
+    }
+}
+
+/// Turns an ORDER BY key into an expression over the sort scope.
+///
+/// A bare integer literal refers to an output column by its 1-based position
+fn resolve_sort_key(key: &OrderByExpr, width: usize, scope: &Scope) -> Result<Expr> {
+    if let Expr::Literal(Value::Int(position)) = key.expr {
+        if position < 1 || position as usize > width {
+            return Err(KirinError::InvalidArgument(format!("ORDER BY position {position} is not in the select list")));
+        }
+        let column = &scope.columns[position as usize - 1];
+        let column = ColumnRef { table: None, name: column.name.clone() };
+        return Ok(Expr::Column(column));
+    }
+
+    infer_type(&key.expr, scope)?;
+    Ok(key.expr.clone())
+}
+
+/// Orders two sort key values, applying the key's direction and NULL placement
+pub(crate) fn compare_sort_values(key: &OrderByExpr, left: &Value, right: &Value) -> Ordering {
+    // Without NULLS FIRST/LAST, NULL is the smallest value in either direction
+    let nulls_first = key.nulls_first.unwrap_or(!key.descending);
+
+    match (left.is_null(), right.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) if nulls_first => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (false, true) if nulls_first => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) if key.descending => left.total_cmp(right).reverse(),
+        (false, false) => left.total_cmp(right),
+    }
+}
+
+/// Evaluates a LIMIT or OFFSET clause to a row count; NULL means no bound
+fn evaluate_row_count(clause: &str, expr: Option<&Expr>) -> Result<Option<usize>> {
+    let Some(expr) = expr else {
+        return Ok(None);
+    };
+
+    match evaluate(expr, &Scope::empty(), &[])? {
+        Value::Null => Ok(None),
+        Value::Int(count) => usize::try_from(count)
+            .map(Some)
+            .map_err(|_| KirinError::InvalidArgument(format!("{clause} must not be negative"))),
+        value => Err(KirinError::TypeMismatch {
+            column: clause.to_string(),
+            expected: ValueType::Int,
+            actual: ValueType::from(&value),
+        }),
+    }
+}
+
+/// Runs an UPDATE statement, returning the number of rows changed.
+///
+/// Every new row is computed and checked before any is written, so a
+/// failing statement leaves the table untouched
+pub fn execute_update(db: &mut Database, update: &UpdateStmt) -> Result<usize> {
+    // Changes are worked out before any is applied, so subqueries see the table as it was
+    let table = get_table(db, &update.table)?;
+    let (patterns, subqueries) = (PatternCache::default(), SubqueryCache::default());
+    let context = Context::new(db).with_patterns(&patterns).with_subqueries(&subqueries);
+    let scope = Scope::from_table(&update.table, &table.columns).with_context(context);
+
+    let mut targets = Vec::with_capacity(update.assignments.len());
+    for assignment in &update.assignments {
+        let index = table.column_index(&assignment.column)
+            .ok_or_else(|| KirinError::UnknownColumn(assignment.column.clone()))?;
+        infer_type(&assignment.value, &scope)?;
+        targets.push((index, &assignment.value));
+    }
+
+    if let Some(selection) = &update.selection {
+        infer_type(selection, &scope)?;
+    }
+
+    let mut changes = Vec::new();
+    for (row_id, row) in table.iter_with_ids() {
+        if !is_selected(update.selection.as_ref(), &scope, &row.values)? {
+            continue;
+        }
+
+        let mut values = row.values.clone();
+        for (index, expr) in &targets {
+            values[*index] = evaluate(expr, &scope, &row.values)?;
+        }
+        changes.push((row_id, table.coerce_values(values)?));
+    }
+
+    let count = changes.len();
+    let table = get_table_mut(db, &update.table)?;
+    for (row_id, values) in changes {
+        table.update(row_id, values)?;
+    }
+
+    Ok(count)
+}
+
+/// Runs a DELETE statement, returning the number of rows removed
+pub fn execute_delete(db: &mut Database, delete: &DeleteStmt) -> Result<usize> {
+    let table = get_table(db, &delete.table)?;
+    let (patterns, subqueries) = (PatternCache::default(), SubqueryCache::default());
+    let context = Context::new(db).with_patterns(&patterns).with_subqueries(&subqueries);
+    let scope = Scope::from_table(&delete.table, &table.columns).with_context(context);
+
+    if let Some(selection) = &delete.selection {
+        infer_type(selection, &scope)?;
+    }
+
+    let mut doomed = Vec::new();
+    for (row_id, row) in table.iter_with_ids() {
+        if is_selected(delete.selection.as_ref(), &scope, &row.values)? {
+            doomed.push(row_id);
+        }
+    }
+
+    let table = get_table_mut(db, &delete.table)?;
+    Ok(doomed.into_iter().filter(|row_id| table.delete(*row_id)).count())
+}
+
+/// Expands wildcards in the select list, pairing every output column with its name
+fn expand_projection(items: &[SelectItem], scope: &Scope) -> Result<Vec<(String, Expr)>> {
+    let mut projection = Vec::new();
+
+    for item in items {
+        match item {
+            SelectItem::Wildcard | SelectItem::QualifiedWildcard(_) => {
+                let qualifier = match item {
+                    SelectItem::QualifiedWildcard(table) => Some(table),
+                    _ => None,
+                };
+
+                let matching: Vec<_> = scope.columns.iter()
+                    .filter(|col| match (qualifier, &col.table) {
+                        (None, _) => !col.hidden,
+                        (Some(wanted), Some(table)) => wanted.eq_ignore_ascii_case(table),
+                        (Some(_), None) => false,
+                    })
+                    .collect();
+
+                if let (Some(table), true) = (qualifier, matching.is_empty()) {
+                    return Err(KirinError::UnknownTable(table.clone()));
+                }
+
+                for col in matching {
+                    let column = ColumnRef { table: col.table.clone(), name: col.name.clone() };
+                    projection.push((col.name.clone(), Expr::Column(column)));
+                }
+            }
+
+            SelectItem::Expr { expr, alias } => {
+                let name = match (alias, expr) {
+                    (Some(alias), _) => alias.clone(),
+                    (None, Expr::Column(column)) => scope.columns[scope.resolve(column)?].name.clone(),
+                    (None, expr) => expr.to_string(),
+                };
+                projection.push((name, expr.clone()));
+            }
+        }
+    }
+
+    Ok(projection)
+}
@@ -0,0 +1,523 @@
+use bigdecimal::RoundingMode;
+use chrono::{DateTime, FixedOffset, NaiveTime, Offset, Utc};
+use core::error::Result;
+use core::{decimal, parse_hex, to_hex, KirinError, Value, ValueType};
+
+use crate::ast::{BinaryOp, Expr, FunctionCall};
+use crate::datetime::{self, DateField};
+use crate::eval::{binary, common_type, evaluate, evaluate_predicate, Scope};
+
+/// A built-in scalar function
+pub struct Function {
+    pub name: &'static str,
+    /// Fewest arguments accepted
+    pub min_args: usize,
+    /// Most arguments accepted, or None for any number
+    pub max_args: Option<usize>,
+    /// The result type for the given argument types, or None if the function
+    /// does not accept them
+    signature: fn(&[ValueType]) -> Option<ValueType>,
+    body: Body,
+}
+
+enum Body {
+    /// Computes the result from the argument values; NULL when any of them is NULL
+    Strict(fn(&[Value]) -> Result<Value>),
+    /// Computes the result from the argument values, NULLs included
+    NullAware(fn(&[Value]) -> Result<Value>),
+    /// Evaluates the arguments itself, so some may never be evaluated, and
+    /// may read the statement's context from the scope
+    Lazy(fn(&[Expr], &Scope, &[Value]) -> Result<Value>),
+}
+
+/// Every scalar function, by name
+static FUNCTIONS: &[Function] = &[
+    // Text
+    Function { name: "LOWER", min_args: 1, max_args: Some(1), signature: text_args, body: Body::Strict(lower) },
+    Function { name: "UPPER", min_args: 1, max_args: Some(1), signature: text_args, body: Body::Strict(upper) },
+    Function { name: "LENGTH", min_args: 1, max_args: Some(1), signature: length_args, body: Body::Strict(length) },
+    Function { name: "SUBSTR", min_args: 2, max_args: Some(3), signature: substr_args, body: Body::Strict(substr) },
+    Function { name: "TRIM", min_args: 1, max_args: Some(2), signature: text_args, body: Body::Strict(trim) },
+    Function { name: "REPLACE", min_args: 3, max_args: Some(3), signature: text_args, body: Body::Strict(replace) },
+    Function { name: "CONCAT", min_args: 1, max_args: None, signature: concat_args, body: Body::NullAware(concat) },
+
+    // Binary
+    Function { name: "HEX", min_args: 1, max_args: Some(1), signature: hex_args, body: Body::Strict(hex) },
+    Function { name: "UNHEX", min_args: 1, max_args: Some(1), signature: unhex_args, body: Body::Strict(unhex) },
+
+    // Math
+    Function { name: "ABS", min_args: 1, max_args: Some(1), signature: numeric_arg, body: Body::Strict(abs) },
+    Function { name: "ROUND", min_args: 1, max_args: Some(2), signature: round_args, body: Body::Strict(round) },
+    Function { name: "FLOOR", min_args: 1, max_args: Some(1), signature: numeric_arg, body: Body::Strict(floor) },
+    Function { name: "CEIL", min_args: 1, max_args: Some(1), signature: numeric_arg, body: Body::Strict(ceil) },
+    Function { name: "CEILING", min_args: 1, max_args: Some(1), signature: numeric_arg, body: Body::Strict(ceil) },
+    Function { name: "POWER", min_args: 2, max_args: Some(2), signature: float_result, body: Body::Strict(power) },
+    Function { name: "SQRT", min_args: 1, max_args: Some(1), signature: float_result, body: Body::Strict(sqrt) },
+    Function { name: "MOD", min_args: 2, max_args: Some(2), signature: mod_args, body: Body::Strict(modulo) },
+
+    // Date and time
+    Function { name: "NOW", min_args: 0, max_args: Some(0), signature: datetime_result, body: Body::Lazy(now) },
+    Function { name: "DATE_TRUNC", min_args: 2, max_args: Some(2), signature: date_trunc_args, body: Body::Strict(date_trunc) },
+    Function { name: "DATE_PART", min_args: 2, max_args: Some(2), signature: date_part_args, body: Body::Strict(date_part) },
+    Function { name: "DATE_ADD", min_args: 3, max_args: Some(3), signature: date_add_args, body: Body::Strict(date_add) },
+    Function { name: "DATE_DIFF", min_args: 3, max_args: Some(3), signature: date_diff_args, body: Body::Strict(date_diff) },
+    Function { name: "STRFTIME", min_args: 2, max_args: Some(2), signature: strftime_args, body: Body::Strict(strftime) },
+    Function { name: "STRPTIME", min_args: 2, max_args: Some(2), signature: strptime_args, body: Body::Strict(strptime) },
+
+    // Conditional
+    Function { name: "COALESCE", min_args: 1, max_args: None, signature: common_args, body: Body::Lazy(coalesce) },
+    Function { name: "NULLIF", min_args: 2, max_args: Some(2), signature: nullif_args, body: Body::NullAware(nullif) },
+    Function { name: "IIF", min_args: 3, max_args: Some(3), signature: iif_args, body: Body::Lazy(iif) },
+    Function { name: "GREATEST", min_args: 1, max_args: None, signature: common_args, body: Body::NullAware(greatest) },
+    Function { name: "LEAST", min_args: 1, max_args: None, signature: common_args, body: Body::NullAware(least) },
+];
+
+/// Finds a scalar function by name, ignoring case
+pub fn lookup(name: &str) -> Option<&'static Function> {
+    FUNCTIONS.iter().find(|function| function.name.eq_ignore_ascii_case(name))
+}
+
+impl Function {
+    /// Checks a call's arguments, given their types, and works out the type of its result
+    pub fn return_type(&self, call: &FunctionCall, args: &[ValueType]) -> Result<ValueType> {
+        self.check_call(call)?;
+        (self.signature)(args).ok_or_else(|| self.invalid_operands(args.to_vec()))
+    }
+
+    /// Calls the function for one row of `scope`
+    pub fn call(&self, call: &FunctionCall, scope: &Scope, row: &[Value]) -> Result<Value> {
+        self.check_call(call)?;
+
+        let (body, strict) = match self.body {
+            Body::Lazy(body) => return body(&call.args, scope, row),
+            Body::Strict(body) => (body, true),
+            Body::NullAware(body) => (body, false),
+        };
+
+        let args = call.args.iter().map(|arg| evaluate(arg, scope, row)).collect::<Result<Vec<_>>>()?;
+        if strict && args.iter().any(Value::is_null) {
+            return Ok(Value::Null);
+        }
+
+        // Values reaching here unchecked, e.g. in INSERT, are checked like their types would be
+        let types: Vec<ValueType> = args.iter().map(ValueType::from).collect();
+        if (self.signature)(&types).is_none() {
+            return Err(self.invalid_operands(types));
+        }
+        body(&args)
+    }
+
+    fn check_call(&self, call: &FunctionCall) -> Result<()> {
+        if call.distinct || call.wildcard {
+            return Err(KirinError::InvalidArgument(format!("{} cannot be called with DISTINCT or *", self.name)));
+        }
+
+        let actual = call.args.len();
+        if actual < self.min_args {
+            return Err(KirinError::ArityMismatch { expected: self.min_args, actual });
+        }
+        match self.max_args {
+            Some(max_args) if actual > max_args => Err(KirinError::ArityMismatch { expected: max_args, actual }),
+            _ => Ok(()),
+        }
+    }
+
+    fn invalid_operands(&self, operands: Vec<ValueType>) -> KirinError {
+        KirinError::InvalidOperands { op: self.name.to_string(), operands }
+    }
+}
+
+fn is_text(arg: &ValueType) -> bool {
+    matches!(arg, ValueType::Text | ValueType::Null)
+}
+
+fn is_blob(arg: &ValueType) -> bool {
+    matches!(arg, ValueType::Blob | ValueType::Null)
+}
+
+fn is_int(arg: &ValueType) -> bool {
+    matches!(arg, ValueType::Int | ValueType::Null)
+}
+
+fn is_numeric(arg: &ValueType) -> bool {
+    matches!(arg, ValueType::Int | ValueType::Float | ValueType::Decimal { .. } | ValueType::Null)
+}
+
+/// A date or a timestamp, with or without a time zone
+fn is_datetime(arg: &ValueType) -> bool {
+    matches!(arg, ValueType::Date | ValueType::DateTime | ValueType::DateTimeTz | ValueType::Null)
+}
+
+/// The timestamp type a date or timestamp argument gives: one with a time
+/// zone keeps it, while dates become timestamps at midnight
+fn timestamp_type(arg: &ValueType) -> ValueType {
+    match arg {
+        ValueType::DateTimeTz => ValueType::DateTimeTz,
+        _ => ValueType::DateTime,
+    }
+}
+
+fn text_args(args: &[ValueType]) -> Option<ValueType> {
+    args.iter().all(is_text).then_some(ValueType::Text)
+}
+
+fn length_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_text(&args[0]) || is_blob(&args[0])).then_some(ValueType::Int)
+}
+
+/// Text or a blob, giving a result of the same kind
+fn substr_args(args: &[ValueType]) -> Option<ValueType> {
+    let result = match args[0] {
+        ValueType::Blob => ValueType::Blob,
+        arg if is_text(&arg) => ValueType::Text,
+        _ => return None,
+    };
+    args[1..].iter().all(is_int).then_some(result)
+}
+
+fn hex_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_text(&args[0]) || is_blob(&args[0])).then_some(ValueType::Text)
+}
+
+fn unhex_args(args: &[ValueType]) -> Option<ValueType> {
+    is_text(&args[0]).then_some(ValueType::Blob)
+}
+
+fn concat_args(_: &[ValueType]) -> Option<ValueType> {
+    Some(ValueType::Text)
+}
+
+/// A single number, giving a result of the same type
+fn numeric_arg(args: &[ValueType]) -> Option<ValueType> {
+    is_numeric(&args[0]).then_some(args[0])
+}
+
+fn round_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_numeric(&args[0]) && args[1..].iter().all(is_int)).then_some(args[0])
+}
+
+fn float_result(args: &[ValueType]) -> Option<ValueType> {
+    args.iter().all(is_numeric).then_some(ValueType::Float)
+}
+
+fn mod_args(args: &[ValueType]) -> Option<ValueType> {
+    common_type(args[0], args[1]).filter(is_numeric)
+}
+
+fn datetime_result(_: &[ValueType]) -> Option<ValueType> {
+    Some(ValueType::DateTime)
+}
+
+fn date_trunc_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_text(&args[0]) && is_datetime(&args[1])).then(|| timestamp_type(&args[1]))
+}
+
+fn date_part_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_text(&args[0]) && is_datetime(&args[1])).then_some(ValueType::Int)
+}
+
+fn date_add_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_datetime(&args[0]) && is_int(&args[1]) && is_text(&args[2])).then(|| timestamp_type(&args[0]))
+}
+
+fn date_diff_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_text(&args[0]) && is_datetime(&args[1]) && is_datetime(&args[2])).then_some(ValueType::Int)
+}
+
+fn strftime_args(args: &[ValueType]) -> Option<ValueType> {
+    (is_text(&args[0]) && is_datetime(&args[1])).then_some(ValueType::Text)
+}
+
+fn strptime_args(args: &[ValueType]) -> Option<ValueType> {
+    args.iter().all(is_text).then_some(ValueType::DateTime)
+}
+
+/// Arguments of one common type, which is also the result's
+fn common_args(args: &[ValueType]) -> Option<ValueType> {
+    args.iter().try_fold(ValueType::Null, |acc, arg| common_type(acc, *arg))
+}
+
+fn nullif_args(args: &[ValueType]) -> Option<ValueType> {
+    common_type(args[0], args[1]).map(|_| args[0])
+}
+
+fn iif_args(args: &[ValueType]) -> Option<ValueType> {
+    matches!(args[0], ValueType::Bool | ValueType::Null).then(|| common_type(args[1], args[2])).flatten()
+}
+
+fn text(value: &Value) -> &str {
+    match value {
+        Value::Text(text) => text,
+        _ => unreachable!("Arguments are type checked"),
+    }
+}
+
+fn int(value: &Value) -> i64 {
+    match value {
+        Value::Int(int) => *int,
+        _ => unreachable!("Arguments are type checked"),
+    }
+}
+
+fn float(value: &Value) -> f64 {
+    match value {
+        Value::Int(int) => *int as f64,
+        Value::Float(float) => *float,
+        Value::Decimal(decimal) => decimal::to_float(decimal),
+        _ => unreachable!("Arguments are type checked"),
+    }
+}
+
+/// A date or timestamp without a time zone as a UTC timestamp, dates at midnight
+fn datetime(value: &Value) -> DateTime<Utc> {
+    match value {
+        Value::Date(date) => date.and_time(NaiveTime::MIN).and_utc(),
+        Value::DateTime(datetime) => *datetime,
+        _ => unreachable!("Arguments are type checked"),
+    }
+}
+
+/// Any date or timestamp, keeping its own time zone and taking the others as UTC
+fn datetime_tz(value: &Value) -> DateTime<FixedOffset> {
+    match value {
+        Value::DateTimeTz(datetime) => *datetime,
+        value => datetime(value).with_timezone(&Utc.fix()),
+    }
+}
+
+fn lower(args: &[Value]) -> Result<Value> {
+    Ok(Value::Text(text(&args[0]).to_lowercase()))
+}
+
+fn upper(args: &[Value]) -> Result<Value> {
+    Ok(Value::Text(text(&args[0]).to_uppercase()))
+}
+
+/// The number of characters in text, or of bytes in a blob
+fn length(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Blob(bytes) => Ok(Value::Int(bytes.len() as i64)),
+        value => Ok(Value::Int(text(value).chars().count() as i64)),
+    }
+}
+
+/// `SUBSTR(text, start [, length])`, counting characters from 1, or bytes
+/// for a blob. Positions before the start count towards the length but
+/// yield nothing
+fn substr(args: &[Value]) -> Result<Value> {
+    let start = int(&args[1]);
+    let end = match args.get(2).map(int) {
+        Some(length) if length < 0 => {
+            return Err(KirinError::InvalidArgument(format!("SUBSTR length {length} must not be negative")));
+        }
+        Some(length) => start.saturating_add(length),
+        None => i64::MAX,
+    };
+
+    let first = start.max(1);
+    let (skip, take) = ((first - 1) as usize, (end - first).max(0) as usize);
+    match &args[0] {
+        Value::Blob(bytes) => Ok(Value::Blob(bytes.iter().skip(skip).take(take).copied().collect())),
+        value => Ok(Value::Text(text(value).chars().skip(skip).take(take).collect())),
+    }
+}
+
+/// `TRIM(text [, characters])`, removing spaces or the given characters from both ends
+fn trim(args: &[Value]) -> Result<Value> {
+    let characters = args.get(1).map_or(" ", text);
+    let trimmed = text(&args[0]).trim_matches(|c| characters.contains(c));
+    Ok(Value::Text(trimmed.to_string()))
+}
+
+fn replace(args: &[Value]) -> Result<Value> {
+    let (source, from, to) = (text(&args[0]), text(&args[1]), text(&args[2]));
+    match from.is_empty() {
+        true => Ok(Value::Text(source.to_string())),
+        false => Ok(Value::Text(source.replace(from, to))),
+    }
+}
+
+/// The bytes of a blob, or of text in UTF-8, as upper case hex digits
+fn hex(args: &[Value]) -> Result<Value> {
+    let bytes = match &args[0] {
+        Value::Blob(bytes) => &bytes[..],
+        value => text(value).as_bytes(),
+    };
+    Ok(Value::Text(to_hex(bytes)))
+}
+
+/// The blob written as hex digits, in either case
+fn unhex(args: &[Value]) -> Result<Value> {
+    let hex = text(&args[0]);
+    parse_hex(hex).map(|bytes| Value::Blob(bytes.into()))
+        .ok_or_else(|| KirinError::InvalidArgument(format!("UNHEX argument '{hex}' must be an even number of hex digits")))
+}
+
+/// Joins the arguments as text, skipping NULLs
+fn concat(args: &[Value]) -> Result<Value> {
+    let joined = args.iter().filter(|arg| !arg.is_null()).map(Value::to_string).collect();
+    Ok(Value::Text(joined))
+}
+
+fn abs(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Int(int) => int.checked_abs().map(Value::Int).ok_or(KirinError::Overflow),
+        Value::Decimal(decimal) => Ok(Value::Decimal(decimal.abs())),
+        value => Ok(Value::Float(float(value).abs())),
+    }
+}
+
+/// `ROUND(number [, digits])`, rounding halves away from zero. Negative
+/// digits round to tens, hundreds and so on
+fn round(args: &[Value]) -> Result<Value> {
+    let digits = args.get(1).map_or(0, int);
+
+    match &args[0] {
+        Value::Int(int) if digits >= 0 => Ok(Value::Int(*int)),
+        Value::Int(int) => {
+            let Some(factor) = u32::try_from(-digits).ok().and_then(|exponent| 10i64.checked_pow(exponent)) else {
+                return Ok(Value::Int(0));
+            };
+            let (truncated, remainder) = (int - int % factor, int % factor);
+            match remainder.abs() >= factor / 2 {
+                true => truncated.checked_add(factor * remainder.signum()).map(Value::Int).ok_or(KirinError::Overflow),
+                false => Ok(Value::Int(truncated)),
+            }
+        }
+        // Decimals keep the digits asked for, or none when rounding to tens and beyond
+        Value::Decimal(decimal) => {
+            let limit = decimal::MAX_PRECISION as i64;
+            let rounded = decimal.with_scale_round(digits.clamp(-limit, limit), RoundingMode::HalfUp);
+            Ok(Value::Decimal(if digits < 0 { rounded.with_scale(0) } else { rounded }))
+        }
+        value => {
+            let value = float(value);
+            let factor = 10f64.powi(digits.clamp(-400, 400) as i32);
+            // Past the range of f64 there is nothing left to round, or nothing left at all
+            let rounded = if factor == 0.0 {
+                0.0
+            } else if factor.is_infinite() {
+                value
+            } else {
+                (value * factor).round() / factor
+            };
+            Ok(Value::Float(if rounded.is_finite() { rounded } else { value }))
+        }
+    }
+}
+
+fn floor(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Int(int) => Ok(Value::Int(*int)),
+        Value::Decimal(decimal) => Ok(Value::Decimal(decimal.with_scale_round(0, RoundingMode::Floor))),
+        value => Ok(Value::Float(float(value).floor())),
+    }
+}
+
+fn ceil(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Int(int) => Ok(Value::Int(*int)),
+        Value::Decimal(decimal) => Ok(Value::Decimal(decimal.with_scale_round(0, RoundingMode::Ceiling))),
+        value => Ok(Value::Float(float(value).ceil())),
+    }
+}
+
+fn power(args: &[Value]) -> Result<Value> {
+    let (base, exponent) = (float(&args[0]), float(&args[1]));
+    if base < 0.0 && exponent.fract() != 0.0 {
+        return Err(KirinError::InvalidArgument(format!("POWER cannot raise {base} to the fractional power {exponent}")));
+    }
+    Ok(Value::Float(base.powf(exponent)))
+}
+
+fn sqrt(args: &[Value]) -> Result<Value> {
+    match float(&args[0]) {
+        value if value < 0.0 => Err(KirinError::InvalidArgument(format!("SQRT of negative number {value}"))),
+        value => Ok(Value::Float(value.sqrt())),
+    }
+}
+
+fn modulo(args: &[Value]) -> Result<Value> {
+    binary(BinaryOp::Mod, args[0].clone(), args[1].clone())
+}
+
+/// When the statement started, so every call in it gives the same time
+fn now(_: &[Expr], scope: &Scope, _: &[Value]) -> Result<Value> {
+    Ok(Value::DateTime(scope.context.map_or_else(Utc::now, |context| context.now())))
+}
+
+/// Timestamps with a time zone are truncated in that zone
+fn date_trunc(args: &[Value]) -> Result<Value> {
+    let field = DateField::parse(text(&args[0]))?;
+    match &args[1] {
+        Value::DateTimeTz(datetime) => datetime::truncate(field, datetime).map(Value::DateTimeTz),
+        value => datetime::truncate(field, &datetime(value)).map(Value::DateTime),
+    }
+}
+
+fn date_part(args: &[Value]) -> Result<Value> {
+    Ok(Value::Int(datetime::extract(DateField::parse(text(&args[0]))?, &datetime_tz(&args[1]))))
+}
+
+fn date_add(args: &[Value]) -> Result<Value> {
+    let unit = DateField::parse(text(&args[2]))?;
+    match &args[0] {
+        Value::DateTimeTz(datetime) => datetime::add(datetime, int(&args[1]), unit).map(Value::DateTimeTz),
+        value => datetime::add(&datetime(value), int(&args[1]), unit).map(Value::DateTime),
+    }
+}
+
+/// Months are counted in the start's time zone
+fn date_diff(args: &[Value]) -> Result<Value> {
+    let start = datetime_tz(&args[1]);
+    let end = datetime_tz(&args[2]).with_timezone(start.offset());
+    datetime::diff(DateField::parse(text(&args[0]))?, &start, &end).map(Value::Int)
+}
+
+fn strftime(args: &[Value]) -> Result<Value> {
+    match &args[1] {
+        Value::DateTimeTz(datetime) => datetime::format(text(&args[0]), datetime).map(Value::Text),
+        value => datetime::format(text(&args[0]), &datetime(value)).map(Value::Text),
+    }
+}
+
+fn strptime(args: &[Value]) -> Result<Value> {
+    datetime::parse(text(&args[0]), text(&args[1])).map(Value::DateTime)
+}
+
+/// The first non-NULL argument; later arguments are not evaluated
+fn coalesce(args: &[Expr], scope: &Scope, row: &[Value]) -> Result<Value> {
+    for arg in args {
+        let value = evaluate(arg, scope, row)?;
+        if !value.is_null() {
+            return Ok(value);
+        }
+    }
+    Ok(Value::Null)
+}
+
+/// NULL if both arguments are equal, otherwise the first
+fn nullif(args: &[Value]) -> Result<Value> {
+    match binary(BinaryOp::Eq, args[0].clone(), args[1].clone())? {
+        Value::Bool(true) => Ok(Value::Null),
+        _ => Ok(args[0].clone()),
+    }
+}
+
+/// `IIF(condition, then, else)`, evaluating only the chosen branch
+fn iif(args: &[Expr], scope: &Scope, row: &[Value]) -> Result<Value> {
+    match evaluate_predicate(&args[0], scope, row)? {
+        true => evaluate(&args[1], scope, row),
+        false => evaluate(&args[2], scope, row),
+    }
+}
+
+/// The largest argument, ignoring NULLs
+fn greatest(args: &[Value]) -> Result<Value> {
+    Ok(args.iter().filter(|arg| !arg.is_null()).max().cloned().unwrap_or(Value::Null))
+}
+
+/// The smallest argument, ignoring NULLs
+fn least(args: &[Value]) -> Result<Value> {
+    Ok(args.iter().filter(|arg| !arg.is_null()).min().cloned().unwrap_or(Value::Null))
+}
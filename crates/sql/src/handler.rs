@@ -1,58 +1,104 @@
-use core::{Column, Value};
-use std::io::{Result, Write};
+use chrono::FixedOffset;
+use core::error::Result;
+use core::{Column, KirinError, Value};
+use std::io::Write;
 
 use database::Database;
 
+use crate::ast::{AlterTableOp, AlterTableStmt, CreateTableStmt, DropTableStmt, InsertStmt, SelectStmt, Statement};
+use crate::eval::{evaluate, Scope};
+use crate::executor::{self, get_table_mut, ResultColumn, ResultSet};
+use crate::lexer::{tokenize, Keyword, Token};
+use crate::parser::parse;
+
 type Writer<'a> = &'a mut dyn Write;
 
-pub fn handle_select<'a>(db: &mut Database, writer: Writer, input: &str) -> Result<()> {
+/// Parses and executes every statement in `input`
+pub fn handle_statement(db: &mut Database, writer: Writer, input: &str) -> Result<()> {
+    for statement in parse(input)? {
+        match statement {
+            Statement::Select(select) => execute_select(db, writer, &select)?,
+            Statement::Explain(select) => {
+                for line in executor::explain_select(db, &select)? {
+                    writeln!(writer, "{line}")?;
+                }
+            }
+            Statement::Insert(insert) => execute_insert(db, writer, &insert)?,
+            Statement::Update(update) => {
+                let count = executor::execute_update(db, &update)?;
+                writeln!(writer, "Updated {count} rows")?;
+            }
+            Statement::Delete(delete) => {
+                let count = executor::execute_delete(db, &delete)?;
+                writeln!(writer, "Deleted {count} rows")?;
+            }
+            Statement::CreateTable(create) => execute_create_table(db, writer, create)?,
+            Statement::DropTable(drop) => execute_drop_table(db, writer, &drop)?,
+            Statement::AlterTable(alter) => execute_alter_table(db, writer, alter)?,
+            Statement::SetTimeZone(zone) => {
+                db.set_time_zone(zone);
+                writeln!(writer, "Time zone set to {zone}")?;
+            }
+            Statement::ShowTimeZone => writeln!(writer, "{}", db.time_zone())?,
+        }
+    }
 
-    // Expected format:
-    // "SELECT * FROM <table>"
+    Ok(())
+}
 
-    let input = input.trim();
+pub fn handle_select(db: &mut Database, writer: Writer, input: &str) -> Result<()> {
+    expect_leading_keyword(input, Keyword::Select)?;
+    handle_statement(db, writer, input)
+}
 
-    let input_upper = input.to_uppercase();
-    if !input_upper.starts_with("SELECT") {
-        return writeln!(writer, "Malformed SELECT command")
-    }
+pub fn handle_insert(db: &mut Database, writer: Writer, input: &str) -> Result<()> {
+    expect_leading_keyword(input, Keyword::Insert)?;
+    handle_statement(db, writer, input)
+}
 
-    // Very naive split (stub only)
-    let parts: Vec<&str> = input.split_whitespace().collect();
-    if parts.len() < 4 {
-        return writeln!(writer, "Expected syntax: SELECT * FROM <table>")
+/// Checks that `input` starts with the given keyword
+fn expect_leading_keyword(input: &str, keyword: Keyword) -> Result<()> {
+    let first = tokenize(input)?.swap_remove(0);
+    if first.token == Token::Keyword(keyword) {
+        return Ok(());
     }
 
-    // Check for FROM keyword
-    if parts.get(2).unwrap().trim() != "FROM" {
-        return writeln!(writer, "Missing FROM clause")
-    }
+    let message = format!("Expected {keyword}, found '{}'", first.token);
+    Err(KirinError::Parse { message, span: first.span })
+}
 
-    // Check table name matches
-    let table_name = *parts.get(3).unwrap_or(&"");
-    let Some(table) = db.get_table(table_name.to_string()) else {
-        return writeln!(writer, "Table '{table_name}' not found")
-    };
+fn execute_select(db: &mut Database, writer: Writer, select: &SelectStmt) -> Result<()> {
+    let result = executor::execute_select(db, select)?;
+    print_result(writer, &result, db.time_zone())
+}
+
+/// Prints a result set as a table, preceded by its row count. Timestamps with
+/// a time zone are shown in `time_zone`
+fn print_result(writer: Writer, result: &ResultSet, time_zone: FixedOffset) -> Result<()> {
 
     // Print table information
-    let row_count = table.iter().count();
+    let row_count = result.rows.len();
     writeln!(writer, "({row_count} rows)")?;
 
     // Print schema
-    let column_schema = table.columns.iter()
-        .map(|Column{ name, col_type }| format!("{name} <{col_type}>"))
+    let column_schema = result.columns.iter()
+        .map(|ResultColumn{ name, col_type }| format!("{name} <{col_type}>"))
         .fold(String::from("|"), |acc, x| format!("{acc} {x} |"));
     writeln!(writer, "{column_schema}")?;
 
     // Print spacer
-    let row_spacer = table.columns.iter()
+    let row_spacer = result.columns.iter()
         .map(|_| String::from(" --- "))
         .fold(String::from("|"), |acc, x| format!("{acc} {x} |"));
     writeln!(writer, "{row_spacer}")?;
 
-    // Print all rows        
-    for row in table.iter() {
-        let row_str = row.values.iter()
+    // Print all rows
+    for row in &result.rows {
+        let row_str = row.iter()
+        .map(|value| match value {
+            Value::DateTimeTz(datetime) => Value::DateTimeTz(datetime.with_timezone(&time_zone)).to_string(),
+            value => value.to_string(),
+        })
         .fold(String::from("|"), |acc, x| format!("{acc} {x} |"));
         writeln!(writer, "{row_str}")?
     }
@@ -60,59 +106,104 @@ pub fn handle_select<'a>(db: &mut Database, writer: Writer, input: &str) -> Resu
     Ok(())
 }
 
-    pub fn handle_insert(db: &mut Database, writer: Writer, input: &str) -> Result<()> {
+fn execute_insert(db: &mut Database, writer: Writer, insert: &InsertStmt) -> Result<()> {
 
-        // Expected format:
-        // INSERT INTO users VALUES ('Alice', 170.5)
+    // Values are evaluated up front, since subqueries in them read the database
+    let scope = Scope::empty().with_database(db);
+    let rows = insert.rows.iter()
+        .map(|exprs| exprs.iter().map(|expr| evaluate(expr, &scope, &[])).collect::<Result<Vec<Value>>>())
+        .collect::<Result<Vec<_>>>()?;
 
-        let input = input.trim();
+    let table = get_table_mut(db, &insert.table)?;
 
-        if !input.to_uppercase().starts_with("INSERT INTO") {
-            return writeln!(writer, "Malformed INSERT command")
-        }
+    for mut values in rows {
+
+        // Reorder values from the column list into table order
+        if let Some(columns) = &insert.columns {
+            if columns.len() != values.len() {
+                return Err(KirinError::ArityMismatch { expected: columns.len(), actual: values.len() })
+            }
+
+            if let Some(unknown) = columns.iter().find(|name| !table.columns.iter().any(|col| col.name.eq_ignore_ascii_case(name))) {
+                return Err(KirinError::UnknownColumn(unknown.clone()))
+            }
 
-        // Very naive split (stub only)
-        let parts: Vec<&str> = input.split_whitespace().collect();
-        if parts.len() < 4 {
-            return writeln!(writer, "Expected syntax: INSERT INTO <table> VALUES (...)")
+            // Columns left out of the list are NULL
+            values = table.columns.iter()
+                .map(|column| match columns.iter().position(|name| name.eq_ignore_ascii_case(&column.name)) {
+                    Some(index) => values[index].clone(),
+                    None => Value::Null,
+                })
+                .collect();
         }
 
-        // We are assuming it is in the correct order
-        let table_name = parts[2];
-        let Some(table) = db.get_table(table_name.to_string()) else {
-            return writeln!(writer, "Table '{table_name}' not found")
-        };
+        let row_id = table.insert(values)?;
+        writeln!(writer, "Inserted row with id {row_id}")?;
+    }
 
+    Ok(())
+}
 
-        // Convert input *eagerly* into typed values
-        let values_str = input.split("VALUES").nth(1).unwrap_or("").trim().trim_start_matches('(').trim_end_matches(')');
-        if values_str.is_empty() {
-            return writeln!(writer, "No values provided")
-        }
+fn execute_create_table(db: &mut Database, writer: Writer, create: CreateTableStmt) -> Result<()> {
 
-        let values: Vec<Value> = values_str.split(",").map(|s| eagerly_convert_to_value(s.trim())).collect();
+    if create.if_not_exists && db.has_table(&create.name) {
+        writeln!(writer, "Table '{}' already exists, skipping", create.name)?;
+        return Ok(())
+    }
 
+    let columns = create.columns.into_iter()
+        .map(Column::from)
+        .collect();
 
-        if let Some(row_id) = table.insert(values) {
-            return writeln!(writer, "Inserted row with id {row_id}")
-        };
+    db.create_table(&create.name, columns)?;
+    writeln!(writer, "Created table '{}'", create.name)?;
 
-        writeln!(writer, "Insert failed: type mismatch or column count mismatch")?;
+    Ok(())
+}
 
-        Ok(())
+fn execute_drop_table(db: &mut Database, writer: Writer, drop: &DropTableStmt) -> Result<()> {
+
+    if drop.if_exists && !db.has_table(&drop.name) {
+        writeln!(writer, "Table '{}' does not exist, skipping", drop.name)?;
+        return Ok(())
     }
 
+    db.drop_table(&drop.name)?;
+    writeln!(writer, "Dropped table '{}'", drop.name)?;
+
+    Ok(())
+}
+
+fn execute_alter_table(db: &mut Database, writer: Writer, alter: AlterTableStmt) -> Result<()> {
 
-/// This is obviously a bad way of doing this.
-/// TODO: Literally anything else
-fn eagerly_convert_to_value(input: &str) -> Value {
-    if let Ok(integer) = input.parse::<i64>() {
-        return Value::Int(integer);
+    let table_name = alter.name;
+
+    match alter.operation {
+        AlterTableOp::RenameTable { new_name } => {
+            db.rename_table(&table_name, &new_name)?;
+            writeln!(writer, "Renamed table '{table_name}' to '{new_name}'")?;
+        }
 
-    } else if let Ok(float) = input.parse::<f64>() {
-        return Value::Float(float);
+        AlterTableOp::AddColumn { column, default } => {
+            let default = default.map(|expr| evaluate(&expr, &Scope::empty(), &[])).transpose()?;
 
-    } else {
-        return Value::Text(input.to_string())
+            let name = column.name.clone();
+            let column = Column::from(column);
+            get_table_mut(db, &table_name)?.add_column(column, default)?;
+            writeln!(writer, "Added column '{name}' to '{table_name}'")?;
+        }
+
+        AlterTableOp::DropColumn { name } => {
+            get_table_mut(db, &table_name)?.drop_column(&name)?;
+            writeln!(writer, "Dropped column '{name}' from '{table_name}'")?;
+        }
+
+        AlterTableOp::RenameColumn { name, new_name } => {
+            get_table_mut(db, &table_name)?.rename_column(&name, &new_name)?;
+            writeln!(writer, "Renamed column '{name}' to '{new_name}'")?;
+        }
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
+
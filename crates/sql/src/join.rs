@@ -0,0 +1,338 @@
+use core::error::Result;
+use core::{KirinError, Value, ValueType};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::ast::{BinaryOp, ColumnRef, Expr, JoinConstraint, JoinKind};
+use crate::eval::{common_type, evaluate, evaluate_predicate, infer_type, Scope, ScopeColumn};
+
+/// Rows flowing through a query, along with the scope describing their columns
+#[derive(Debug, Clone, Default)]
+pub struct Relation<'a> {
+    pub scope: Scope<'a>,
+    pub rows: Vec<Vec<Value>>,
+    /// Columns the rows are known to be sorted by, ascending with NULLs first
+    pub sorted_by: Vec<usize>,
+}
+
+/// How matching rows of the two join inputs are found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinStrategy {
+    /// Compares every left row with every right row; works for any condition
+    NestedLoop,
+    /// Builds a hash table over the right input's join keys and probes it with the left
+    Hash,
+    /// Walks both inputs in step, used when both are known to be sorted by their join keys
+    Merge,
+}
+
+impl Display for JoinStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            JoinStrategy::NestedLoop => "Nested Loop Join",
+            JoinStrategy::Hash => "Hash Join",
+            JoinStrategy::Merge => "Merge Join",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Combines two relations, returning the joined rows and the strategy used.
+///
+/// Equality conditions between the two sides (`ON a.x = b.y AND ...` or
+/// `USING (...)`) are used as join keys, for a hash join or, when both inputs
+/// are known to be sorted by their keys, a merge join. The strategy depends
+/// only on the inputs' scopes and orderings, not on their rows. Any other condition falls
+/// back to a nested loop. Rows without a partner in an outer join are padded
+/// with NULLs.
+///
+/// A USING join puts one merged column per USING name in front of both sides'
+/// columns, which stay reachable through their table names but are left out of `*`
+pub fn join<'a>(left: Relation<'a>, right: Relation<'a>, kind: JoinKind, constraint: &JoinConstraint) -> Result<(Relation<'a>, JoinStrategy)> {
+    let mut columns = Vec::new();
+    let mut using = Vec::new();
+    let mut keys = Vec::new();
+    let mut residual = None;
+
+    match constraint {
+        JoinConstraint::Using(names) => {
+            let (mut left_scope, mut right_scope) = (left.scope.clone(), right.scope.clone());
+
+            for name in names {
+                let column = ColumnRef { table: None, name: name.clone() };
+                let (l, r) = (left.scope.resolve(&column)?, right.scope.resolve(&column)?);
+                let (left_type, right_type) = (left.scope.columns[l].col_type, right.scope.columns[r].col_type);
+
+                let col_type = common_type(left_type, right_type).ok_or_else(|| KirinError::InvalidOperands {
+                    op: BinaryOp::Eq.to_string(),
+                    operands: vec![left_type, right_type],
+                })?;
+
+                left_scope.columns[l].hidden = true;
+                right_scope.columns[r].hidden = true;
+                columns.push(ScopeColumn { table: None, name: left.scope.columns[l].name.clone(), col_type, hidden: false, depth: 0 });
+                using.push((l, r));
+                keys.push((Expr::Column(column.clone()), Expr::Column(column)));
+            }
+
+            columns.extend(left_scope.columns);
+            columns.extend(right_scope.columns);
+        }
+        JoinConstraint::On(_) | JoinConstraint::None => {
+            columns.extend(left.scope.columns.iter().cloned());
+            columns.extend(right.scope.columns.iter().cloned());
+        }
+    }
+
+    let scope = Scope { columns, context: left.scope.context.or(right.scope.context) };
+    if let JoinConstraint::On(on) = constraint {
+        match infer_type(on, &scope)? {
+            ValueType::Bool | ValueType::Null => {}
+            actual => return Err(KirinError::TypeMismatch { column: on.to_string(), expected: ValueType::Bool, actual }),
+        }
+        (keys, residual) = split_join_keys(on, &scope, left.scope.columns.len())?;
+    }
+
+    let strategy = if keys.is_empty() {
+        JoinStrategy::NestedLoop
+    } else if is_sorted_by(keys.iter().map(|(key, _)| key), &left) && is_sorted_by(keys.iter().map(|(_, key)| key), &right) {
+        JoinStrategy::Merge
+    } else {
+        JoinStrategy::Hash
+    };
+
+    let left_keys = key_values(keys.iter().map(|(key, _)| key), &left)?;
+    let right_keys = key_values(keys.iter().map(|(_, key)| key), &right)?;
+
+    // For ON joins the condition is evaluated over both rows side by side
+    let accepts = |l: &[Value], r: &[Value]| match &residual {
+        Some(condition) => evaluate_predicate(condition, &scope, &[l, r].concat()),
+        None => Ok(true),
+    };
+
+    let matches = match strategy {
+        JoinStrategy::NestedLoop => nested_loop_matches(&left.rows, &right.rows, accepts)?,
+        JoinStrategy::Hash => hash_matches(&left, &right, &left_keys, &right_keys, accepts)?,
+        JoinStrategy::Merge => merge_matches(&left, &right, &left_keys, &right_keys, accepts)?,
+    };
+
+    let (left_width, right_width) = (left.scope.columns.len(), right.scope.columns.len());
+    let combine = |l: Option<&Vec<Value>>, r: Option<&Vec<Value>>| {
+        let mut row = Vec::with_capacity(using.len() + left_width + right_width);
+        for (li, ri) in &using {
+            let merged = l.map(|l| &l[*li]).filter(|value| !value.is_null()).or(r.map(|r| &r[*ri]));
+            row.push(merged.cloned().unwrap_or(Value::Null));
+        }
+        match l {
+            Some(l) => row.extend(l.iter().cloned()),
+            None => row.extend((0..left_width).map(|_| Value::Null)),
+        }
+        match r {
+            Some(r) => row.extend(r.iter().cloned()),
+            None => row.extend((0..right_width).map(|_| Value::Null)),
+        }
+        row
+    };
+
+    let mut rows = Vec::new();
+    let mut right_matched = vec![false; right.rows.len()];
+
+    for (l, partners) in left.rows.iter().zip(&matches) {
+        for r in partners {
+            right_matched[*r] = true;
+            rows.push(combine(Some(l), Some(&right.rows[*r])));
+        }
+
+        if partners.is_empty() && matches!(kind, JoinKind::Left | JoinKind::Full) {
+            rows.push(combine(Some(l), None));
+        }
+    }
+
+    if matches!(kind, JoinKind::Right | JoinKind::Full) {
+        for (r, matched) in right.rows.iter().zip(right_matched) {
+            if !matched {
+                rows.push(combine(None, Some(r)));
+            }
+        }
+    }
+
+    Ok((Relation { scope, rows, sorted_by: Vec::new() }, strategy))
+}
+
+/// Expressions over the left and right input that must be equal for rows to match
+type JoinKeys = Vec<(Expr, Expr)>;
+
+/// Splits an ON condition into equality keys `(left expr, right expr)` and the
+/// remaining conditions, which are checked for every pair of key-matched rows
+fn split_join_keys(on: &Expr, scope: &Scope, left_width: usize) -> Result<(JoinKeys, Option<Expr>)> {
+    let mut keys = Vec::new();
+    let mut rest = Vec::new();
+
+    for condition in conjuncts(on) {
+        if let Expr::Binary { left, op: BinaryOp::Eq, right } = condition {
+            match (side(left, scope, left_width)?, side(right, scope, left_width)?) {
+                (Some(Side::Left), Some(Side::Right)) => {
+                    keys.push((*left.clone(), *right.clone()));
+                    continue;
+                }
+                (Some(Side::Right), Some(Side::Left)) => {
+                    keys.push((*right.clone(), *left.clone()));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        rest.push(condition.clone());
+    }
+
+    let residual = rest.into_iter().reduce(|left, right| Expr::Binary {
+        left: Box::new(left),
+        op: BinaryOp::And,
+        right: Box::new(right),
+    });
+    Ok((keys, residual))
+}
+
+/// The operands of a chain of ANDs
+fn conjuncts(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Binary { left, op: BinaryOp::And, right } => [conjuncts(left), conjuncts(right)].concat(),
+        Expr::Nested(expr) => conjuncts(expr),
+        expr => vec![expr],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Which join input an expression reads from, or None when it reads both or neither
+fn side(expr: &Expr, scope: &Scope, left_width: usize) -> Result<Option<Side>> {
+    let mut sides = Vec::new();
+    collect_sides(expr, scope, left_width, &mut sides)?;
+
+    match sides.first() {
+        Some(first) if sides.iter().all(|side| side == first) => Ok(Some(*first)),
+        _ => Ok(None),
+    }
+}
+
+fn collect_sides(expr: &Expr, scope: &Scope, left_width: usize, sides: &mut Vec<Side>) -> Result<()> {
+    if let Expr::Column(column) = expr {
+        let side = if scope.resolve(column)? < left_width { Side::Left } else { Side::Right };
+        sides.push(side);
+    }
+    expr.children().into_iter().try_for_each(|child| collect_sides(child, scope, left_width, sides))
+}
+
+/// Evaluates the join keys of every row of one input
+fn key_values<'a>(keys: impl Iterator<Item = &'a Expr> + Clone, input: &Relation) -> Result<Vec<Vec<Value>>> {
+    input.rows.iter()
+        .map(|row| keys.clone().map(|key| evaluate(key, &input.scope, row)).collect())
+        .collect()
+}
+
+/// Whether the rows of `input` are known to be sorted by `keys`, which must be
+/// its leading sort columns in the same order
+fn is_sorted_by<'e>(keys: impl Iterator<Item = &'e Expr>, input: &Relation) -> bool {
+    keys.enumerate().all(|(position, key)| match key {
+        Expr::Column(column) => input.scope.resolve(column).ok().is_some_and(|index| input.sorted_by.get(position) == Some(&index)),
+        _ => false,
+    })
+}
+
+/// Keys containing NULL never compare equal, so their rows never match
+fn has_null(key: &[Value]) -> bool {
+    key.iter().any(Value::is_null)
+}
+
+/// The right row positions matching each left row
+type Matches = Vec<Vec<usize>>;
+
+fn nested_loop_matches(
+    left: &[Vec<Value>],
+    right: &[Vec<Value>],
+    accepts: impl Fn(&[Value], &[Value]) -> Result<bool>,
+) -> Result<Matches> {
+    let mut matches = Vec::with_capacity(left.len());
+    for l in left {
+        let mut partners = Vec::new();
+        for (index, r) in right.iter().enumerate() {
+            if accepts(l, r)? {
+                partners.push(index);
+            }
+        }
+        matches.push(partners);
+    }
+    Ok(matches)
+}
+
+fn hash_matches(
+    left: &Relation,
+    right: &Relation,
+    left_keys: &[Vec<Value>],
+    right_keys: &[Vec<Value>],
+    accepts: impl Fn(&[Value], &[Value]) -> Result<bool>,
+) -> Result<Matches> {
+    let mut table: HashMap<&[Value], Vec<usize>> = HashMap::new();
+    for (index, key) in right_keys.iter().enumerate() {
+        if !has_null(key) {
+            table.entry(key).or_default().push(index);
+        }
+    }
+
+    let mut matches = Vec::with_capacity(left.rows.len());
+    for (l, key) in left.rows.iter().zip(left_keys) {
+        let mut partners = Vec::new();
+        if let Some(candidates) = table.get(key.as_slice()) {
+            for index in candidates {
+                if accepts(l, &right.rows[*index])? {
+                    partners.push(*index);
+                }
+            }
+        }
+        matches.push(partners);
+    }
+    Ok(matches)
+}
+
+/// Both inputs must be sorted by their keys
+fn merge_matches(
+    left: &Relation,
+    right: &Relation,
+    left_keys: &[Vec<Value>],
+    right_keys: &[Vec<Value>],
+    accepts: impl Fn(&[Value], &[Value]) -> Result<bool>,
+) -> Result<Matches> {
+    let mut matches = vec![Vec::new(); left.rows.len()];
+    let (mut i, mut j) = (0, 0);
+
+    while i < left_keys.len() && j < right_keys.len() {
+        match left_keys[i].cmp(&right_keys[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                let run_end = |keys: &[Vec<Value>], start: usize| {
+                    start + keys[start..].iter().take_while(|key| **key == keys[start]).count()
+                };
+                let (left_end, right_end) = (run_end(left_keys, i), run_end(right_keys, j));
+
+                if !has_null(&left_keys[i]) {
+                    for (l, partners) in matches.iter_mut().enumerate().take(left_end).skip(i) {
+                        for r in j..right_end {
+                            if accepts(&left.rows[l], &right.rows[r])? {
+                                partners.push(r);
+                            }
+                        }
+                    }
+                }
+
+                (i, j) = (left_end, right_end);
+            }
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,495 @@
+use core::error::Result;
+use core::{parse_hex, to_hex, KirinError};
+use std::fmt::Display;
+
+pub use core::Span;
+
+/// Reserved words of the SQL dialect.
+///
+/// Keywords are matched case-insensitively and can only be used as
+/// identifiers when quoted (e.g. `"select"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keyword {
+    All,
+    Alter,
+    And,
+    As,
+    Asc,
+    By,
+    Case,
+    Create,
+    Cross,
+    Delete,
+    Desc,
+    Distinct,
+    Drop,
+    Else,
+    End,
+    Except,
+    Exists,
+    Explain,
+    False,
+    From,
+    Full,
+    Glob,
+    Group,
+    Having,
+    If,
+    Ilike,
+    In,
+    Inner,
+    Insert,
+    Intersect,
+    Into,
+    Is,
+    Join,
+    Left,
+    Like,
+    Limit,
+    Not,
+    Null,
+    Offset,
+    On,
+    Or,
+    Order,
+    Outer,
+    Recursive,
+    Regexp,
+    Right,
+    Select,
+    Set,
+    Table,
+    Then,
+    True,
+    Union,
+    Update,
+    Using,
+    Values,
+    When,
+    Where,
+    With,
+}
+
+impl Keyword {
+    fn lookup(word: &str) -> Option<Self> {
+        let keyword = match word.to_ascii_uppercase().as_str() {
+            "ALL" => Keyword::All,
+            "ALTER" => Keyword::Alter,
+            "AND" => Keyword::And,
+            "AS" => Keyword::As,
+            "ASC" => Keyword::Asc,
+            "BY" => Keyword::By,
+            "CASE" => Keyword::Case,
+            "CREATE" => Keyword::Create,
+            "CROSS" => Keyword::Cross,
+            "DELETE" => Keyword::Delete,
+            "DESC" => Keyword::Desc,
+            "DISTINCT" => Keyword::Distinct,
+            "DROP" => Keyword::Drop,
+            "ELSE" => Keyword::Else,
+            "END" => Keyword::End,
+            "EXCEPT" => Keyword::Except,
+            "EXISTS" => Keyword::Exists,
+            "EXPLAIN" => Keyword::Explain,
+            "FALSE" => Keyword::False,
+            "FROM" => Keyword::From,
+            "FULL" => Keyword::Full,
+            "GLOB" => Keyword::Glob,
+            "GROUP" => Keyword::Group,
+            "HAVING" => Keyword::Having,
+            "IF" => Keyword::If,
+            "ILIKE" => Keyword::Ilike,
+            "IN" => Keyword::In,
+            "INNER" => Keyword::Inner,
+            "INSERT" => Keyword::Insert,
+            "INTERSECT" => Keyword::Intersect,
+            "INTO" => Keyword::Into,
+            "IS" => Keyword::Is,
+            "JOIN" => Keyword::Join,
+            "LEFT" => Keyword::Left,
+            "LIKE" => Keyword::Like,
+            "LIMIT" => Keyword::Limit,
+            "NOT" => Keyword::Not,
+            "NULL" => Keyword::Null,
+            "OFFSET" => Keyword::Offset,
+            "ON" => Keyword::On,
+            "OR" => Keyword::Or,
+            "ORDER" => Keyword::Order,
+            "OUTER" => Keyword::Outer,
+            "RECURSIVE" => Keyword::Recursive,
+            "REGEXP" => Keyword::Regexp,
+            "RIGHT" => Keyword::Right,
+            "SELECT" => Keyword::Select,
+            "SET" => Keyword::Set,
+            "TABLE" => Keyword::Table,
+            "THEN" => Keyword::Then,
+            "TRUE" => Keyword::True,
+            "UNION" => Keyword::Union,
+            "UPDATE" => Keyword::Update,
+            "USING" => Keyword::Using,
+            "VALUES" => Keyword::Values,
+            "WHEN" => Keyword::When,
+            "WHERE" => Keyword::Where,
+            "WITH" => Keyword::With,
+            _ => return None,
+        };
+        Some(keyword)
+    }
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = format!("{self:?}").to_ascii_uppercase();
+        f.write_str(&text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Keyword(Keyword),
+
+    /// Bare or double-quoted identifier
+    Ident(String),
+
+    // Literals
+    String(String),
+    /// `X'...'`, bytes written as hex digits
+    Blob(Vec<u8>),
+    Integer(i64),
+    /// A number with a decimal point or too large for an Integer, kept as
+    /// written so it can be read exactly
+    Decimal(String),
+    /// A number with an exponent, e.g. `1.5e3`
+    Float(f64),
+
+    // Punctuation
+    Comma,
+    Dot,
+    Semicolon,
+    LParen,
+    RParen,
+
+    // Operators
+    Star,
+    Plus,
+    Minus,
+    Slash,
+    Percent,
+    Concat,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Tilde,
+    DoubleColon,
+
+    /// End of input
+    Eof,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Keyword(keyword) => write!(f, "{keyword}"),
+            Token::Ident(ident) => write!(f, "{ident}"),
+            Token::String(str) => write!(f, "'{}'", str.replace('\'', "''")),
+            Token::Blob(bytes) => write!(f, "X'{}'", to_hex(bytes)),
+            Token::Integer(int) => write!(f, "{int}"),
+            Token::Decimal(text) => f.write_str(text),
+            Token::Float(float) => write!(f, "{float}"),
+            Token::Comma => f.write_str(","),
+            Token::Dot => f.write_str("."),
+            Token::Semicolon => f.write_str(";"),
+            Token::LParen => f.write_str("("),
+            Token::RParen => f.write_str(")"),
+            Token::Star => f.write_str("*"),
+            Token::Plus => f.write_str("+"),
+            Token::Minus => f.write_str("-"),
+            Token::Slash => f.write_str("/"),
+            Token::Percent => f.write_str("%"),
+            Token::Concat => f.write_str("||"),
+            Token::Eq => f.write_str("="),
+            Token::NotEq => f.write_str("<>"),
+            Token::Lt => f.write_str("<"),
+            Token::LtEq => f.write_str("<="),
+            Token::Gt => f.write_str(">"),
+            Token::GtEq => f.write_str(">="),
+            Token::Tilde => f.write_str("~"),
+            Token::DoubleColon => f.write_str("::"),
+            Token::Eof => f.write_str("end of input"),
+        }
+    }
+}
+
+/// A token together with where it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+/// Splits SQL source text into tokens
+pub struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// Characters consumed so far, which unlike the column keeps counting
+    /// across newlines
+    offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            line: 1,
+            column: 1,
+            offset: 0,
+        }
+    }
+
+    /// Tokenizes the whole input.
+    ///
+    /// The returned tokens always end with [`Token::Eof`]
+    pub fn tokenize(mut self) -> Result<Vec<SpannedToken>> {
+        let mut tokens = Vec::new();
+
+        loop {
+            self.skip_whitespace_and_comments()?;
+
+            let (line, column, offset) = (self.line, self.column, self.offset);
+            let token = self.next_token()?;
+            let span = Span { line, column, len: (self.offset - offset).max(1) };
+
+            let done = token == Token::Eof;
+            tokens.push(SpannedToken { token, span });
+            if done {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        self.offset += 1;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn bump_if(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.bump();
+            return true;
+        }
+        false
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> KirinError {
+        let span = Span { line: self.line, column: self.column, len: 1 };
+        KirinError::Parse { message: message.into(), span }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(ch) if ch.is_whitespace() => {
+                    self.bump();
+                }
+
+                // Line comment: -- ...
+                Some('-') if self.lookahead(1) == Some('-') => {
+                    while let Some(ch) = self.bump() {
+                        if ch == '\n' {
+                            break;
+                        }
+                    }
+                }
+
+                // Block comment: /* ... */
+                Some('/') if self.lookahead(1) == Some('*') => {
+                    let start = self.error_here("Unterminated block comment");
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match self.bump() {
+                            Some('*') if self.peek() == Some('/') => {
+                                self.bump();
+                                break;
+                            }
+                            Some(_) => (),
+                            None => return Err(start),
+                        }
+                    }
+                }
+
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Looks `n` characters past the current one without consuming anything
+    fn lookahead(&self, n: usize) -> Option<char> {
+        self.chars.clone().nth(n)
+    }
+
+    fn next_token(&mut self) -> Result<Token> {
+        let Some(ch) = self.peek() else {
+            return Ok(Token::Eof);
+        };
+
+        if ch.is_ascii_digit() || (ch == '.' && self.lookahead(1).is_some_and(|c| c.is_ascii_digit())) {
+            return self.number();
+        }
+
+        if matches!(ch, 'x' | 'X') && self.lookahead(1) == Some('\'') {
+            return self.blob();
+        }
+
+        if ch.is_alphabetic() || ch == '_' {
+            return Ok(self.word());
+        }
+
+        match ch {
+            '\'' => return self.string(),
+            '"' => return self.quoted_ident(),
+            _ => (),
+        }
+
+        let error = self.error_here(format!("Unexpected character '{ch}'"));
+        self.bump();
+
+        let token = match ch {
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            ';' => Token::Semicolon,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '*' => Token::Star,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '=' => {
+                self.bump_if('=');
+                Token::Eq
+            }
+            '!' if self.bump_if('=') => Token::NotEq,
+            '<' if self.bump_if('=') => Token::LtEq,
+            '<' if self.bump_if('>') => Token::NotEq,
+            '<' => Token::Lt,
+            '>' if self.bump_if('=') => Token::GtEq,
+            '>' => Token::Gt,
+            '|' if self.bump_if('|') => Token::Concat,
+            '~' => Token::Tilde,
+            ':' if self.bump_if(':') => Token::DoubleColon,
+            _ => return Err(error),
+        };
+        Ok(token)
+    }
+
+    fn number(&mut self) -> Result<Token> {
+        let start = self.error_here("Invalid numeric literal");
+        let mut text = String::new();
+        let (mut is_decimal, mut is_float) = (false, false);
+
+        while let Some(ch) = self.peek() {
+            match ch {
+                '0'..='9' => text.push(ch),
+                '.' if !is_decimal && !is_float => {
+                    is_decimal = true;
+                    text.push(ch);
+                }
+                'e' | 'E' => {
+                    is_float = true;
+                    text.push(ch);
+                    self.bump();
+                    if let Some(sign @ ('+' | '-')) = self.peek() {
+                        text.push(sign);
+                    } else {
+                        continue;
+                    }
+                }
+                _ => break,
+            }
+            self.bump();
+        }
+
+        if !is_float && let Ok(int) = text.parse::<i64>() {
+            return Ok(Token::Integer(int));
+        }
+        // Integers too large for i64 are kept exact as decimals
+        if !is_float {
+            return Ok(Token::Decimal(text));
+        }
+
+        text.parse::<f64>().map(Token::Float).map_err(|_| start)
+    }
+
+    fn word(&mut self) -> Token {
+        let mut text = String::new();
+        while let Some(ch) = self.peek() {
+            if !(ch.is_alphanumeric() || ch == '_') {
+                break;
+            }
+            text.push(ch);
+            self.bump();
+        }
+
+        match Keyword::lookup(&text) {
+            Some(keyword) => Token::Keyword(keyword),
+            None => Token::Ident(text),
+        }
+    }
+
+    /// Reads a string delimited by `quote`, where a doubled quote is an escaped quote
+    fn delimited(&mut self, quote: char, what: &str) -> Result<String> {
+        let start = self.error_here(format!("Unterminated {what}"));
+        self.bump();
+
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                Some(ch) if ch == quote => {
+                    if !self.bump_if(quote) {
+                        return Ok(text);
+                    }
+                    text.push(quote);
+                }
+                Some(ch) => text.push(ch),
+                None => return Err(start),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<Token> {
+        self.delimited('\'', "string literal").map(Token::String)
+    }
+
+    /// `X'DEADBEEF'`: an even number of hex digits, in either case
+    fn blob(&mut self) -> Result<Token> {
+        let error = self.error_here("Blob literal must hold an even number of hex digits");
+        self.bump();
+        let hex = self.delimited('\'', "blob literal")?;
+        parse_hex(&hex).map(Token::Blob).ok_or(error)
+    }
+
+    fn quoted_ident(&mut self) -> Result<Token> {
+        self.delimited('"', "quoted identifier").map(Token::Ident)
+    }
+}
+
+/// Convenience wrapper around [`Lexer::tokenize`]
+pub fn tokenize(input: &str) -> Result<Vec<SpannedToken>> {
+    Lexer::new(input).tokenize()
+}
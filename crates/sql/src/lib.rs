@@ -1 +1,15 @@
-pub mod handler;
\ No newline at end of file
+pub mod aggregate;
+pub mod ast;
+pub mod datetime;
+pub mod eval;
+pub mod executor;
+pub mod functions;
+pub mod handler;
+pub mod join;
+pub mod lexer;
+pub mod parser;
+pub mod pattern;
+pub mod subquery;
+pub mod window;
+
+pub use parser::parse;
@@ -0,0 +1,1093 @@
+use bigdecimal::BigDecimal;
+use chrono::{FixedOffset, Offset, Utc};
+use core::error::Result;
+use core::{decimal, parse_time_zone, KirinError, Value, ValueType};
+
+use crate::aggregate::is_aggregate;
+use crate::ast::{
+    AlterTableOp, AlterTableStmt, Assignment, BinaryOp, ColumnDef, ColumnRef, Compound, CreateTableStmt, Cte, DeleteStmt, Distinct,
+    DropTableStmt, Expr, FrameBound, FrameUnits, FunctionCall, InsertStmt, Join, JoinConstraint, JoinKind, MatchOp, OrderByExpr, SelectItem, SelectStmt, SetOperator, Statement,
+    TableExpr, TableRef, UnaryOp, UpdateStmt, WindowFrame, WindowSpec, With,
+};
+use crate::lexer::{tokenize, Keyword, Span, SpannedToken, Token};
+
+/// Parses a string of one or more `;` separated statements
+pub fn parse(input: &str) -> Result<Vec<Statement>> {
+    Parser::new(input)?.parse_statements()
+}
+
+/// Parses a single expression
+pub fn parse_expr(input: &str) -> Result<Expr> {
+    let mut parser = Parser::new(input)?;
+    let expr = parser.parse_expr()?;
+    parser.expect(&Token::Eof)?;
+    Ok(expr)
+}
+
+/// Units that may follow the text of an interval literal, as in `INTERVAL '2' HOUR`
+const INTERVAL_UNITS: [&str; 6] = ["YEAR", "MONTH", "DAY", "HOUR", "MINUTE", "SECOND"];
+
+/// Binding power of binary operators, higher binds tighter
+mod precedence {
+    pub const OR: u8 = 1;
+    pub const AND: u8 = 2;
+    pub const NOT: u8 = 3;
+    pub const COMPARISON: u8 = 4;
+    pub const CONCAT: u8 = 5;
+    pub const ADDITIVE: u8 = 6;
+    pub const MULTIPLICATIVE: u8 = 7;
+    pub const UNARY: u8 = 8;
+    pub const CAST: u8 = 9;
+}
+
+/// Recursive descent parser over the token stream
+pub struct Parser {
+    tokens: Vec<SpannedToken>,
+    pos: usize,
+}
+
+impl Parser {
+    pub fn new(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        Ok(Self { tokens, pos: 0 })
+    }
+
+    // ---------------------------------------------------------------------
+    // Token helpers
+    // ---------------------------------------------------------------------
+
+    fn peek(&self) -> &Token {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        let index = (self.pos + n).min(self.tokens.len() - 1);
+        &self.tokens[index].token
+    }
+
+    fn span(&self) -> Span {
+        self.tokens[self.pos].span
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].token.clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Consumes the next token if it matches
+    fn consume(&mut self, token: &Token) -> bool {
+        if self.peek() == token {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    fn consume_keyword(&mut self, keyword: Keyword) -> bool {
+        self.consume(&Token::Keyword(keyword))
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<()> {
+        if self.consume(token) {
+            return Ok(());
+        }
+        self.unexpected(&format!("'{token}'"))
+    }
+
+    fn expect_keyword(&mut self, keyword: Keyword) -> Result<()> {
+        if self.consume_keyword(keyword) {
+            return Ok(());
+        }
+        self.unexpected(&keyword.to_string())
+    }
+
+    /// Checks for a non-reserved word such as `RENAME`, which may also be used as an identifier
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident.eq_ignore_ascii_case(word))
+    }
+
+    fn consume_word(&mut self, word: &str) -> bool {
+        if self.peek_word(word) {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<()> {
+        if self.consume_word(word) {
+            return Ok(());
+        }
+        self.unexpected(word)
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Token::Ident(_) => match self.next() {
+                Token::Ident(ident) => Ok(ident),
+                _ => unreachable!(),
+            },
+            _ => self.unexpected("identifier"),
+        }
+    }
+
+    /// Builds an error pointing at the current token
+    fn error<T>(&self, message: impl Into<String>) -> Result<T> {
+        Err(KirinError::Parse { message: message.into(), span: self.span() })
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        let found = match self.peek() {
+            Token::Eof => String::from("end of input"),
+            token => format!("'{token}'"),
+        };
+        self.error(format!("Expected {expected}, found {found}"))
+    }
+
+    // ---------------------------------------------------------------------
+    // Statements
+    // ---------------------------------------------------------------------
+
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>> {
+        let mut statements = Vec::new();
+
+        loop {
+            while self.consume(&Token::Semicolon) {}
+            if self.peek() == &Token::Eof {
+                return Ok(statements);
+            }
+
+            statements.push(self.parse_statement()?);
+
+            if !self.consume(&Token::Semicolon) && self.peek() != &Token::Eof {
+                return self.unexpected("';' or end of input");
+            }
+        }
+    }
+
+    pub fn parse_statement(&mut self) -> Result<Statement> {
+        match self.peek() {
+            Token::Keyword(Keyword::Select | Keyword::With) => self.parse_select().map(|select| Statement::Select(Box::new(select))),
+            Token::Keyword(Keyword::Explain) => {
+                self.next();
+                self.parse_select().map(|select| Statement::Explain(Box::new(select)))
+            }
+            Token::Keyword(Keyword::Insert) => self.parse_insert().map(Statement::Insert),
+            Token::Keyword(Keyword::Update) => self.parse_update().map(Statement::Update),
+            Token::Keyword(Keyword::Delete) => self.parse_delete().map(Statement::Delete),
+            Token::Keyword(Keyword::Create) => self.parse_create_table().map(Statement::CreateTable),
+            Token::Keyword(Keyword::Drop) => self.parse_drop_table().map(Statement::DropTable),
+            Token::Keyword(Keyword::Alter) => self.parse_alter_table().map(Statement::AlterTable),
+            Token::Keyword(Keyword::Set) => self.parse_set_time_zone().map(Statement::SetTimeZone),
+            Token::Ident(word) if word.eq_ignore_ascii_case("SHOW") => {
+                self.next();
+                self.expect_word("TIME")?;
+                self.expect_word("ZONE")?;
+                Ok(Statement::ShowTimeZone)
+            }
+            _ => self.unexpected("statement"),
+        }
+    }
+
+    fn parse_select(&mut self) -> Result<SelectStmt> {
+        let with = if self.consume_keyword(Keyword::With) {
+            Some(self.parse_with()?)
+        } else {
+            None
+        };
+
+        let mut select = self.parse_select_core()?;
+        select.with = with;
+
+        while let Some(operator) = self.parse_set_operator() {
+            let all = self.consume_keyword(Keyword::All);
+            let core = self.parse_select_core()?;
+            select.compound.push(Compound { operator, all, select: core });
+        }
+
+        if self.consume_keyword(Keyword::Order) {
+            self.expect_keyword(Keyword::By)?;
+            select.order_by = self.parse_comma_separated(Self::parse_order_by_expr)?;
+        }
+
+        if self.consume_keyword(Keyword::Limit) {
+            select.limit = Some(self.parse_expr()?);
+        }
+
+        if self.consume_keyword(Keyword::Offset) {
+            select.offset = Some(self.parse_expr()?);
+        }
+
+        Ok(select)
+    }
+
+    /// Parses `SELECT ... [HAVING ...]`, the part of a query that set operators combine
+    fn parse_select_core(&mut self) -> Result<SelectStmt> {
+        self.expect_keyword(Keyword::Select)?;
+
+        let distinct = if self.consume_keyword(Keyword::Distinct) {
+            match self.consume_keyword(Keyword::On) {
+                true => Some(Distinct::On(self.parse_parenthesized(Self::parse_expr)?)),
+                false => Some(Distinct::Rows),
+            }
+        } else {
+            self.consume_keyword(Keyword::All);
+            None
+        };
+
+        let projection = self.parse_comma_separated(Self::parse_select_item)?;
+
+        let from = if self.consume_keyword(Keyword::From) {
+            Some(self.parse_table_expr()?)
+        } else {
+            None
+        };
+
+        // A stray word after the select list is most likely a misspelt FROM
+        if from.is_none() && matches!(self.peek(), Token::Ident(_)) {
+            return self.unexpected("FROM");
+        }
+
+        let selection = self.parse_where()?;
+
+        let group_by = if self.consume_keyword(Keyword::Group) {
+            self.expect_keyword(Keyword::By)?;
+            self.parse_comma_separated(Self::parse_expr)?
+        } else {
+            Vec::new()
+        };
+
+        let having = if self.consume_keyword(Keyword::Having) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(SelectStmt {
+            with: None,
+            distinct,
+            projection,
+            from,
+            selection,
+            group_by,
+            having,
+            compound: Vec::new(),
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        })
+    }
+
+    /// Parses `[RECURSIVE] <cte>, ...` after WITH
+    fn parse_with(&mut self) -> Result<With> {
+        let recursive = self.consume_keyword(Keyword::Recursive);
+        let ctes = self.parse_comma_separated(Self::parse_cte)?;
+        Ok(With { recursive, ctes })
+    }
+
+    /// Parses `name [(column, ...)] AS (<query>)`
+    fn parse_cte(&mut self) -> Result<Cte> {
+        let name = self.expect_ident()?;
+
+        let columns = if self.peek() == &Token::LParen {
+            self.parse_parenthesized(Self::expect_ident)?
+        } else {
+            Vec::new()
+        };
+
+        self.expect_keyword(Keyword::As)?;
+        let query = self.parse_subquery()?;
+        Ok(Cte { name, columns, query })
+    }
+
+    fn parse_set_operator(&mut self) -> Option<SetOperator> {
+        let operator = match self.peek() {
+            Token::Keyword(Keyword::Union) => SetOperator::Union,
+            Token::Keyword(Keyword::Intersect) => SetOperator::Intersect,
+            Token::Keyword(Keyword::Except) => SetOperator::Except,
+            _ => return None,
+        };
+        self.next();
+        Some(operator)
+    }
+
+    /// Parses an optional `WHERE <expr>`
+    fn parse_where(&mut self) -> Result<Option<Expr>> {
+        if self.consume_keyword(Keyword::Where) {
+            return self.parse_expr().map(Some);
+        }
+        Ok(None)
+    }
+
+    fn parse_order_by_expr(&mut self) -> Result<OrderByExpr> {
+        let expr = self.parse_expr()?;
+
+        let descending = if self.consume_keyword(Keyword::Desc) {
+            true
+        } else {
+            self.consume_keyword(Keyword::Asc);
+            false
+        };
+
+        let nulls_first = if self.consume_word("NULLS") {
+            if self.consume_word("FIRST") {
+                Some(true)
+            } else {
+                self.expect_word("LAST")?;
+                Some(false)
+            }
+        } else {
+            None
+        };
+
+        Ok(OrderByExpr { expr, descending, nulls_first })
+    }
+
+    /// Parses `([PARTITION BY ...] [ORDER BY ...] [<frame>])` after OVER
+    fn parse_window_spec(&mut self) -> Result<WindowSpec> {
+        self.expect(&Token::LParen)?;
+
+        let partition_by = if self.consume_word("PARTITION") {
+            self.expect_keyword(Keyword::By)?;
+            self.parse_comma_separated(Self::parse_expr)?
+        } else {
+            Vec::new()
+        };
+
+        let order_by = if self.consume_keyword(Keyword::Order) {
+            self.expect_keyword(Keyword::By)?;
+            self.parse_comma_separated(Self::parse_order_by_expr)?
+        } else {
+            Vec::new()
+        };
+
+        let units = if self.consume_word("ROWS") {
+            Some(FrameUnits::Rows)
+        } else if self.consume_word("RANGE") {
+            Some(FrameUnits::Range)
+        } else {
+            None
+        };
+
+        let frame = match units {
+            Some(units) if self.consume_word("BETWEEN") => {
+                let start = self.parse_frame_bound()?;
+                self.expect_keyword(Keyword::And)?;
+                let end = self.parse_frame_bound()?;
+                Some(WindowFrame { units, start, end })
+            }
+            Some(units) => Some(WindowFrame { units, start: self.parse_frame_bound()?, end: FrameBound::CurrentRow }),
+            None => None,
+        };
+
+        if let Some(frame) = &frame {
+            if frame.start == FrameBound::UnboundedFollowing {
+                return self.error("Frame cannot start at UNBOUNDED FOLLOWING");
+            }
+            if frame.end == FrameBound::UnboundedPreceding {
+                return self.error("Frame cannot end at UNBOUNDED PRECEDING");
+            }
+        }
+
+        self.expect(&Token::RParen)?;
+        Ok(WindowSpec { partition_by, order_by, frame })
+    }
+
+    /// Parses `UNBOUNDED PRECEDING|FOLLOWING`, `CURRENT ROW` or `<offset> PRECEDING|FOLLOWING`
+    fn parse_frame_bound(&mut self) -> Result<FrameBound> {
+        if self.consume_word("UNBOUNDED") {
+            if self.consume_word("PRECEDING") {
+                return Ok(FrameBound::UnboundedPreceding);
+            }
+            self.expect_word("FOLLOWING")?;
+            return Ok(FrameBound::UnboundedFollowing);
+        }
+
+        if self.consume_word("CURRENT") {
+            self.expect_word("ROW")?;
+            return Ok(FrameBound::CurrentRow);
+        }
+
+        // Offsets are evaluated once per frame, outside any row
+        let span = self.span();
+        let offset = Box::new(self.parse_prefix()?);
+        if !is_constant(&offset) {
+            let message = format!("Frame offset {offset} must be a constant, without columns, subqueries or aggregates");
+            return Err(KirinError::Parse { message, span });
+        }
+        if self.consume_word("PRECEDING") {
+            return Ok(FrameBound::Preceding(offset));
+        }
+        self.expect_word("FOLLOWING")?;
+        Ok(FrameBound::Following(offset))
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem> {
+        if self.consume(&Token::Star) {
+            return Ok(SelectItem::Wildcard);
+        }
+
+        // table.*
+        if let (Token::Ident(table), Token::Dot, Token::Star) = (self.peek(), self.peek_nth(1), self.peek_nth(2)) {
+            let table = table.clone();
+            self.pos += 3;
+            return Ok(SelectItem::QualifiedWildcard(table));
+        }
+
+        let expr = self.parse_expr()?;
+        let alias = self.parse_alias()?;
+        Ok(SelectItem::Expr { expr, alias })
+    }
+
+    /// Whether the next tokens open a parenthesized query
+    fn at_subquery(&self) -> bool {
+        self.peek() == &Token::LParen && matches!(self.peek_nth(1), Token::Keyword(Keyword::Select | Keyword::With))
+    }
+
+    /// Parses `(SELECT ...)`
+    fn parse_subquery(&mut self) -> Result<Box<SelectStmt>> {
+        self.expect(&Token::LParen)?;
+        let query = self.parse_select()?;
+        self.expect(&Token::RParen)?;
+        Ok(Box::new(query))
+    }
+
+    /// Parses an optional `[AS] alias`
+    fn parse_alias(&mut self) -> Result<Option<String>> {
+        if self.consume_keyword(Keyword::As) {
+            return self.expect_ident().map(Some);
+        }
+        if let Token::Ident(_) = self.peek() {
+            return self.expect_ident().map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Parses the FROM clause: tables combined by joins, which associate to the left
+    fn parse_table_expr(&mut self) -> Result<TableExpr> {
+        let mut left = self.parse_table_factor()?;
+
+        loop {
+            let kind = if self.consume(&Token::Comma) {
+                JoinKind::Cross
+            } else if let Some(kind) = self.parse_join_kind()? {
+                kind
+            } else {
+                return Ok(left);
+            };
+
+            let right = self.parse_table_factor()?;
+            let constraint = match kind {
+                JoinKind::Cross => JoinConstraint::None,
+                _ if self.consume_keyword(Keyword::On) => JoinConstraint::On(self.parse_expr()?),
+                _ if self.consume_keyword(Keyword::Using) => JoinConstraint::Using(self.parse_parenthesized(Self::expect_ident)?),
+                _ => return self.unexpected("ON or USING"),
+            };
+
+            left = TableExpr::Join(Box::new(Join { left, right, kind, constraint }));
+        }
+    }
+
+    /// Parses `[INNER | LEFT [OUTER] | RIGHT [OUTER] | FULL [OUTER] | CROSS] JOIN`
+    fn parse_join_kind(&mut self) -> Result<Option<JoinKind>> {
+        let kind = match self.peek() {
+            Token::Keyword(Keyword::Join | Keyword::Inner) => JoinKind::Inner,
+            Token::Keyword(Keyword::Left) => JoinKind::Left,
+            Token::Keyword(Keyword::Right) => JoinKind::Right,
+            Token::Keyword(Keyword::Full) => JoinKind::Full,
+            Token::Keyword(Keyword::Cross) => JoinKind::Cross,
+            _ => return Ok(None),
+        };
+
+        if !self.consume_keyword(Keyword::Join) {
+            self.next();
+            if let JoinKind::Left | JoinKind::Right | JoinKind::Full = kind {
+                self.consume_keyword(Keyword::Outer);
+            }
+            self.expect_keyword(Keyword::Join)?;
+        }
+        Ok(Some(kind))
+    }
+
+    /// Parses a single table with an optional alias, a subquery with an alias,
+    /// or a parenthesized join
+    fn parse_table_factor(&mut self) -> Result<TableExpr> {
+        if self.at_subquery() {
+            let query = self.parse_subquery()?;
+            let Some(alias) = self.parse_alias()? else {
+                return self.unexpected("alias for subquery");
+            };
+            return Ok(TableExpr::Subquery { query, alias });
+        }
+
+        if self.consume(&Token::LParen) {
+            let table = self.parse_table_expr()?;
+            self.expect(&Token::RParen)?;
+            return Ok(table);
+        }
+
+        let name = self.expect_ident()?;
+        let alias = self.parse_alias()?;
+        Ok(TableExpr::Table(TableRef { name, alias }))
+    }
+
+    fn parse_insert(&mut self) -> Result<InsertStmt> {
+        self.expect_keyword(Keyword::Insert)?;
+        self.expect_keyword(Keyword::Into)?;
+
+        let table = self.expect_ident()?;
+
+        let columns = if self.peek() == &Token::LParen {
+            Some(self.parse_parenthesized(Self::expect_ident)?)
+        } else {
+            None
+        };
+
+        self.expect_keyword(Keyword::Values)?;
+        let rows = self.parse_comma_separated(|parser| parser.parse_parenthesized(Self::parse_expr))?;
+
+        Ok(InsertStmt { table, columns, rows })
+    }
+
+    fn parse_update(&mut self) -> Result<UpdateStmt> {
+        self.expect_keyword(Keyword::Update)?;
+        let table = self.expect_ident()?;
+
+        self.expect_keyword(Keyword::Set)?;
+        let assignments = self.parse_comma_separated(|parser| {
+            let column = parser.expect_ident()?;
+            parser.expect(&Token::Eq)?;
+            let value = parser.parse_expr()?;
+            Ok(Assignment { column, value })
+        })?;
+
+        let selection = self.parse_where()?;
+        Ok(UpdateStmt { table, assignments, selection })
+    }
+
+    fn parse_delete(&mut self) -> Result<DeleteStmt> {
+        self.expect_keyword(Keyword::Delete)?;
+        self.expect_keyword(Keyword::From)?;
+        let table = self.expect_ident()?;
+
+        let selection = self.parse_where()?;
+        Ok(DeleteStmt { table, selection })
+    }
+
+    fn parse_create_table(&mut self) -> Result<CreateTableStmt> {
+        self.expect_keyword(Keyword::Create)?;
+        self.expect_keyword(Keyword::Table)?;
+
+        let if_not_exists = self.consume_keyword(Keyword::If);
+        if if_not_exists {
+            self.expect_keyword(Keyword::Not)?;
+            self.expect_keyword(Keyword::Exists)?;
+        }
+
+        let name = self.expect_ident()?;
+        let columns = self.parse_parenthesized(Self::parse_column_def)?;
+
+        Ok(CreateTableStmt { name, if_not_exists, columns })
+    }
+
+    fn parse_drop_table(&mut self) -> Result<DropTableStmt> {
+        self.expect_keyword(Keyword::Drop)?;
+        self.expect_keyword(Keyword::Table)?;
+
+        let if_exists = self.consume_keyword(Keyword::If);
+        if if_exists {
+            self.expect_keyword(Keyword::Exists)?;
+        }
+
+        let name = self.expect_ident()?;
+        Ok(DropTableStmt { name, if_exists })
+    }
+
+    fn parse_alter_table(&mut self) -> Result<AlterTableStmt> {
+        self.expect_keyword(Keyword::Alter)?;
+        self.expect_keyword(Keyword::Table)?;
+
+        let name = self.expect_ident()?;
+
+        let operation = if self.consume_word("RENAME") {
+            if self.consume_word("TO") {
+                AlterTableOp::RenameTable { new_name: self.expect_ident()? }
+            } else {
+                self.consume_word("COLUMN");
+                let name = self.expect_ident()?;
+                self.expect_word("TO")?;
+                AlterTableOp::RenameColumn { name, new_name: self.expect_ident()? }
+            }
+        } else if self.consume_word("ADD") {
+            self.consume_word("COLUMN");
+            let column = self.parse_column_def()?;
+            let default = if self.consume_word("DEFAULT") { Some(self.parse_expr()?) } else { None };
+            AlterTableOp::AddColumn { column, default }
+        } else if self.consume_keyword(Keyword::Drop) {
+            self.consume_word("COLUMN");
+            AlterTableOp::DropColumn { name: self.expect_ident()? }
+        } else {
+            return self.unexpected("RENAME, ADD or DROP");
+        };
+
+        Ok(AlterTableStmt { name, operation })
+    }
+
+    /// `SET TIME ZONE '<zone>'`, or `DEFAULT` for UTC. Zones are UTC or fixed
+    /// offsets from it; named zones such as `'Europe/Paris'` and `LOCAL`, the
+    /// zone of the machine, are not supported
+    fn parse_set_time_zone(&mut self) -> Result<FixedOffset> {
+        self.expect_keyword(Keyword::Set)?;
+        self.expect_word("TIME")?;
+        self.expect_word("ZONE")?;
+        if self.consume_word("DEFAULT") {
+            return Ok(Utc.fix());
+        }
+        if self.peek_word("LOCAL") {
+            return self.error("SET TIME ZONE LOCAL is not supported, only UTC or a fixed offset such as '+02:00'");
+        }
+
+        let Token::String(text) = self.peek().clone() else {
+            return self.unexpected("time zone");
+        };
+        let Some(zone) = parse_time_zone(&text) else {
+            return self.error(format!("Unknown time zone '{text}', expected UTC or a fixed offset such as '+02:00'"));
+        };
+        self.next();
+        Ok(zone)
+    }
+
+    fn parse_column_def(&mut self) -> Result<ColumnDef> {
+        let name = self.expect_ident()?;
+        let data_type = self.parse_data_type()?;
+
+        let nullable = if self.consume_keyword(Keyword::Not) {
+            self.expect_keyword(Keyword::Null)?;
+            false
+        } else {
+            self.consume_keyword(Keyword::Null);
+            true
+        };
+
+        Ok(ColumnDef { name, data_type, nullable })
+    }
+
+    /// Maps a SQL type name onto a [`ValueType`]
+    fn parse_data_type(&mut self) -> Result<ValueType> {
+        let Token::Ident(name) = self.peek() else {
+            return self.unexpected("data type");
+        };
+
+        let data_type = match name.to_ascii_uppercase().as_str() {
+            "INTEGER" | "INT" | "BIGINT" | "SMALLINT" => ValueType::Int,
+            "TEXT" | "VARCHAR" | "CHAR" | "STRING" => ValueType::Text,
+            "BLOB" | "BYTEA" | "BINARY" | "VARBINARY" => ValueType::Blob,
+            "REAL" | "FLOAT" | "DOUBLE" => ValueType::Float,
+            "BOOLEAN" | "BOOL" => ValueType::Bool,
+            "DATE" => ValueType::Date,
+            "TIME" => ValueType::Time,
+            "TIMESTAMP" | "DATETIME" => ValueType::DateTime,
+            "TIMESTAMPTZ" => ValueType::DateTimeTz,
+            "INTERVAL" => ValueType::Interval,
+            "DECIMAL" | "NUMERIC" | "DEC" => {
+                self.next();
+                return self.parse_decimal_type();
+            }
+            _ => return self.error(format!("Unknown data type '{name}'")),
+        };
+        self.next();
+
+        // TIMESTAMP WITH TIME ZONE, while WITHOUT TIME ZONE is the default
+        if matches!(data_type, ValueType::Time | ValueType::DateTime) {
+            if self.peek() == &Token::Keyword(Keyword::With) {
+                if data_type == ValueType::Time {
+                    return self.error("TIME WITH TIME ZONE is not supported, use TIMESTAMP WITH TIME ZONE");
+                }
+                self.next();
+                self.expect_word("TIME")?;
+                self.expect_word("ZONE")?;
+                return Ok(ValueType::DateTimeTz);
+            }
+            if self.consume_word("WITHOUT") {
+                self.expect_word("TIME")?;
+                self.expect_word("ZONE")?;
+            }
+        }
+
+        // Length modifiers such as VARCHAR(255) are accepted but not enforced
+        if matches!(data_type, ValueType::Text | ValueType::Blob) && self.peek() == &Token::LParen {
+            self.next();
+            let Token::Integer(_) = self.next() else {
+                return self.unexpected("length");
+            };
+            self.expect(&Token::RParen)?;
+        }
+
+        Ok(data_type)
+    }
+
+    /// `DECIMAL[(precision[, scale])]`, where the scale defaults to 0
+    fn parse_decimal_type(&mut self) -> Result<ValueType> {
+        let (mut precision, mut scale) = (decimal::DEFAULT_PRECISION as i64, 0);
+        if self.consume(&Token::LParen) {
+            let span = self.span();
+            let Token::Integer(digits) = self.next() else {
+                return self.unexpected("precision");
+            };
+            precision = digits;
+            if self.consume(&Token::Comma) {
+                let Token::Integer(digits) = self.next() else {
+                    return self.unexpected("scale");
+                };
+                scale = digits;
+            }
+            self.expect(&Token::RParen)?;
+
+            if !(1..=decimal::MAX_PRECISION as i64).contains(&precision) || !(0..=precision).contains(&scale) {
+                let message = format!("DECIMAL({precision}, {scale}) needs a precision from 1 to {} and a scale from 0 to the precision", decimal::MAX_PRECISION);
+                return Err(KirinError::Parse { message, span });
+            }
+        }
+        Ok(ValueType::Decimal { precision: precision as u32, scale: scale as u32 })
+    }
+
+    /// Whether a date or time type name starts a literal such as `DATE '2026-01-01'`
+    fn at_typed_literal(&self, name: &str) -> bool {
+        let name = name.to_ascii_uppercase();
+        match self.peek_nth(1) {
+            Token::String(_) => ["DATE", "TIME", "TIMESTAMP", "TIMESTAMPTZ", "INTERVAL"].contains(&name.as_str()),
+            Token::Keyword(Keyword::With) => name == "TIMESTAMP",
+            Token::Ident(word) => name == "TIMESTAMP" && word.eq_ignore_ascii_case("WITHOUT"),
+            _ => false,
+        }
+    }
+
+    /// `<type> '<text>'`, read as the type when parsed. Timestamps with a time
+    /// zone are only checked here, since they are read in the session's time
+    /// zone once evaluated. An interval's unit may follow the text, as in
+    /// `INTERVAL '2' HOUR`
+    fn parse_typed_literal(&mut self) -> Result<Expr> {
+        let data_type = self.parse_data_type()?;
+        let span = self.span();
+        let Token::String(mut text) = self.peek().clone() else {
+            return self.unexpected("string");
+        };
+        self.next();
+
+        if data_type == ValueType::Interval {
+            match self.peek().clone() {
+                Token::Ident(unit) if INTERVAL_UNITS.iter().any(|known| unit.eq_ignore_ascii_case(known)) => {
+                    self.next();
+                    text = format!("{text} {unit}");
+                }
+                _ => {}
+            }
+        }
+
+        let literal = Value::Text(text);
+        let value = literal.cast_to(data_type).map_err(|_| {
+            let kind = match data_type {
+                ValueType::Date => "date",
+                ValueType::Time => "time",
+                ValueType::Interval => "interval",
+                _ => "timestamp",
+            };
+            KirinError::Parse { message: format!("Invalid {kind} '{literal}'"), span }
+        })?;
+
+        match data_type {
+            ValueType::DateTimeTz => Ok(Expr::Cast { expr: Box::new(Expr::Literal(literal)), data_type }),
+            _ => Ok(Expr::Literal(value)),
+        }
+    }
+
+    fn parse_comma_separated<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = vec![f(self)?];
+        while self.consume(&Token::Comma) {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Parses `( item, item, ... )`
+    fn parse_parenthesized<T>(&mut self, f: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        self.expect(&Token::LParen)?;
+        let items = self.parse_comma_separated(f)?;
+        self.expect(&Token::RParen)?;
+        Ok(items)
+    }
+
+    // ---------------------------------------------------------------------
+    // Expressions
+    // ---------------------------------------------------------------------
+
+    pub fn parse_expr(&mut self) -> Result<Expr> {
+        self.parse_expr_with_precedence(0)
+    }
+
+    /// Precedence climbing: parses a prefix expression, then folds in every
+    /// binary operator that binds tighter than `min_precedence`
+    fn parse_expr_with_precedence(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut left = self.parse_prefix()?;
+
+        loop {
+            // Postfix: expr::type
+            if self.peek() == &Token::DoubleColon && precedence::CAST > min_precedence {
+                self.next();
+                let data_type = self.parse_data_type()?;
+                left = Expr::Cast { expr: Box::new(left), data_type };
+                continue;
+            }
+
+            // Postfix: expr IS [NOT] NULL
+            if self.peek() == &Token::Keyword(Keyword::Is) && precedence::COMPARISON > min_precedence {
+                self.next();
+                let negated = self.consume_keyword(Keyword::Not);
+                self.expect_keyword(Keyword::Null)?;
+                left = Expr::IsNull { expr: Box::new(left), negated };
+                continue;
+            }
+
+            // Postfix: expr [NOT] IN (list | subquery)
+            let negated = self.peek() == &Token::Keyword(Keyword::Not) && self.peek_nth(1) == &Token::Keyword(Keyword::In);
+            if (negated || self.peek() == &Token::Keyword(Keyword::In)) && precedence::COMPARISON > min_precedence {
+                self.pos += if negated { 2 } else { 1 };
+                let expr = Box::new(left);
+
+                left = if self.at_subquery() {
+                    Expr::InSubquery { expr, subquery: self.parse_subquery()?, negated }
+                } else {
+                    Expr::InList { expr, list: self.parse_parenthesized(Self::parse_expr)?, negated }
+                };
+                continue;
+            }
+
+            // Postfix: expr [NOT] LIKE|ILIKE|GLOB|REGEXP pattern [ESCAPE escape], or expr ~ pattern
+            let negated = self.peek() == &Token::Keyword(Keyword::Not) && Self::match_op(self.peek_nth(1)).is_some();
+            if let Some(op) = Self::match_op(self.peek_nth(negated as usize)) && precedence::COMPARISON > min_precedence {
+                self.pos += 1 + negated as usize;
+                let pattern = self.parse_expr_with_precedence(precedence::COMPARISON)?;
+
+                // ESCAPE is only special after a LIKE pattern, so it still works as a column alias elsewhere
+                let escape = match matches!(op, MatchOp::Like | MatchOp::ILike) && self.consume_word("ESCAPE") {
+                    true => Some(Box::new(self.parse_expr_with_precedence(precedence::COMPARISON)?)),
+                    false => None,
+                };
+                left = Expr::Match { expr: Box::new(left), op, pattern: Box::new(pattern), escape, negated };
+                continue;
+            }
+
+            let Some((op, precedence)) = self.peek_binary_op() else {
+                break;
+            };
+            if precedence <= min_precedence {
+                break;
+            }
+            self.next();
+
+            let right = self.parse_expr_with_precedence(precedence)?;
+            left = Expr::Binary { left: Box::new(left), op, right: Box::new(right) };
+        }
+
+        Ok(left)
+    }
+
+    fn match_op(token: &Token) -> Option<MatchOp> {
+        match token {
+            Token::Keyword(Keyword::Like) => Some(MatchOp::Like),
+            Token::Keyword(Keyword::Ilike) => Some(MatchOp::ILike),
+            Token::Keyword(Keyword::Glob) => Some(MatchOp::Glob),
+            Token::Keyword(Keyword::Regexp) | Token::Tilde => Some(MatchOp::Regexp),
+            _ => None,
+        }
+    }
+
+    fn peek_binary_op(&self) -> Option<(BinaryOp, u8)> {
+        let op = match self.peek() {
+            Token::Keyword(Keyword::Or) => (BinaryOp::Or, precedence::OR),
+            Token::Keyword(Keyword::And) => (BinaryOp::And, precedence::AND),
+            Token::Eq => (BinaryOp::Eq, precedence::COMPARISON),
+            Token::NotEq => (BinaryOp::NotEq, precedence::COMPARISON),
+            Token::Lt => (BinaryOp::Lt, precedence::COMPARISON),
+            Token::LtEq => (BinaryOp::LtEq, precedence::COMPARISON),
+            Token::Gt => (BinaryOp::Gt, precedence::COMPARISON),
+            Token::GtEq => (BinaryOp::GtEq, precedence::COMPARISON),
+            Token::Concat => (BinaryOp::Concat, precedence::CONCAT),
+            Token::Plus => (BinaryOp::Add, precedence::ADDITIVE),
+            Token::Minus => (BinaryOp::Sub, precedence::ADDITIVE),
+            Token::Star => (BinaryOp::Mul, precedence::MULTIPLICATIVE),
+            Token::Slash => (BinaryOp::Div, precedence::MULTIPLICATIVE),
+            Token::Percent => (BinaryOp::Mod, precedence::MULTIPLICATIVE),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr> {
+        let unary = |parser: &mut Self, op, precedence| {
+            parser.next();
+            let expr = parser.parse_expr_with_precedence(precedence)?;
+            Ok(Expr::Unary { op, expr: Box::new(expr) })
+        };
+
+        match self.peek().clone() {
+            Token::Keyword(Keyword::Not) => unary(self, UnaryOp::Not, precedence::NOT),
+            // The one negative integer whose digits alone do not fit in i64
+            Token::Minus if matches!(self.peek_nth(1), Token::Decimal(text) if text == "9223372036854775808") => {
+                self.next();
+                self.next();
+                Ok(Expr::Literal(Value::Int(i64::MIN)))
+            }
+            Token::Minus => unary(self, UnaryOp::Minus, precedence::UNARY),
+            Token::Plus => unary(self, UnaryOp::Plus, precedence::UNARY),
+
+            Token::Integer(int) => {
+                self.next();
+                Ok(Expr::Literal(Value::Int(int)))
+            }
+            // Read from the text as written, so no digit is lost to a float
+            Token::Decimal(text) => {
+                let Ok(decimal) = text.parse::<BigDecimal>() else {
+                    return self.error(format!("Invalid numeric literal '{text}'"));
+                };
+                self.next();
+                Ok(Expr::Literal(Value::Decimal(decimal)))
+            }
+            Token::Float(float) => {
+                self.next();
+                Ok(Expr::Literal(Value::Float(float)))
+            }
+            Token::String(str) => {
+                self.next();
+                Ok(Expr::Literal(Value::Text(str)))
+            }
+            Token::Blob(bytes) => {
+                self.next();
+                Ok(Expr::Literal(Value::Blob(bytes.into())))
+            }
+            Token::Keyword(Keyword::True) => {
+                self.next();
+                Ok(Expr::Literal(Value::Bool(true)))
+            }
+            Token::Keyword(Keyword::False) => {
+                self.next();
+                Ok(Expr::Literal(Value::Bool(false)))
+            }
+            Token::Keyword(Keyword::Null) => {
+                self.next();
+                Ok(Expr::Literal(Value::Null))
+            }
+
+            Token::LParen if self.at_subquery() => {
+                self.parse_subquery().map(Expr::Subquery)
+            }
+            Token::LParen => {
+                self.next();
+                let expr = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(Expr::Nested(Box::new(expr)))
+            }
+
+            Token::Keyword(Keyword::Exists) => {
+                self.next();
+                self.parse_subquery().map(Expr::Exists)
+            }
+
+            Token::Keyword(Keyword::Case) => {
+                self.next();
+                let operand = match self.peek() {
+                    Token::Keyword(Keyword::When) => None,
+                    _ => Some(Box::new(self.parse_expr()?)),
+                };
+
+                let mut branches = Vec::new();
+                while self.consume_keyword(Keyword::When) {
+                    let when = self.parse_expr()?;
+                    self.expect_keyword(Keyword::Then)?;
+                    branches.push((when, self.parse_expr()?));
+                }
+                if branches.is_empty() {
+                    return self.unexpected("WHEN");
+                }
+
+                let else_result = match self.consume_keyword(Keyword::Else) {
+                    true => Some(Box::new(self.parse_expr()?)),
+                    false => None,
+                };
+                self.expect_keyword(Keyword::End)?;
+                Ok(Expr::Case { operand, branches, else_result })
+            }
+
+            // Typed literals: DATE '2026-01-01', TIMESTAMP [WITH TIME ZONE] '2026-01-01T00:00:00Z', INTERVAL '1 day'
+            Token::Ident(name) if self.at_typed_literal(&name) => self.parse_typed_literal(),
+
+            // CAST(expr AS type)
+            Token::Ident(name) if name.eq_ignore_ascii_case("CAST") && self.peek_nth(1) == &Token::LParen => {
+                self.next();
+                self.next();
+                let expr = self.parse_expr()?;
+                self.expect_keyword(Keyword::As)?;
+                let data_type = self.parse_data_type()?;
+                self.expect(&Token::RParen)?;
+                Ok(Expr::Cast { expr: Box::new(expr), data_type })
+            }
+
+            // EXTRACT(field FROM expr)
+            Token::Ident(name) if name.eq_ignore_ascii_case("EXTRACT") && self.peek_nth(1) == &Token::LParen => {
+                self.next();
+                self.next();
+                let field = self.expect_ident()?.to_ascii_uppercase();
+                self.expect_keyword(Keyword::From)?;
+                let expr = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(Expr::Extract { field, expr: Box::new(expr) })
+            }
+
+            Token::Ident(name) if self.peek_nth(1) == &Token::LParen => {
+                self.next();
+                self.next();
+                let wildcard = self.consume(&Token::Star);
+                let distinct = !wildcard && self.consume_keyword(Keyword::Distinct);
+                let args = if wildcard || (!distinct && self.peek() == &Token::RParen) {
+                    Vec::new()
+                } else {
+                    self.parse_comma_separated(Self::parse_expr)?
+                };
+                self.expect(&Token::RParen)?;
+
+                // OVER is only special before a window, so it still works as a column alias
+                let over = if self.peek_word("OVER") && self.peek_nth(1) == &Token::LParen {
+                    self.next();
+                    Some(Box::new(self.parse_window_spec()?))
+                } else {
+                    None
+                };
+                Ok(Expr::Function(FunctionCall { name, args, distinct, wildcard, over }))
+            }
+
+            Token::Ident(name) => {
+                self.next();
+                if self.consume(&Token::Dot) {
+                    let column = self.expect_ident()?;
+                    return Ok(Expr::Column(ColumnRef { table: Some(name), name: column }));
+                }
+                Ok(Expr::Column(ColumnRef { table: None, name }))
+            }
+
+            _ => self.unexpected("expression"),
+        }
+    }
+}
+
+/// Whether an expression can be evaluated on its own: it reads no columns and
+/// holds no subqueries, aggregates or window calls
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Column(_) | Expr::Exists(_) | Expr::Subquery(_) | Expr::InSubquery { .. } => false,
+        Expr::Function(call) if call.over.is_some() || is_aggregate(&call.name) => false,
+        _ => expr.children().into_iter().all(is_constant),
+    }
+}
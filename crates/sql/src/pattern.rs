@@ -0,0 +1,130 @@
+use core::error::Result;
+use core::KirinError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::ast::MatchOp;
+
+/// The most patterns a cache keeps; patterns read from a column may all differ
+const CAPACITY: usize = 256;
+
+/// A pattern as written, along with how it is read and its escape character
+type PatternKey = (MatchOp, String, Option<char>);
+
+/// The compiled patterns of one statement, so a pattern is compiled once
+/// rather than for every row it is matched against
+#[derive(Debug, Default)]
+pub struct PatternCache {
+    regexes: RefCell<HashMap<PatternKey, Regex>>,
+}
+
+impl PatternCache {
+    fn get(&self, op: MatchOp, pattern: &str, escape: Option<char>) -> Result<Regex> {
+        let key = (op, pattern.to_string(), escape);
+        if let Some(regex) = self.regexes.borrow().get(&key) {
+            return Ok(regex.clone());
+        }
+
+        let regex = compile(op, pattern, escape)?;
+        let mut regexes = self.regexes.borrow_mut();
+        if regexes.len() < CAPACITY {
+            regexes.insert(key, regex.clone());
+        }
+        Ok(regex)
+    }
+}
+
+/// Whether `text` matches `pattern`, compiling it through `cache` if given
+pub fn is_match(op: MatchOp, text: &str, pattern: &str, escape: Option<char>, cache: Option<&PatternCache>) -> Result<bool> {
+    let regex = match cache {
+        Some(cache) => cache.get(op, pattern, escape)?,
+        None => compile(op, pattern, escape)?,
+    };
+    Ok(regex.is_match(text))
+}
+
+/// Reads the argument of `ESCAPE`, which must be a single character
+pub fn escape_char(escape: &str) -> Result<char> {
+    let mut chars = escape.chars();
+    match (chars.next(), chars.next()) {
+        (Some(escape), None) => Ok(escape),
+        _ => Err(KirinError::InvalidArgument(format!("ESCAPE '{escape}' must be a single character"))),
+    }
+}
+
+/// Translates a pattern into a regular expression. Every pattern but a
+/// REGEXP one has to match the whole text
+fn compile(op: MatchOp, pattern: &str, escape: Option<char>) -> Result<Regex> {
+    let source = match op {
+        MatchOp::Like => format!("(?s)^{}$", like_regex(pattern, escape)?),
+        MatchOp::ILike => format!("(?si)^{}$", like_regex(pattern, escape)?),
+        MatchOp::Glob => format!("(?s)^{}$", glob_regex(pattern)),
+        MatchOp::Regexp => pattern.to_string(),
+    };
+    Regex::new(&source).map_err(|error| KirinError::InvalidArgument(format!("invalid {op} pattern '{pattern}': {error}")))
+}
+
+fn literal(c: char) -> String {
+    regex::escape(c.encode_utf8(&mut [0; 4]))
+}
+
+/// `%` matches any text and `_` any one character, unless preceded by the escape character
+fn like_regex(pattern: &str, escape: Option<char>) -> Result<String> {
+    let mut regex = String::new();
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if Some(c) == escape => match chars.next() {
+                Some(escaped) => regex.push_str(&literal(escaped)),
+                None => return Err(KirinError::InvalidArgument(format!("LIKE pattern '{pattern}' ends with its escape character"))),
+            },
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c => regex.push_str(&literal(c)),
+        }
+    }
+    Ok(regex)
+}
+
+/// `*` matches any text, `?` any one character, and `[...]` one of a set of
+/// characters, or any other character with `[!...]` or `[^...]`. A `[`
+/// without its `]` stands for itself
+fn glob_regex(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut regex = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                let negated = matches!(chars.get(i + 1), Some('!' | '^'));
+                let first = i + 1 + negated as usize;
+                // A `]` right after the opening bracket belongs to the set
+                let close = chars.iter().skip(first + 1).position(|&c| c == ']').map(|offset| first + 1 + offset);
+
+                match close {
+                    Some(close) => {
+                        regex.push_str(if negated { "[^" } else { "[" });
+                        for &c in &chars[first..close] {
+                            match c {
+                                '-' => regex.push('-'),
+                                c => regex.push_str(&literal(c)),
+                            }
+                        }
+                        regex.push(']');
+                        i = close;
+                    }
+                    None => regex.push_str(&literal('[')),
+                }
+            }
+            c => regex.push_str(&literal(c)),
+        }
+        i += 1;
+    }
+    regex
+}
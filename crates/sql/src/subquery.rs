@@ -0,0 +1,105 @@
+use core::error::Result;
+use core::Value;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::ast::SelectStmt;
+use crate::eval::Scope;
+use crate::executor::{self, Context, ResultSet};
+
+/// The rows of a subquery, along with its values as a set once IN needs them
+#[derive(Debug)]
+pub struct SubqueryRows {
+    pub result: ResultSet,
+    /// The values of the first column, and whether one of them is NULL
+    members: OnceCell<(HashSet<Value>, bool)>,
+}
+
+impl SubqueryRows {
+    pub fn new(result: ResultSet) -> Self {
+        Self { result, members: OnceCell::new() }
+    }
+
+    /// `value IN (subquery)`: NULL rather than false when no row matches but
+    /// the value or one of the rows is NULL
+    pub fn contains(&self, value: &Value, negated: bool) -> Value {
+        if self.result.rows.is_empty() {
+            return Value::Bool(negated);
+        }
+
+        let (values, has_null) = self.members.get_or_init(|| {
+            let (mut values, mut has_null) = (HashSet::new(), false);
+            for row in &self.result.rows {
+                match &row[0] {
+                    Value::Null => has_null = true,
+                    value => {
+                        values.insert(value.clone());
+                    }
+                }
+            }
+            (values, has_null)
+        });
+
+        match value {
+            Value::Null => Value::Null,
+            value if values.contains(value) => Value::Bool(!negated),
+            _ if *has_null => Value::Null,
+            _ => Value::Bool(negated),
+        }
+    }
+}
+
+/// A subquery within the CTEs it sees. The subquery is kept in its debug
+/// form, since the SQL text does not tell apart literals such as 1 and 1e0,
+/// while the node itself may be a clone made for one evaluation
+type SubqueryKey = (usize, String);
+
+/// The subqueries of one statement that do not refer to an enclosing query,
+/// so each runs once rather than for every row it is used in
+#[derive(Debug, Default)]
+pub struct SubqueryCache {
+    /// The rows of each subquery, or None for one that reads the enclosing row
+    results: RefCell<HashMap<SubqueryKey, Option<Rc<SubqueryRows>>>>,
+    /// The last CTE scope handed out by [`SubqueryCache::new_scope`]
+    scopes: Cell<usize>,
+}
+
+impl SubqueryCache {
+    /// A number telling apart the sets of CTEs a statement's subqueries may
+    /// see, since the same text can name different CTEs in each
+    pub fn new_scope(&self) -> usize {
+        self.scopes.set(self.scopes.get() + 1);
+        self.scopes.get()
+    }
+
+    /// The rows of `subquery`, running it only the first time when it can be
+    /// planned without the enclosing query's columns, and through
+    /// `run_correlated` for every row otherwise
+    pub fn rows(
+        &self,
+        context: Context,
+        subquery: &SelectStmt,
+        run_correlated: impl FnOnce() -> Result<ResultSet>,
+    ) -> Result<Rc<SubqueryRows>> {
+        let key = (context.cte_scope(), format!("{subquery:?}"));
+        let cached = self.results.borrow().get(&key).cloned();
+
+        let cached = match cached {
+            Some(cached) => cached,
+            None => {
+                let uncorrelated = match executor::describe_subquery(context, subquery, &Scope::empty()) {
+                    Ok(_) => Some(Rc::new(SubqueryRows::new(executor::execute_subquery(context, subquery, &Scope::empty(), &[])?))),
+                    Err(_) => None,
+                };
+                self.results.borrow_mut().insert(key, uncorrelated.clone());
+                uncorrelated
+            }
+        };
+
+        match cached {
+            Some(rows) => Ok(rows),
+            None => run_correlated().map(|result| Rc::new(SubqueryRows::new(result))),
+        }
+    }
+}
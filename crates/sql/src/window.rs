@@ -0,0 +1,355 @@
+use core::error::Result;
+use core::{KirinError, Value, ValueType};
+use std::collections::HashMap;
+
+use bigdecimal::Signed;
+use chrono::NaiveTime;
+
+use crate::aggregate::{self, is_aggregate};
+use crate::ast::{BinaryOp, ColumnRef, Expr, FrameBound, FrameUnits, FunctionCall, OrderByExpr, WindowFrame, WindowSpec};
+use crate::eval::{binary, common_type, evaluate, infer_type, Scope, ScopeColumn};
+use crate::executor::{compare_rows, compare_sort_values};
+
+/// Whether `name` is a function that can only be called with OVER
+pub fn is_window_function(name: &str) -> bool {
+    matches!(
+        name.to_ascii_uppercase().as_str(),
+        "ROW_NUMBER" | "RANK" | "DENSE_RANK" | "NTILE" | "LAG" | "LEAD" | "FIRST_VALUE" | "LAST_VALUE"
+    )
+}
+
+/// The window function calls inside `expr`, outermost first
+pub fn window_calls(expr: &Expr) -> Vec<&FunctionCall> {
+    match expr {
+        Expr::Function(call) if call.over.is_some() => vec![call],
+        _ => expr.children().into_iter().flat_map(window_calls).collect(),
+    }
+}
+
+/// The window function calls of a query, each computed once per row
+#[derive(Debug, Default)]
+pub struct Windows {
+    calls: Vec<FunctionCall>,
+}
+
+impl Windows {
+    /// Copies `expr`, replacing every window function call with a reference
+    /// to the column that will hold its result
+    pub fn extract(&mut self, expr: &Expr) -> Result<Expr> {
+        let mut expr = expr.clone();
+        self.replace_calls(&mut expr)?;
+        Ok(expr)
+    }
+
+    fn replace_calls(&mut self, expr: &mut Expr) -> Result<()> {
+        if let Expr::Function(call) = &*expr && call.over.is_some() {
+            if expr.children().into_iter().any(|child| !window_calls(child).is_empty()) {
+                return Err(KirinError::InvalidWindow(format!("window functions cannot be nested, as in {call}")));
+            }
+
+            let name = call.to_string();
+            if !self.calls.iter().any(|other| other.to_string() == name) {
+                self.calls.push(call.clone());
+            }
+            *expr = Expr::Column(ColumnRef { table: None, name });
+            return Ok(());
+        }
+
+        expr.children_mut().into_iter().try_for_each(|child| self.replace_calls(child))
+    }
+
+    /// Computes every window call for each row, appending the results to the
+    /// rows and as columns named after the calls to the scope
+    pub fn compute<'a>(&self, scope: &Scope<'a>, mut rows: Vec<Vec<Value>>) -> Result<(Scope<'a>, Vec<Vec<Value>>)> {
+        let mut columns = scope.columns.clone();
+        for call in &self.calls {
+            let col_type = result_type(call, scope)?;
+            columns.push(ScopeColumn { table: None, name: call.to_string(), col_type, hidden: false, depth: 0 });
+        }
+
+        let mut results = Vec::with_capacity(self.calls.len());
+        for call in &self.calls {
+            results.push(compute(call, scope, &rows)?.into_iter());
+        }
+        for row in &mut rows {
+            row.extend(results.iter_mut().map(|values| values.next().expect("One result per row")));
+        }
+
+        Ok((Scope { columns, context: scope.context }, rows))
+    }
+}
+
+fn window(call: &FunctionCall) -> &WindowSpec {
+    call.over.as_deref().expect("Window calls have an OVER clause")
+}
+
+/// Works out the type of a window call, checking its arguments and window
+fn result_type(call: &FunctionCall, scope: &Scope) -> Result<ValueType> {
+    let over = window(call);
+    for key in &over.partition_by {
+        infer_type(key, scope)?;
+    }
+    for key in &over.order_by {
+        infer_type(&key.expr, scope)?;
+    }
+
+    let name = call.name.to_ascii_uppercase();
+    if is_aggregate(&name) {
+        return aggregate::result_type(call, scope);
+    }
+    if !is_window_function(&name) {
+        return Err(KirinError::InvalidWindow(format!("{name} is not a window function")));
+    }
+    if call.distinct || call.wildcard {
+        return Err(KirinError::InvalidWindow(format!("{name} cannot be called with DISTINCT or *")));
+    }
+
+    let args = call.args.iter().map(|arg| infer_type(arg, scope)).collect::<Result<Vec<_>>>()?;
+    let expect_int = |index: usize| match args[index] {
+        ValueType::Int | ValueType::Null => Ok(()),
+        actual => Err(KirinError::TypeMismatch { column: name.clone(), expected: ValueType::Int, actual }),
+    };
+
+    match (name.as_str(), args.len()) {
+        ("ROW_NUMBER" | "RANK" | "DENSE_RANK", 0) => Ok(ValueType::Int),
+        ("ROW_NUMBER" | "RANK" | "DENSE_RANK", actual) => Err(KirinError::ArityMismatch { expected: 0, actual }),
+        ("NTILE", 1) => expect_int(0).map(|_| ValueType::Int),
+        ("FIRST_VALUE" | "LAST_VALUE", 1) => Ok(args[0]),
+        ("LAG" | "LEAD", 1..=3) => {
+            if args.len() > 1 {
+                expect_int(1)?;
+            }
+            match args.get(2) {
+                Some(default) => common_type(args[0], *default)
+                    .ok_or_else(|| KirinError::InvalidOperands { op: name.clone(), operands: vec![args[0], *default] }),
+                None => Ok(args[0]),
+            }
+        }
+        ("LAG" | "LEAD", 0) => Err(KirinError::ArityMismatch { expected: 1, actual: 0 }),
+        ("LAG" | "LEAD", actual) => Err(KirinError::ArityMismatch { expected: 3, actual }),
+        (_, actual) => Err(KirinError::ArityMismatch { expected: 1, actual }),
+    }
+}
+
+/// Computes a window call for every row, returning the results in row order
+fn compute(call: &FunctionCall, scope: &Scope, rows: &[Vec<Value>]) -> Result<Vec<Value>> {
+    let over = window(call);
+
+    let mut partitions: HashMap<Vec<Value>, Vec<usize>> = HashMap::new();
+    let mut sort_keys = Vec::with_capacity(rows.len());
+    for (index, row) in rows.iter().enumerate() {
+        let partition = over.partition_by.iter()
+            .map(|expr| evaluate(expr, scope, row))
+            .collect::<Result<Vec<_>>>()?;
+        partitions.entry(partition).or_default().push(index);
+
+        let keys = over.order_by.iter()
+            .map(|key| evaluate(&key.expr, scope, row))
+            .collect::<Result<Vec<_>>>()?;
+        sort_keys.push(keys);
+    }
+
+    let mut results = vec![Value::Null; rows.len()];
+    for mut partition in partitions.into_values() {
+        // Stable, so peers keep their storage order
+        partition.sort_by(|&left, &right| compare_rows(&over.order_by, &sort_keys[left], &sort_keys[right]));
+
+        let window = Window { call, scope, rows, sort_keys: &sort_keys, partition: &partition };
+        for (position, peers) in window.peer_groups().into_iter().enumerate() {
+            results[partition[position]] = window.value_at(position, peers)?;
+        }
+    }
+
+    Ok(results)
+}
+
+/// The rows with ORDER BY values equal to a given row's
+#[derive(Debug, Clone, Copy)]
+struct Peers {
+    /// Positions `start..end` of the peers within the partition
+    start: usize,
+    end: usize,
+    /// 1-based number of the group of peers, counting from the partition start
+    group: usize,
+}
+
+/// One partition of the rows, sorted by the window's ORDER BY
+struct Window<'w, 'a> {
+    call: &'w FunctionCall,
+    scope: &'w Scope<'a>,
+    rows: &'w [Vec<Value>],
+    sort_keys: &'w [Vec<Value>],
+    /// Row indices in window order
+    partition: &'w [usize],
+}
+
+impl Window<'_, '_> {
+    fn row(&self, position: usize) -> &[Value] {
+        &self.rows[self.partition[position]]
+    }
+
+    fn order_by(&self) -> &[OrderByExpr] {
+        &window(self.call).order_by
+    }
+
+    /// The peers of each row, by position
+    fn peer_groups(&self) -> Vec<Peers> {
+        let keys = |position: usize| &self.sort_keys[self.partition[position]];
+        let mut groups = Vec::with_capacity(self.partition.len());
+
+        let mut start = 0;
+        while start < self.partition.len() {
+            let end = start + (start..self.partition.len())
+                .take_while(|&position| compare_rows(self.order_by(), keys(position), keys(start)).is_eq())
+                .count();
+            let peers = Peers { start, end, group: groups.last().map_or(1, |last: &Peers| last.group + 1) };
+            groups.extend((start..end).map(|_| peers));
+            start = end;
+        }
+        groups
+    }
+
+    /// Computes the call for the row at `position`
+    fn value_at(&self, position: usize, peers: Peers) -> Result<Value> {
+        let call = self.call;
+        let row = self.row(position);
+
+        match call.name.to_ascii_uppercase().as_str() {
+            "ROW_NUMBER" => Ok(Value::Int(position as i64 + 1)),
+            "RANK" => Ok(Value::Int(peers.start as i64 + 1)),
+            "DENSE_RANK" => Ok(Value::Int(peers.group as i64)),
+            "NTILE" => match evaluate(&call.args[0], self.scope, row)? {
+                Value::Null => Ok(Value::Null),
+                Value::Int(buckets) if buckets > 0 => Ok(Value::Int(ntile(position, self.partition.len(), buckets as usize))),
+                _ => Err(KirinError::InvalidArgument(String::from("NTILE bucket count must be positive"))),
+            },
+            "LAG" | "LEAD" => {
+                let offset = match call.args.get(1) {
+                    Some(offset) => evaluate(offset, self.scope, row)?,
+                    None => Value::Int(1),
+                };
+                let name = call.name.to_ascii_uppercase();
+                let offset = match offset {
+                    Value::Null => return Ok(Value::Null),
+                    Value::Int(offset) => usize::try_from(offset)
+                        .map_err(|_| KirinError::InvalidArgument(format!("{name} offset must not be negative")))?,
+                    offset => return Err(KirinError::InvalidArgument(format!("{name} offset {offset} must be an integer"))),
+                };
+
+                let target = match call.name.eq_ignore_ascii_case("LAG") {
+                    true => position.checked_sub(offset),
+                    false => position.checked_add(offset).filter(|&target| target < self.partition.len()),
+                };
+                match (target, call.args.get(2)) {
+                    (Some(target), _) => evaluate(&call.args[0], self.scope, self.row(target)),
+                    (None, Some(default)) => evaluate(default, self.scope, row),
+                    (None, None) => Ok(Value::Null),
+                }
+            }
+            name => {
+                let (start, end) = self.frame(position, peers)?;
+                match name {
+                    "FIRST_VALUE" if start < end => evaluate(&call.args[0], self.scope, self.row(start)),
+                    "LAST_VALUE" if start < end => evaluate(&call.args[0], self.scope, self.row(end - 1)),
+                    "FIRST_VALUE" | "LAST_VALUE" => Ok(Value::Null),
+                    _ => aggregate::compute(call, self.scope, (start..end).map(|position| self.row(position))),
+                }
+            }
+        }
+    }
+
+    /// The positions `start..end` of the window frame of the row at `position`.
+    ///
+    /// Without a frame clause the frame runs from the start of the partition
+    /// to the current row's last peer
+    fn frame(&self, position: usize, peers: Peers) -> Result<(usize, usize)> {
+        let default = WindowFrame { units: FrameUnits::Range, start: FrameBound::UnboundedPreceding, end: FrameBound::CurrentRow };
+        let frame = window(self.call).frame.as_ref().unwrap_or(&default);
+
+        let start = self.bound(frame, &frame.start, position, peers, false)?;
+        let end = self.bound(frame, &frame.end, position, peers, true)?;
+        Ok((start, end.max(start)))
+    }
+
+    /// The position a frame bound stands for: the first position in the frame
+    /// for its start, or one past the last for its end
+    fn bound(&self, frame: &WindowFrame, bound: &FrameBound, position: usize, peers: Peers, is_end: bool) -> Result<usize> {
+        let len = self.partition.len();
+
+        let (offset, preceding) = match bound {
+            FrameBound::UnboundedPreceding => return Ok(0),
+            FrameBound::UnboundedFollowing => return Ok(len),
+            FrameBound::CurrentRow => {
+                return Ok(match (frame.units, is_end) {
+                    (FrameUnits::Rows, false) => position,
+                    (FrameUnits::Rows, true) => position + 1,
+                    (FrameUnits::Range, false) => peers.start,
+                    (FrameUnits::Range, true) => peers.end,
+                });
+            }
+            FrameBound::Preceding(offset) => (offset, true),
+            FrameBound::Following(offset) => (offset, false),
+        };
+
+        let offset = evaluate(offset, &Scope::empty(), &[])?;
+        let negative = match &offset {
+            Value::Int(int) => *int < 0,
+            Value::Float(float) => *float < 0.0 || float.is_nan(),
+            Value::Decimal(decimal) => decimal.is_negative(),
+            Value::Interval(interval) => interval.length() < 0,
+            _ => true,
+        };
+        if negative || (frame.units == FrameUnits::Rows && !matches!(offset, Value::Int(_))) {
+            return Err(KirinError::InvalidArgument(format!("frame offset {offset} must be a non-negative number of rows or distance")));
+        }
+
+        if let (FrameUnits::Rows, Value::Int(offset)) = (frame.units, &offset) {
+            let offset = *offset as usize;
+            let target = match preceding {
+                true => position.checked_sub(offset),
+                false => Some(position.saturating_add(offset)),
+            };
+            // A bound before the partition start leaves nothing before it
+            return Ok(target.map_or(0, |target| target.saturating_add(is_end as usize).min(len)));
+        }
+
+        // A RANGE offset is a distance from the current row's sort key: a
+        // number for numeric keys, an interval for dates and timestamps
+        let [key] = self.order_by() else {
+            return Err(KirinError::InvalidWindow(String::from("a RANGE frame with an offset needs exactly one ORDER BY key")));
+        };
+        let current = self.sort_keys[self.partition[position]][0].clone();
+        let op = match preceding != key.descending {
+            true => BinaryOp::Sub,
+            false => BinaryOp::Add,
+        };
+        let boundary = binary(op, current, offset)?;
+
+        Ok(self.partition.partition_point(|&index| {
+            // A date moved by an interval becomes a timestamp, which dates compare with at midnight
+            let ordering = match (&self.sort_keys[index][0], &boundary) {
+                (Value::Date(date), Value::DateTime(_)) => {
+                    compare_sort_values(key, &Value::DateTime(date.and_time(NaiveTime::MIN).and_utc()), &boundary)
+                }
+                (value, boundary) => compare_sort_values(key, value, boundary),
+            };
+            match is_end {
+                true => ordering.is_le(),
+                false => ordering.is_lt(),
+            }
+        }))
+    }
+}
+
+/// The 1-based bucket of the row at `position` when `len` rows are split into
+/// `buckets` groups as evenly as possible, larger groups first
+fn ntile(position: usize, len: usize, buckets: usize) -> i64 {
+    let size = len / buckets;
+    let larger = len % buckets;
+
+    let bucket = match position < larger * (size + 1) {
+        true => position / (size + 1),
+        false => larger + (position - larger * (size + 1)) / size,
+    };
+    bucket as i64 + 1
+}
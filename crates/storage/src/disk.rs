@@ -20,6 +20,10 @@ impl Storage for DiskStorage {
         unimplemented!("Disk-backed get not implemented yet")
     }
 
+    fn update(&mut self, row_id: RowId, row: Row) -> bool {
+        unimplemented!("Disk-backed update not implemented yet")
+    }
+
     fn delete(&mut self, row_id: RowId) -> bool {
         unimplemented!("Disk-backed delete not implemented yet")
     }
@@ -27,4 +31,8 @@ impl Storage for DiskStorage {
     fn iter(&self) -> Box<dyn Iterator<Item = &Row> + '_> {
         unimplemented!("Disk-backed iter not implemented yet")
     }
+
+    fn iter_with_ids(&self) -> Box<dyn Iterator<Item = (RowId, &Row)> + '_> {
+        unimplemented!("Disk-backed iter not implemented yet")
+    }
 }
@@ -1,16 +1,17 @@
 use core::{Row, RowId, Storage};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// In memory storage implementation
-
+///
+/// Rows are kept ordered by RowId, so iteration follows insertion order
 pub struct MemoryStorage {
-    data: HashMap<RowId, Row>,
+    data: BTreeMap<RowId, Row>,
     next_id: RowId
 }
 
 impl MemoryStorage {
     pub fn new() -> Self {
-        let data = HashMap::new();
+        let data = BTreeMap::new();
         let next_id = 0;
 
         Self {
@@ -20,6 +21,12 @@ impl MemoryStorage {
     }
 }
 
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Storage for MemoryStorage {
     fn insert(&mut self, row: Row) -> RowId {
         let id = self.next_id;
@@ -34,6 +41,16 @@ impl Storage for MemoryStorage {
         self.data.get(&row_id)
     }
 
+    fn update(&mut self, row_id: RowId, row: Row) -> bool {
+        match self.data.get_mut(&row_id) {
+            Some(existing) => {
+                *existing = row;
+                true
+            }
+            None => false,
+        }
+    }
+
     fn delete(&mut self, row_id: RowId) -> bool {
         self.data.remove(&row_id).is_some()
     }
@@ -41,4 +58,8 @@ impl Storage for MemoryStorage {
     fn iter(&self) -> Box<dyn Iterator<Item = &Row> + '_> {
         Box::new(self.data.values())
     }
-}
\ No newline at end of file
+
+    fn iter_with_ids(&self) -> Box<dyn Iterator<Item = (RowId, &Row)> + '_> {
+        Box::new(self.data.iter().map(|(id, row)| (*id, row)))
+    }
+}
@@ -1,4 +1,4 @@
-use core::{Column, Table, Value, ValueType};
+use core::{parse_time_zone, Column, KirinError, Table, Value, ValueType};
 
 use storage::MemoryStorage;
 
@@ -6,13 +6,13 @@ use storage::MemoryStorage;
 #[test]
 fn insert_and_retrieve_row() {
     let columns = vec![
-        Column { name: "id".into(), col_type: ValueType::Int },
-        Column { name: "name".into(), col_type: ValueType::Text },
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
     ];
     let mut table = Table::new(columns, MemoryStorage::new());
 
     let inserted = table.insert(vec![Value::Int(1), Value::Text("Alice".into())]);
-    assert!(inserted.is_some(), "Insert should succeed");
+    assert!(inserted.is_ok(), "Insert should succeed");
 
     let row_id = inserted.unwrap();
     let row = table.get(row_id).expect("Row should exist");
@@ -25,21 +25,24 @@ fn insert_and_retrieve_row() {
 #[test]
 fn insert_fails_on_column_mismatch() {
     let columns = vec![
-        Column { name: "id".into(), col_type: ValueType::Int },
-        Column { name: "name".into(), col_type: ValueType::Text },
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
     ];
     let mut table = Table::new(columns, MemoryStorage::new());
 
     // Only one value instead of two
     let result = table.insert(vec![Value::Int(42)]);
-    assert!(result.is_none(), "Insert should fail on column count mismatch");
+    assert!(
+        matches!(result, Err(KirinError::ArityMismatch { expected: 2, actual: 1 })),
+        "Insert should fail on column count mismatch"
+    );
 }
 
 #[test]
 fn insert_fails_on_type_mismatch() {
     let columns = vec![
-        Column { name: "id".into(), col_type: ValueType::Int },
-        Column { name: "name".into(), col_type: ValueType::Text },
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
     ];
     let mut table = Table::new(columns, MemoryStorage::new());
 
@@ -48,19 +51,22 @@ fn insert_fails_on_type_mismatch() {
         Value::Text("Not an Int".into()),
         Value::Text("Alice".into())
     ]);
-    assert!(result.is_none(), "Insert should fail on type mismatch");
+    assert!(
+        matches!(result, Err(KirinError::TypeMismatch { expected: ValueType::Int, actual: ValueType::Text, .. })),
+        "Insert should fail on type mismatch"
+    );
 }
 
 #[test]
 fn iterate_returns_all_rows() {
     let columns = vec![
-        Column { name: "id".into(), col_type: ValueType::Int },
-        Column { name: "name".into(), col_type: ValueType::Text },
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
     ];
     let mut table = Table::new(columns, MemoryStorage::new());
 
-    table.insert(vec![Value::Int(1), Value::Text("Alice".into())]);
-    table.insert(vec![Value::Int(2), Value::Text("Bob".into())]);
+    table.insert(vec![Value::Int(1), Value::Text("Alice".into())]).unwrap();
+    table.insert(vec![Value::Int(2), Value::Text("Bob".into())]).unwrap();
 
     let rows: Vec<_> = table.iter().collect();
     assert_eq!(rows.len(), 2);
@@ -70,3 +76,141 @@ fn iterate_returns_all_rows() {
     assert!(names.contains(&Value::Text("Bob".into())));
 
 }
+
+#[test]
+fn add_column_backfills_existing_rows() {
+    let columns = vec![
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+    ];
+    let mut table = Table::new(columns, MemoryStorage::new());
+    let row_id = table.insert(vec![Value::Int(1)]).unwrap();
+
+    let missing_default = table.add_column(Column { name: "score".into(), col_type: ValueType::Float, nullable: false }, None);
+    assert!(matches!(missing_default, Err(KirinError::NotNull(_))), "NOT NULL column needs a default");
+
+    let wrong_type = table.add_column(Column { name: "score".into(), col_type: ValueType::Float, nullable: true }, Some(Value::Text("none".into())));
+    assert!(matches!(wrong_type, Err(KirinError::TypeMismatch { .. })));
+
+    table.add_column(Column { name: "score".into(), col_type: ValueType::Float, nullable: true }, Some(Value::Float(0.5))).unwrap();
+
+    assert_eq!(table.columns.len(), 2);
+    assert_eq!(table.get(row_id).unwrap().values, vec![Value::Int(1), Value::Float(0.5)]);
+    assert!(table.insert(vec![Value::Int(2), Value::Float(1.0)]).is_ok());
+}
+
+#[test]
+fn drop_and_rename_columns() {
+    let columns = vec![
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
+    ];
+    let mut table = Table::new(columns, MemoryStorage::new());
+    let row_id = table.insert(vec![Value::Int(1), Value::Text("Alice".into())]).unwrap();
+
+    table.rename_column("NAME", "full_name").unwrap();
+    assert!(matches!(table.rename_column("id", "Full_Name"), Err(KirinError::ColumnExists(_))));
+
+    table.drop_column("id").unwrap();
+    assert!(matches!(table.drop_column("id"), Err(KirinError::UnknownColumn(_))));
+
+    assert_eq!(table.columns[0].name, "full_name");
+    assert_eq!(table.get(row_id).unwrap().values, vec![Value::Text("Alice".into())]);
+}
+
+#[test]
+fn update_and_delete_rows() {
+    let columns = vec![
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
+    ];
+    let mut table = Table::new(columns, MemoryStorage::new());
+    let row_id = table.insert(vec![Value::Int(1), Value::Text("Alice".into())]).unwrap();
+
+    let result = table.update(row_id, vec![Value::Int(1), Value::Int(2)]);
+    assert!(matches!(result, Err(KirinError::TypeMismatch { .. })), "Update should be type checked");
+
+    assert!(table.update(row_id, vec![Value::Int(1), Value::Text("Alicia".into())]).unwrap());
+    assert_eq!(table.get(row_id).unwrap().values[1], Value::Text("Alicia".into()));
+
+    assert!(table.delete(row_id));
+    assert!(!table.delete(row_id));
+    assert!(table.get(row_id).is_none());
+    assert!(!table.update(row_id, vec![Value::Int(1), Value::Text("Alice".into())]).unwrap());
+}
+
+#[test]
+fn null_is_only_accepted_by_nullable_columns() {
+    let columns = vec![
+        Column { name: "id".into(), col_type: ValueType::Int, nullable: false },
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
+    ];
+    let mut table = Table::new(columns, MemoryStorage::new());
+
+    let row_id = table.insert(vec![Value::Int(1), Value::Null]).expect("NULL fits a nullable column");
+
+    let result = table.insert(vec![Value::Null, Value::Text("Alice".into())]);
+    assert!(matches!(result, Err(KirinError::NotNull(name)) if name == "id"));
+
+    // New nullable columns are backfilled with NULL
+    table.add_column(Column { name: "score".into(), col_type: ValueType::Float, nullable: true }, None).unwrap();
+    assert_eq!(table.get(row_id).unwrap().values, vec![Value::Int(1), Value::Null, Value::Null]);
+}
+
+#[test]
+fn values_are_converted_to_column_types_without_loss() {
+    let columns = vec![
+        Column { name: "count".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "height".into(), col_type: ValueType::Float, nullable: true },
+        Column { name: "born".into(), col_type: ValueType::DateTime, nullable: true },
+    ];
+    let mut table = Table::new(columns, MemoryStorage::new());
+
+    let row_id = table.insert(vec![Value::Float(3.0), Value::Int(170), Value::Text("2026-01-01 12:00:00".into())]).unwrap();
+    let values = &table.get(row_id).unwrap().values;
+    assert!(matches!(values.as_slice(), [Value::Int(3), Value::Float(170.0), Value::DateTime(_)]));
+
+    let fraction = table.insert(vec![Value::Float(2.5), Value::Null, Value::Null]);
+    assert!(matches!(fraction, Err(KirinError::InvalidConversion { expected: ValueType::Int, .. })));
+
+    // 2^53 + 1 has no exact float
+    let too_precise = table.insert(vec![Value::Null, Value::Int(9_007_199_254_740_993), Value::Null]);
+    assert!(matches!(too_precise, Err(KirinError::InvalidConversion { expected: ValueType::Float, .. })));
+
+    let not_a_date = table.insert(vec![Value::Null, Value::Null, Value::Text("soon".into())]);
+    assert!(matches!(not_a_date, Err(KirinError::InvalidConversion { expected: ValueType::DateTime, .. })));
+
+    let no_conversion = table.insert(vec![Value::Bool(true), Value::Null, Value::Null]);
+    assert!(matches!(no_conversion, Err(KirinError::TypeMismatch { expected: ValueType::Int, actual: ValueType::Bool, .. })));
+}
+
+#[test]
+fn timestamps_with_a_time_zone_are_read_in_the_table_time_zone() {
+    let columns = vec![Column { name: "at".into(), col_type: ValueType::DateTimeTz, nullable: true }];
+    let mut table = Table::new(columns, MemoryStorage::new());
+    table.set_time_zone(parse_time_zone("+02:00").unwrap());
+
+    let row_id = table.insert(vec![Value::Text("2026-01-01 12:00:00".into())]).unwrap();
+    assert_eq!(table.get(row_id).unwrap().values[0].to_string(), "2026-01-01T12:00:00+02:00");
+
+    table.add_column(Column { name: "since".into(), col_type: ValueType::DateTimeTz, nullable: true }, Some(Value::Text("2026-01-01".into()))).unwrap();
+    assert_eq!(table.get(row_id).unwrap().values[1].to_string(), "2026-01-01T00:00:00+02:00");
+}
+
+#[test]
+fn decimal_columns_round_to_their_scale() {
+    let price = ValueType::Decimal { precision: 5, scale: 2 };
+    let columns = vec![Column { name: "price".into(), col_type: price, nullable: true }];
+    let mut table = Table::new(columns, MemoryStorage::new());
+    let decimal = |text: &str| Value::Decimal(text.parse().unwrap());
+
+    for (value, stored) in [(Value::Int(12), "12.00"), (Value::Float(0.1), "0.10"), (decimal("1.005"), "1.01"), (decimal("-2.345"), "-2.35")] {
+        let row_id = table.insert(vec![value]).unwrap();
+        let values = &table.get(row_id).unwrap().values;
+        assert_eq!(values[0].to_string(), stored);
+    }
+
+    let too_large = table.insert(vec![decimal("1000.00")]);
+    assert!(matches!(too_large, Err(KirinError::InvalidConversion { expected, .. }) if expected == price));
+    let not_a_number = table.insert(vec![Value::Float(f64::NAN)]);
+    assert!(matches!(not_a_number, Err(KirinError::InvalidConversion { .. })));
+}
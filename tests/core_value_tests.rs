@@ -0,0 +1,240 @@
+use std::cmp::Ordering;
+
+use bigdecimal::{BigDecimal, Zero};
+use core::{decimal, KirinError, Value, ValueType};
+
+
+#[test]
+fn total_cmp_orders_floats() {
+    assert_eq!(Value::Float(-0.0).total_cmp(&Value::Float(0.0)), Ordering::Equal);
+    assert_eq!(Value::Float(f64::NAN).total_cmp(&Value::Float(f64::NAN)), Ordering::Equal);
+    assert_eq!(Value::Float(f64::NAN).total_cmp(&Value::Float(f64::INFINITY)), Ordering::Greater);
+    assert_eq!(Value::Float(f64::NEG_INFINITY).total_cmp(&Value::Float(-1e300)), Ordering::Less);
+}
+
+#[test]
+fn total_cmp_compares_integers_and_floats_exactly() {
+    assert_eq!(Value::Int(1).total_cmp(&Value::Float(1.0)), Ordering::Equal);
+    assert_eq!(Value::Int(1).total_cmp(&Value::Float(1.5)), Ordering::Less);
+    assert_eq!(Value::Int(-1).total_cmp(&Value::Float(-1.5)), Ordering::Greater);
+    assert_eq!(Value::Int(0).total_cmp(&Value::Float(-0.0)), Ordering::Equal);
+    assert_eq!(Value::Int(i64::MAX).total_cmp(&Value::Float(i64::MAX as f64)), Ordering::Less);
+    assert_eq!(Value::Float(f64::NAN).total_cmp(&Value::Int(i64::MAX)), Ordering::Greater);
+}
+
+#[test]
+fn total_cmp_orders_values_of_different_types() {
+    let mut values = vec![
+        Value::Text("a".into()),
+        Value::Float(2.5),
+        Value::Null,
+        Value::Int(3),
+        Value::Bool(true),
+        Value::Int(-7),
+    ];
+    values.sort_by(Value::total_cmp);
+
+    assert_eq!(values, vec![
+        Value::Null,
+        Value::Bool(true),
+        Value::Int(-7),
+        Value::Float(2.5),
+        Value::Int(3),
+        Value::Text("a".into()),
+    ]);
+}
+
+#[test]
+fn equal_values_hash_alike() {
+    use std::collections::HashSet;
+
+    let values = [
+        Value::Int(1),
+        Value::Float(1.0),
+        Value::Float(0.0),
+        Value::Float(-0.0),
+        Value::Int(0),
+        Value::Float(f64::NAN),
+        Value::Float(-f64::NAN),
+        Value::Float(1.5),
+        Value::Text("1".into()),
+        Value::Null,
+    ];
+    let distinct: HashSet<_> = values.iter().cloned().collect();
+
+    assert_eq!(distinct.len(), 6);
+    assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+    assert_ne!(Value::Int(1), Value::Text("1".into()));
+    assert!(Value::Int(2) > Value::Float(1.5));
+}
+
+#[test]
+fn cast_to_follows_the_conversion_matrix() {
+    let text = |text: &str| Value::Text(text.into());
+    let cast = |value: Value, target| value.cast_to(target).unwrap();
+
+    assert_eq!(cast(text(" 42 "), ValueType::Int), Value::Int(42));
+    assert_eq!(cast(text("2.5"), ValueType::Float), Value::Float(2.5));
+    assert_eq!(cast(text("Yes"), ValueType::Bool), Value::Bool(true));
+    assert_eq!(cast(text("off"), ValueType::Bool), Value::Bool(false));
+    assert_eq!(cast(text("2026-01-01"), ValueType::DateTime), Value::DateTime(core::parse_datetime("2026-01-01").unwrap()));
+    assert_eq!(cast(Value::Int(7), ValueType::Float), Value::Float(7.0));
+    assert_eq!(cast(Value::Float(2.5), ValueType::Int), Value::Int(3));
+    assert_eq!(cast(Value::Float(-2.5), ValueType::Int), Value::Int(-3));
+    assert_eq!(cast(Value::Int(2), ValueType::Bool), Value::Bool(true));
+    assert_eq!(cast(Value::Bool(true), ValueType::Int), Value::Int(1));
+    assert_eq!(cast(Value::Bool(false), ValueType::Text), text("false"));
+    assert_eq!(cast(Value::Float(1.5), ValueType::Text), text("1.5"));
+    assert_eq!(cast(Value::Null, ValueType::Int), Value::Null);
+    assert_eq!(cast(text("a"), ValueType::Text), text("a"));
+
+    // Every pair CAST accepts for some values, and none other
+    let types = [ValueType::Text, ValueType::Int, ValueType::Float, ValueType::Bool, ValueType::DateTime];
+    let castable: usize = types.iter().map(|from| types.iter().filter(|to| from.can_cast_to(**to)).count()).sum();
+    assert_eq!(castable, 5 + 4 + 3 + 3 + 2);
+    assert!(!ValueType::DateTime.can_cast_to(ValueType::Int));
+    assert!(!ValueType::Float.can_cast_to(ValueType::Bool));
+}
+
+#[test]
+fn cast_to_reports_values_that_do_not_convert() {
+    let failures = [
+        (Value::Text("abc".into()), ValueType::Int),
+        (Value::Text("1.5".into()), ValueType::Int),
+        (Value::Text("maybe".into()), ValueType::Bool),
+        (Value::Text("soon".into()), ValueType::DateTime),
+        (Value::Float(f64::NAN), ValueType::Int),
+        (Value::Float(1e20), ValueType::Int),
+        (Value::Bool(true), ValueType::Float),
+        (Value::DateTime(core::parse_datetime("2026-01-01").unwrap()), ValueType::Int),
+    ];
+
+    for (value, target) in failures {
+        let error = value.cast_to(target).unwrap_err();
+        let expected = ValueType::from(&value);
+        assert!(matches!(error, KirinError::InvalidCast { from, to, .. } if from == expected && to == target), "{value:?} to {target}");
+    }
+}
+
+#[test]
+fn decimals_compare_and_hash_with_other_numbers() {
+    use std::collections::HashSet;
+
+    let decimal = |text: &str| Value::Decimal(text.parse::<BigDecimal>().unwrap());
+
+    assert_eq!(decimal("1.50").to_string(), "1.50");
+    assert_eq!(decimal("0.00001").to_string(), "0.00001");
+    assert_eq!(decimal("1.0"), Value::Int(1));
+    assert_eq!(decimal("1.50"), decimal("1.5"));
+    // A float compares as the shortest decimal that reads back as it
+    assert_eq!(decimal("0.1"), Value::Float(0.1));
+    assert_eq!(decimal("0.10000000000000000001").total_cmp(&Value::Float(0.1)), Ordering::Greater);
+    assert_eq!(decimal("1e30").total_cmp(&Value::Float(f64::INFINITY)), Ordering::Less);
+    assert_eq!(decimal("-1e30").total_cmp(&Value::Float(f64::NEG_INFINITY)), Ordering::Greater);
+    assert_eq!(decimal("1e30").total_cmp(&Value::Float(f64::NAN)), Ordering::Less);
+    assert_eq!(decimal("9223372036854775808").total_cmp(&Value::Int(i64::MAX)), Ordering::Greater);
+
+    let values = [decimal("1.00"), Value::Int(1), Value::Float(1.0), decimal("0.1"), Value::Float(0.1), decimal("0.100"), decimal("1e20"), Value::Float(1e20)];
+    let distinct: HashSet<&Value> = values.iter().collect();
+    assert_eq!(distinct.len(), 3);
+}
+
+#[test]
+fn cast_to_decimal_rounds_to_the_scale() {
+    let decimal = |text: &str| Value::Decimal(text.parse::<BigDecimal>().unwrap());
+    let money = ValueType::Decimal { precision: 6, scale: 2 };
+
+    assert_eq!(Value::Text(" 12.345 ".into()).cast_to(money).unwrap().to_string(), "12.35");
+    assert_eq!(Value::Float(-0.125).cast_to(money).unwrap().to_string(), "-0.13");
+    assert_eq!(Value::Int(42).cast_to(money).unwrap().to_string(), "42.00");
+    assert_eq!(decimal("2.5").cast_to(ValueType::Int).unwrap(), Value::Int(3));
+    assert_eq!(decimal("2.25").cast_to(ValueType::Float).unwrap(), Value::Float(2.25));
+    assert_eq!(decimal("2.50").cast_to(ValueType::Text).unwrap(), Value::Text("2.50".into()));
+    assert_eq!(ValueType::from(&decimal("0.05")), ValueType::Decimal { precision: 2, scale: 2 });
+
+    for (value, target) in [
+        (Value::Int(10_000), money),
+        (Value::Text("lots".into()), money),
+        (Value::Float(f64::INFINITY), money),
+        (decimal("1e30"), ValueType::Int),
+        (Value::Bool(true), money),
+    ] {
+        assert!(matches!(value.cast_to(target), Err(KirinError::InvalidCast { to, .. }) if to == target), "{value:?} to {target}");
+    }
+    assert!(!money.can_cast_to(ValueType::Bool));
+}
+
+#[test]
+fn decimals_with_huge_exponents_are_bounded_before_rounding() {
+    let decimal = |text: &str| text.parse::<BigDecimal>().unwrap();
+    let money = ValueType::Decimal { precision: 10, scale: 2 };
+
+    assert!(matches!(Value::Text("1e999999999".into()).cast_to(money), Err(KirinError::InvalidCast { .. })));
+    assert!(matches!(Value::Text("-1e999999999".into()).cast_to(money), Err(KirinError::InvalidCast { .. })));
+    assert_eq!(Value::Text("1e-999999999".into()).cast_to(money).unwrap().to_string(), "0.00");
+    assert_eq!(Value::Text("0e999999999".into()).cast_to(money).unwrap().to_string(), "0.00");
+    assert_eq!(Value::Text("0.005".into()).cast_to(money).unwrap().to_string(), "0.01");
+    assert_eq!(Value::Text("0.0049".into()).cast_to(money).unwrap().to_string(), "0.00");
+    assert_eq!(Value::Text("0.5".into()).cast_to(ValueType::Decimal { precision: 1, scale: 1 }).unwrap().to_string(), "0.5");
+
+    assert!(matches!(decimal::divide(&decimal("1e999999999"), &decimal("3")), Err(KirinError::Overflow)));
+    assert!(matches!(decimal::divide(&decimal("1"), &decimal("1e-999999999")), Err(KirinError::Overflow)));
+    assert!(decimal::divide(&decimal("1e-999999999"), &decimal("3")).unwrap().is_zero());
+    assert_eq!(decimal::divide(&decimal("1"), &decimal("8")).unwrap().to_string(), "0.1250000000000000");
+    assert!(matches!(decimal::divide(&decimal("1"), &decimal("0")), Err(KirinError::DivisionByZero)));
+}
+
+#[test]
+fn blobs_order_by_bytes_and_convert_through_utf8() {
+    let blob = |bytes: &[u8]| Value::Blob(bytes.into());
+
+    assert_eq!(blob(&[0xDE, 0xAD, 0x01]).to_string(), "X'DEAD01'");
+    assert_eq!(blob(&[]).to_string(), "X''");
+    assert_eq!(blob(&[1, 2]).total_cmp(&blob(&[1, 2, 0])), Ordering::Less);
+    assert_eq!(blob(&[0xFF]).total_cmp(&Value::Text("zzz".into())), Ordering::Greater);
+    assert_eq!(blob(&[0]).total_cmp(&Value::DateTime(core::parse_datetime("2026-01-01").unwrap())), Ordering::Less);
+
+    assert_eq!(Value::Text("hé".into()).cast_to(ValueType::Blob).unwrap(), blob(&[b'h', 0xC3, 0xA9]));
+    assert_eq!(blob(b"abc").cast_to(ValueType::Text).unwrap(), Value::Text("abc".into()));
+    assert!(matches!(blob(&[0xFF]).cast_to(ValueType::Text), Err(KirinError::InvalidCast { from: ValueType::Blob, .. })));
+    assert!(!ValueType::Blob.can_cast_to(ValueType::Int));
+
+    assert_eq!(core::parse_hex("00fFa1"), Some(vec![0x00, 0xFF, 0xA1]));
+    assert_eq!(core::parse_hex("abc"), None);
+    assert_eq!(core::parse_hex("zz"), None);
+    assert_eq!(core::to_hex(&[0x0A, 0xBC]), "0ABC");
+}
+
+#[test]
+fn dates_times_and_intervals_cast_and_compare() {
+    let text = |text: &str| Value::Text(text.into());
+    let interval = |text: &str| Value::Interval(core::Interval::parse(text).unwrap());
+
+    let date = text(" 2026-03-31 ").cast_to(ValueType::Date).unwrap();
+    assert_eq!(date.to_string(), "2026-03-31");
+    assert_eq!(text("12:30").cast_to(ValueType::Time).unwrap().to_string(), "12:30:00");
+    assert_eq!(interval("1.5 years -2 days 1:30").to_string(), "1 year 6 mons -2 days 01:30:00");
+    assert_eq!(interval("90 minutes"), interval("01:30:00"));
+    assert_eq!(interval("1 month"), interval("30 days"));
+    assert_eq!(interval("0 seconds").to_string(), "00:00:00");
+    assert!(core::Interval::parse("3 fortnights").is_none());
+    assert!(matches!(text("yesterday").cast_to(ValueType::Date), Err(KirinError::InvalidCast { to: ValueType::Date, .. })));
+
+    // Timestamps with a time zone read local times in the zone and compare by instant
+    let zone = core::parse_time_zone("+02:00").unwrap();
+    let local = text("2026-01-01 12:00:00").cast_in_zone(ValueType::DateTimeTz, zone).unwrap();
+    assert_eq!(local.to_string(), "2026-01-01T12:00:00+02:00");
+    let utc = Value::DateTime(core::parse_datetime("2026-01-01T10:00:00Z").unwrap());
+    assert_eq!(local, utc);
+    assert_eq!(local.cast_to(ValueType::DateTime).unwrap(), utc);
+    assert_eq!(local.cast_in_zone(ValueType::Date, zone).unwrap().to_string(), "2026-01-01");
+    assert_eq!(date.cast_in_zone(ValueType::DateTimeTz, zone).unwrap().to_string(), "2026-03-31T00:00:00+02:00");
+    assert_eq!(core::parse_time_zone("utc"), core::parse_time_zone("+00:00"));
+    assert_eq!(core::parse_time_zone("-0530").unwrap().to_string(), "-05:30");
+    assert!(core::parse_time_zone("Mars/Olympus").is_none());
+
+    assert!(ValueType::Date.can_cast_to(ValueType::DateTimeTz));
+    assert!(!ValueType::Interval.can_cast_to(ValueType::Int));
+    assert_eq!(date.total_cmp(&utc), Ordering::Less);
+    assert_eq!(interval("1 day").total_cmp(&interval("23:59:59")), Ordering::Greater);
+}
@@ -8,15 +8,15 @@ use storage::MemoryStorage;
     fn handle_select_displays_table_correctly() {
 
         let columns = vec![
-            Column { name: "id".into(), col_type: ValueType::Int },
-            Column { name: "name".into(), col_type: ValueType::Text },
+            Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+            Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
         ];
 
         let mut database: Database = Database::new();
         let mut table = Table::new(columns, MemoryStorage::new());
 
-        table.insert(vec![Value::Int(1), Value::Text("Alice".into())]);
-        table.insert(vec![Value::Int(2), Value::Text("Bob".into())]);
+        table.insert(vec![Value::Int(1), Value::Text("Alice".into())]).unwrap();
+        table.insert(vec![Value::Int(2), Value::Text("Bob".into())]).unwrap();
         database.add_table("users",table);
 
         // Create a buffer to capture output
@@ -41,8 +41,8 @@ use storage::MemoryStorage;
     #[test]
     fn handle_select_on_empty_table_shows_zero_rows() {
         let columns = vec![
-            Column { name: "id".into(), col_type: ValueType::Int },
-            Column { name: "name".into(), col_type: ValueType::Text },
+            Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+            Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
         ];
 
         let mut database: Database = Database::new();
@@ -70,8 +70,8 @@ use storage::MemoryStorage;
     #[test]
     fn malformed_select_shows_error_message() {
         let columns = vec![
-            Column { name: "id".into(), col_type: ValueType::Int },
-            Column { name: "name".into(), col_type: ValueType::Text },
+            Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+            Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
         ];
 
         let mut database: Database = Database::new();
@@ -93,8 +93,8 @@ use storage::MemoryStorage;
         #[test]
     fn select_from_missing_table_shows_error_message() {
         let columns = vec![
-            Column { name: "id".into(), col_type: ValueType::Int },
-            Column { name: "name".into(), col_type: ValueType::Text },
+            Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+            Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
         ];
 
         let mut database: Database = Database::new();
@@ -113,3 +113,99 @@ use storage::MemoryStorage;
         assert!(printed.contains("not found"));
     }
 
+    #[test]
+    fn insert_accepts_quoted_strings_with_commas() {
+        let columns = vec![
+            Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+            Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
+        ];
+
+        let mut database: Database = Database::new();
+        database.add_table("users", Table::new(columns, MemoryStorage::new()));
+        let mut output = Vec::new();
+
+        {
+            let mut shell = Shell::new(database, &mut output);
+            shell.handle_insert("INSERT INTO users VALUES (1, 'Smith, Alice')").unwrap();
+            shell.handle_select("SELECT * FROM users").unwrap();
+        }
+
+        let printed = String::from_utf8(output).expect("Valid UTF-8");
+
+        assert!(printed.contains("Inserted row with id 0"));
+        assert!(printed.contains("| 1 | Smith, Alice |"));
+        assert!(!printed.contains("'Smith"));
+    }
+
+    #[test]
+    fn parse_error_is_underlined_with_caret() {
+        let mut output = Vec::new();
+
+        {
+            let mut shell = Shell::new(Database::new(), &mut output);
+            shell.handle_statement("SELECT * FRM users").unwrap();
+        }
+
+        let printed = String::from_utf8(output).expect("Valid UTF-8");
+
+        assert!(printed.contains("Expected FROM, found 'FRM'"));
+        assert!(printed.contains("  SELECT * FRM users\n"));
+        assert!(printed.contains("\n           ^^^\n"));
+    }
+
+    #[test]
+    fn error_on_multiline_string_is_underlined_to_end_of_line() {
+        let mut output = Vec::new();
+
+        {
+            let mut shell = Shell::new(Database::new(), &mut output);
+            shell.handle_statement("SELECT * FROM 'a\nb'").unwrap();
+        }
+
+        let printed = String::from_utf8(output).expect("Valid UTF-8");
+
+        assert!(printed.contains("  SELECT * FROM 'a\n"));
+        assert!(printed.contains("\n                ^^\n"));
+    }
+
+    #[test]
+    fn insert_type_mismatch_is_reported() {
+        let columns = vec![
+            Column { name: "id".into(), col_type: ValueType::Int, nullable: true },
+            Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
+        ];
+
+        let mut database: Database = Database::new();
+        database.add_table("users", Table::new(columns, MemoryStorage::new()));
+        let mut output = Vec::new();
+
+        {
+            let mut shell = Shell::new(database, &mut output);
+            shell.handle_insert("INSERT INTO users VALUES ('Alice', 1)").unwrap();
+        }
+
+        let printed = String::from_utf8(output).expect("Valid UTF-8");
+
+        assert!(printed.contains("Type mismatch for 'id': expected Integer, found Text"));
+    }
+
+    #[test]
+    fn blobs_are_displayed_as_hex() {
+        let columns = vec![Column { name: "data".into(), col_type: ValueType::Blob, nullable: true }];
+
+        let mut database: Database = Database::new();
+        let mut table = Table::new(columns, MemoryStorage::new());
+        table.insert(vec![Value::Blob(vec![0xDE, 0xAD, 0xBE, 0xEF].into())]).unwrap();
+        database.add_table("files", table);
+        let mut output = Vec::new();
+
+        {
+            let mut shell = Shell::new(database, &mut output);
+            shell.handle_select("SELECT data FROM files").unwrap();
+        }
+
+        let printed = String::from_utf8(output).expect("Valid UTF-8");
+
+        assert!(printed.contains("data <Blob>"));
+        assert!(printed.contains("| X'DEADBEEF' |"));
+    }
@@ -0,0 +1,75 @@
+use core::{Column, KirinError, Value, ValueType};
+
+use sql::eval::{evaluate, Scope};
+use sql::parser::parse_expr;
+
+
+/// Evaluates `input` against a single row of a `people (name, age, height)` table
+fn eval(input: &str) -> Result<Value, KirinError> {
+    let columns = vec![
+        Column { name: "name".into(), col_type: ValueType::Text, nullable: true },
+        Column { name: "age".into(), col_type: ValueType::Int, nullable: true },
+        Column { name: "height".into(), col_type: ValueType::Float, nullable: true },
+    ];
+    let scope = Scope::from_table("people", &columns);
+    let row = vec![Value::Text("Alice".into()), Value::Int(30), Value::Float(170.5)];
+
+    evaluate(&parse_expr(input)?, &scope, &row)
+}
+
+#[test]
+fn arithmetic_follows_precedence_and_widens() {
+    assert_eq!(eval("1 + 2 * 3").unwrap(), Value::Int(7));
+    assert_eq!(eval("(1 + 2) * 3").unwrap(), Value::Int(9));
+    assert_eq!(eval("7 / 2").unwrap(), Value::Int(3));
+    assert_eq!(eval("7 % 4").unwrap(), Value::Int(3));
+    assert_eq!(eval("age + 0.5").unwrap(), Value::Float(30.5));
+    assert_eq!(eval("-height").unwrap(), Value::Float(-170.5));
+}
+
+#[test]
+fn comparisons_and_boolean_logic() {
+    assert_eq!(eval("age = 30").unwrap(), Value::Bool(true));
+    assert_eq!(eval("age <> 30.0").unwrap(), Value::Bool(false));
+    assert_eq!(eval("people.name >= 'Alice' AND height < 171").unwrap(), Value::Bool(true));
+    assert_eq!(eval("NOT age > 40 OR FALSE").unwrap(), Value::Bool(true));
+    assert_eq!(eval("TIMESTAMP '2026-01-01T00:00:00Z' < TIMESTAMP '2026-06-01T00:00:00Z'").unwrap(), Value::Bool(true));
+}
+
+#[test]
+fn columns_resolve_case_insensitively() {
+    assert_eq!(eval("NAME || ' is ' || Age").unwrap(), Value::Text("Alice is 30".into()));
+    assert!(matches!(eval("weight > 1"), Err(KirinError::UnknownColumn(name)) if name == "weight"));
+    assert!(matches!(eval("other.age"), Err(KirinError::UnknownColumn(_))));
+}
+
+#[test]
+fn invalid_operations_are_errors() {
+    assert!(matches!(eval("name - 1"), Err(KirinError::InvalidOperands { .. })));
+    assert!(matches!(eval("age AND TRUE"), Err(KirinError::InvalidOperands { .. })));
+    assert!(matches!(eval("age / 0"), Err(KirinError::DivisionByZero)));
+    assert!(matches!(eval("9223372036854775807 + 1"), Err(KirinError::Overflow)));
+}
+
+#[test]
+fn null_propagates_with_three_valued_logic() {
+    assert_eq!(eval("NULL + 1").unwrap(), Value::Null);
+    assert_eq!(eval("age = NULL").unwrap(), Value::Null);
+    assert_eq!(eval("NOT NULL").unwrap(), Value::Null);
+    assert_eq!(eval("NULL AND FALSE").unwrap(), Value::Bool(false));
+    assert_eq!(eval("NULL AND TRUE").unwrap(), Value::Null);
+    assert_eq!(eval("NULL OR TRUE").unwrap(), Value::Bool(true));
+    assert_eq!(eval("NULL OR FALSE").unwrap(), Value::Null);
+    assert_eq!(eval("NULL IS NULL").unwrap(), Value::Bool(true));
+    assert_eq!(eval("age + NULL IS NOT NULL").unwrap(), Value::Bool(false));
+}
+
+#[test]
+fn coalesce_and_nullif() {
+    assert_eq!(eval("COALESCE(NULL, NULL, name)").unwrap(), Value::Text("Alice".into()));
+    assert_eq!(eval("coalesce(NULL)").unwrap(), Value::Null);
+    assert_eq!(eval("NULLIF(age, 30)").unwrap(), Value::Null);
+    assert_eq!(eval("NULLIF(age, 31)").unwrap(), Value::Int(30));
+    assert!(matches!(eval("NULLIF(age)"), Err(KirinError::ArityMismatch { expected: 2, actual: 1 })));
+    assert!(matches!(eval("NOPE(age)"), Err(KirinError::UnknownFunction(_))));
+}
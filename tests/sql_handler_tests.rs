@@ -0,0 +1,1181 @@
+use core::{KirinError, ValueType};
+
+use database::Database;
+use sql::handler::handle_statement;
+
+
+/// Executes `input` against the database, returning everything printed
+fn run(database: &mut Database, input: &str) -> Result<String, KirinError> {
+    let mut output = Vec::new();
+    handle_statement(database, &mut output, input)?;
+    Ok(String::from_utf8(output).expect("Valid UTF-8"))
+}
+
+/// The data rows of a printed result, skipping the row count, header and spacer
+fn result_rows(printed: &str) -> Vec<&str> {
+    printed.lines().skip(3).collect()
+}
+
+#[test]
+fn create_table_maps_type_names() {
+    let mut database = Database::new();
+
+    run(&mut database, "CREATE TABLE people (id INTEGER, name VARCHAR(20), height REAL, active BOOLEAN, born TIMESTAMP)")
+        .expect("CREATE TABLE should succeed");
+
+    let table = database.table("people").expect("Table should exist");
+    let types: Vec<ValueType> = table.columns.iter().map(|col| col.col_type).collect();
+    assert_eq!(types, vec![ValueType::Int, ValueType::Text, ValueType::Float, ValueType::Bool, ValueType::DateTime]);
+}
+
+#[test]
+fn create_table_then_insert_and_select() {
+    let mut database = Database::new();
+
+    let printed = run(&mut database, "
+        CREATE TABLE users (id INT, name TEXT);
+        INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob');
+        SELECT * FROM users;
+    ").unwrap();
+
+    assert!(printed.contains("Created table 'users'"));
+    assert!(printed.contains("(2 rows)"));
+    assert!(printed.contains("| 2 | Bob |"));
+}
+
+#[test]
+fn create_existing_table_fails_unless_if_not_exists() {
+    let mut database = Database::new();
+    run(&mut database, "CREATE TABLE users (id INT)").unwrap();
+
+    let error = run(&mut database, "CREATE TABLE Users (id INT)").unwrap_err();
+    assert!(matches!(error, KirinError::TableExists(name) if name == "Users"));
+
+    let printed = run(&mut database, "CREATE TABLE IF NOT EXISTS users (id INT)").unwrap();
+    assert!(printed.contains("already exists"));
+}
+
+#[test]
+fn create_table_rejects_unknown_types_and_duplicate_columns() {
+    let mut database = Database::new();
+
+    let error = run(&mut database, "CREATE TABLE t (id BLOBBY)").unwrap_err();
+    assert!(matches!(error, KirinError::Parse { .. }));
+
+    let error = run(&mut database, "CREATE TABLE t (id INT, ID TEXT)").unwrap_err();
+    assert!(matches!(error, KirinError::ColumnExists(name) if name == "ID"));
+    assert!(!database.has_table("t"));
+}
+
+#[test]
+fn drop_table_removes_table() {
+    let mut database = Database::new();
+    run(&mut database, "CREATE TABLE users (id INT)").unwrap();
+
+    run(&mut database, "DROP TABLE users").unwrap();
+    assert!(!database.has_table("users"));
+
+    let error = run(&mut database, "DROP TABLE users").unwrap_err();
+    assert!(matches!(error, KirinError::UnknownTable(_)));
+
+    let printed = run(&mut database, "DROP TABLE IF EXISTS users").unwrap();
+    assert!(printed.contains("does not exist"));
+}
+
+#[test]
+fn alter_table_reshapes_schema_and_rows() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE users (id INT, name TEXT);
+        INSERT INTO users VALUES (1, 'Alice');
+        ALTER TABLE users ADD COLUMN height FLOAT DEFAULT 170.5;
+        ALTER TABLE users RENAME COLUMN name TO full_name;
+        ALTER TABLE users DROP COLUMN id;
+        ALTER TABLE users RENAME TO people;
+    ").unwrap();
+
+    assert!(!database.has_table("users"));
+    let printed = run(&mut database, "SELECT * FROM people").unwrap();
+
+    assert!(printed.contains("| full_name <Text> | height <Float> |"));
+    assert!(printed.contains("| Alice | 170.5 |"));
+}
+
+#[test]
+fn select_where_filters_rows() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, height FLOAT);
+        INSERT INTO people VALUES ('Alice', 170.5), ('Bob', 183.2), ('Carol', 165.0);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT * FROM people WHERE height > 168 AND NOT (name = 'Bob')").unwrap();
+
+    assert!(printed.contains("(1 rows)"));
+    assert!(printed.contains("Alice"));
+    assert!(!printed.contains("Bob"));
+    assert!(!printed.contains("Carol"));
+}
+
+#[test]
+fn select_where_must_be_boolean() {
+    let mut database = Database::new();
+    run(&mut database, "CREATE TABLE people (name TEXT, height FLOAT); INSERT INTO people VALUES ('Alice', 170.5)").unwrap();
+
+    let error = run(&mut database, "SELECT * FROM people WHERE height + 1").unwrap_err();
+    assert!(matches!(error, KirinError::TypeMismatch { expected: ValueType::Bool, actual: ValueType::Float, .. }));
+
+    let error = run(&mut database, "SELECT * FROM people WHERE weight > 1").unwrap_err();
+    assert!(matches!(error, KirinError::UnknownColumn(_)));
+}
+
+#[test]
+fn select_projects_columns_and_expressions() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, height FLOAT, age INT);
+        INSERT INTO people VALUES ('Alice', 170.0, 30);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT NAME, height * 2.54 AS cm, age + 1, people.* FROM people").unwrap();
+
+    assert!(printed.contains("| name <Text> | cm <Float> | age + 1 <Integer> | name <Text> | height <Float> | age <Integer> |"));
+    assert!(printed.contains("| Alice | 431.8 | 31 | Alice | 170 | 30 |"));
+}
+
+#[test]
+fn select_without_from_evaluates_once() {
+    let mut database = Database::new();
+
+    let printed = run(&mut database, "SELECT 1 + 1 AS two, 'a' || 'b'").unwrap();
+
+    assert!(printed.contains("(1 rows)"));
+    assert!(printed.contains("| two <Integer> | 'a' || 'b' <Text> |"));
+    assert!(printed.contains("| 2 | ab |"));
+}
+
+#[test]
+fn select_projection_errors() {
+    let mut database = Database::new();
+    run(&mut database, "CREATE TABLE people (name TEXT, height FLOAT)").unwrap();
+
+    let error = run(&mut database, "SELECT others.* FROM people").unwrap_err();
+    assert!(matches!(error, KirinError::UnknownTable(name) if name == "others"));
+
+    // Type errors surface even when the table is empty
+    let error = run(&mut database, "SELECT name * 2 FROM people").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+}
+
+#[test]
+fn update_and_delete_report_affected_rows() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, height FLOAT);
+        INSERT INTO people VALUES ('Alice', 170.5), ('Bob', 183.2), ('Carol', 165.0);
+    ").unwrap();
+
+    let printed = run(&mut database, "UPDATE people SET height = height + 1.0, name = name || '!' WHERE height < 180").unwrap();
+    assert!(printed.contains("Updated 2 rows"));
+
+    let printed = run(&mut database, "DELETE FROM people WHERE name = 'Bob'").unwrap();
+    assert!(printed.contains("Deleted 1 rows"));
+
+    let printed = run(&mut database, "SELECT * FROM people WHERE height = 171.5").unwrap();
+    assert!(printed.contains("| Alice! | 171.5 |"));
+
+    let printed = run(&mut database, "DELETE FROM people").unwrap();
+    assert!(printed.contains("Deleted 2 rows"));
+}
+
+#[test]
+fn failed_update_leaves_rows_untouched() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, height FLOAT);
+        INSERT INTO people VALUES ('Alice', 170.5);
+    ").unwrap();
+
+    let error = run(&mut database, "UPDATE people SET height = 'tall'").unwrap_err();
+    assert!(matches!(error, KirinError::TypeMismatch { expected: ValueType::Float, actual: ValueType::Text, .. }));
+
+    let error = run(&mut database, "UPDATE people SET weight = 1.0").unwrap_err();
+    assert!(matches!(error, KirinError::UnknownColumn(_)));
+
+    let printed = run(&mut database, "SELECT * FROM people").unwrap();
+    assert!(printed.contains("| Alice | 170.5 |"));
+}
+
+#[test]
+fn null_handling_in_tables_and_queries() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT NOT NULL, height FLOAT);
+        INSERT INTO people VALUES ('Alice', 170.5), ('Bob', NULL);
+        INSERT INTO people (name) VALUES ('Carol');
+    ").unwrap();
+
+    let error = run(&mut database, "INSERT INTO people VALUES (NULL, 180.0)").unwrap_err();
+    assert!(matches!(error, KirinError::NotNull(name) if name == "name"));
+
+    let printed = run(&mut database, "SELECT * FROM people WHERE height > 100").unwrap();
+    assert!(printed.contains("(1 rows)"), "Comparisons with NULL are not true");
+
+    let printed = run(&mut database, "SELECT name, COALESCE(height, 0.0) AS h FROM people WHERE height IS NULL").unwrap();
+    assert!(printed.contains("(2 rows)"));
+    assert!(printed.contains("| h <Float> |"));
+    assert!(printed.contains("| Bob | 0 |"));
+    assert!(printed.contains("| Carol | 0 |"));
+
+    let printed = run(&mut database, "SELECT * FROM people WHERE name = 'Bob'").unwrap();
+    assert!(printed.contains("| Bob | NULL |"));
+}
+
+#[test]
+fn order_by_sorts_on_multiple_keys() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, age INT, height FLOAT);
+        INSERT INTO people VALUES ('Carol', 30, 165.0), ('Alice', 30, 170.5), ('Bob', 25, NULL), ('Dave', NULL, 180.0);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT name, age FROM people ORDER BY age DESC, name").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice | 30 |", "| Carol | 30 |", "| Bob | 25 |", "| Dave | NULL |"]);
+
+    // Output aliases and positions can be used as sort keys, as can unselected columns
+    let printed = run(&mut database, "SELECT name AS n FROM people ORDER BY height NULLS FIRST, 1").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Bob |", "| Carol |", "| Alice |", "| Dave |"]);
+
+    let printed = run(&mut database, "SELECT name AS n FROM people ORDER BY n DESC").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Dave |", "| Carol |", "| Bob |", "| Alice |"]);
+
+    let error = run(&mut database, "SELECT name FROM people ORDER BY 2").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+}
+
+#[test]
+fn limit_and_offset_page_through_results() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE numbers (n INT);
+        INSERT INTO numbers VALUES (5), (3), (1), (4), (2);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT n FROM numbers ORDER BY n LIMIT 2 OFFSET 1").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2 |", "| 3 |"]);
+
+    let printed = run(&mut database, "SELECT n FROM numbers LIMIT 10 OFFSET 3").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 4 |", "| 2 |"]);
+
+    let error = run(&mut database, "SELECT n FROM numbers LIMIT -1").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+
+    let error = run(&mut database, "SELECT n FROM numbers LIMIT 'ten'").unwrap_err();
+    assert!(matches!(error, KirinError::TypeMismatch { expected: ValueType::Int, .. }));
+}
+
+#[test]
+fn aggregates_over_whole_table() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, age INT, height FLOAT);
+        INSERT INTO people VALUES ('Alice', 30, 170.5), ('Bob', 25, NULL), ('Carol', 30, 165.5);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT COUNT(*), COUNT(height), COUNT(DISTINCT age), SUM(age), AVG(age), MIN(name), MAX(height) FROM people").unwrap();
+    assert!(printed.contains("| COUNT(*) <Integer> | COUNT(height) <Integer> | COUNT(DISTINCT age) <Integer> | SUM(age) <Integer> | AVG(age) <Float> |"));
+    assert_eq!(result_rows(&printed), vec!["| 3 | 2 | 2 | 85 | 28.333333333333332 | Alice | 170.5 |"]);
+
+    // An empty input still produces one row
+    let printed = run(&mut database, "SELECT COUNT(*), SUM(age), MAX(name) FROM people WHERE age > 100").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0 | NULL | NULL |"]);
+
+    let error = run(&mut database, "SELECT SUM(name) FROM people").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+
+    let error = run(&mut database, "SELECT name FROM people WHERE COUNT(*) > 1").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidAggregate(_)));
+}
+
+#[test]
+fn group_by_with_having_and_order_by() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE sales (region TEXT, amount INT);
+        INSERT INTO sales VALUES ('north', 10), ('south', 5), ('north', 20), ('east', 1), ('south', 7), (NULL, 3);
+    ").unwrap();
+
+    let printed = run(&mut database, "
+        SELECT region, SUM(amount) AS total, COUNT(*) FROM sales
+        GROUP BY region HAVING COUNT(*) > 1 ORDER BY total DESC
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| north | 30 | 2 |", "| south | 12 | 2 |"]);
+
+    // NULL keys form their own group, and keys may be given by position
+    let printed = run(&mut database, "SELECT region, MAX(amount) FROM sales GROUP BY 1 ORDER BY MAX(amount)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| east | 1 |", "| NULL | 3 |", "| south | 7 |", "| north | 20 |"]);
+
+    let error = run(&mut database, "SELECT region, amount FROM sales GROUP BY region").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidAggregate(_)));
+}
+
+/// Two small tables sharing a `dept_id` column, for join tests
+fn join_database() -> Database {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE employees (name TEXT, dept_id INT);
+        CREATE TABLE depts (dept_id INT, title TEXT);
+        INSERT INTO employees VALUES ('Alice', 1), ('Bob', 2), ('Carol', NULL);
+        INSERT INTO depts VALUES (1, 'Sales'), (3, 'Legal');
+    ").unwrap();
+    database
+}
+
+#[test]
+fn inner_and_outer_joins() {
+    let mut database = join_database();
+
+    let printed = run(&mut database, "SELECT e.name, d.title FROM employees e JOIN depts AS d ON e.dept_id = d.dept_id").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice | Sales |"]);
+
+    let printed = run(&mut database, "SELECT name, title FROM employees e LEFT JOIN depts d ON e.dept_id = d.dept_id").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice | Sales |", "| Bob | NULL |", "| Carol | NULL |"]);
+
+    let printed = run(&mut database, "SELECT name, title FROM employees e RIGHT OUTER JOIN depts d ON e.dept_id = d.dept_id").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice | Sales |", "| NULL | Legal |"]);
+
+    let printed = run(&mut database, "SELECT name, title FROM employees FULL JOIN depts ON employees.dept_id = depts.dept_id").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice | Sales |", "| Bob | NULL |", "| Carol | NULL |", "| NULL | Legal |"]);
+}
+
+#[test]
+fn cross_comma_and_using_joins() {
+    let mut database = join_database();
+
+    let printed = run(&mut database, "SELECT COUNT(*) FROM employees CROSS JOIN depts").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 6 |"]);
+
+    let printed = run(&mut database, "SELECT name, title FROM employees e, depts d WHERE e.dept_id = d.dept_id").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice | Sales |"]);
+
+    // USING merges the shared column, which `*` lists once
+    let printed = run(&mut database, "SELECT * FROM employees FULL JOIN depts USING (dept_id)").unwrap();
+    assert!(printed.contains("| dept_id <Integer> | name <Text> | title <Text> |"));
+    assert_eq!(result_rows(&printed), vec!["| 1 | Alice | Sales |", "| 2 | Bob | NULL |", "| NULL | Carol | NULL |", "| 3 | NULL | Legal |"]);
+
+    let error = run(&mut database, "SELECT dept_id FROM employees JOIN depts ON name = title").unwrap_err();
+    assert!(matches!(error, KirinError::AmbiguousColumn(_)));
+
+    let error = run(&mut database, "SELECT * FROM employees JOIN depts").unwrap_err();
+    assert!(matches!(error, KirinError::Parse { .. }));
+}
+
+#[test]
+fn explain_shows_join_strategy() {
+    let mut database = join_database();
+
+    let printed = run(&mut database, "EXPLAIN SELECT name FROM employees e JOIN depts d ON e.dept_id = d.dept_id WHERE title <> 'x'").unwrap();
+    assert_eq!(printed.lines().collect::<Vec<_>>(), vec![
+        "Project: name",
+        "  Filter: title <> 'x'",
+        "    Hash Join (INNER) ON e.dept_id = d.dept_id",
+        "      Scan: employees AS e",
+        "      Scan: depts AS d",
+    ]);
+
+    // Tables keep no order, even when their rows happen to be sorted...
+    run(&mut database, "CREATE TABLE budgets (dept_id INT, amount INT); INSERT INTO budgets VALUES (1, 100), (1, 50), (3, 70)").unwrap();
+    let printed = run(&mut database, "EXPLAIN SELECT * FROM depts LEFT JOIN budgets USING (dept_id)").unwrap();
+    assert!(printed.contains("Hash Join (LEFT) USING (dept_id)"));
+
+    // ...while subqueries and CTEs sorted by their join keys are merged
+    let printed = run(&mut database, "
+        EXPLAIN WITH b AS (SELECT * FROM budgets ORDER BY dept_id)
+        SELECT * FROM (SELECT dept_id, title FROM depts ORDER BY 1) d LEFT JOIN b USING (dept_id)
+    ").unwrap();
+    assert!(printed.contains("Merge Join (LEFT) USING (dept_id)"));
+    let printed = run(&mut database, "
+        EXPLAIN SELECT * FROM (SELECT * FROM depts ORDER BY dept_id DESC) d JOIN (SELECT * FROM budgets ORDER BY dept_id) b ON d.dept_id = b.dept_id
+    ").unwrap();
+    assert!(printed.contains("Hash Join (INNER)"));
+
+    // Nothing is evaluated, so a query that would fail is still explained
+    let printed = run(&mut database, "EXPLAIN SELECT 1 / 0").unwrap();
+    assert_eq!(printed.lines().collect::<Vec<_>>(), vec!["Project: 1 / 0"]);
+    let printed = run(&mut database, "EXPLAIN SELECT COUNT(*), 1 / 0 FROM budgets WHERE amount / 0 > 1").unwrap();
+    assert!(printed.contains("Scan: budgets"));
+    assert!(run(&mut database, "SELECT 1 / 0").is_err());
+
+    let printed = run(&mut database, "EXPLAIN SELECT * FROM employees e JOIN depts d ON e.dept_id < d.dept_id").unwrap();
+    assert!(printed.contains("Nested Loop Join (INNER) ON e.dept_id < d.dept_id"));
+}
+
+#[test]
+fn join_strategies_agree() {
+    let mut database = join_database();
+    run(&mut database, "CREATE TABLE budgets (dept_id INT, amount INT); INSERT INTO budgets VALUES (NULL, 1), (1, 100), (1, 50), (3, 70)").unwrap();
+
+    // Inputs sorted by the join key take a merge join, the tables a hash join
+    let query = "SELECT title, amount FROM {} d FULL JOIN {} b ON d.dept_id = b.dept_id AND amount > 60 ORDER BY title, amount";
+    let sorted = query
+        .replacen("{}", "(SELECT * FROM depts ORDER BY dept_id)", 1)
+        .replacen("{}", "(SELECT * FROM budgets ORDER BY dept_id)", 1);
+    let tables = query.replacen("{}", "depts", 1).replacen("{}", "budgets", 1);
+    let merged = run(&mut database, &sorted).unwrap();
+    let hashed = run(&mut database, &tables).unwrap();
+    assert_eq!(merged, hashed);
+    assert_eq!(result_rows(&merged), vec!["| NULL | 1 |", "| NULL | 50 |", "| Legal | 70 |", "| Sales | 100 |"]);
+
+    let explained = run(&mut database, &format!("EXPLAIN {sorted}")).unwrap();
+    assert!(explained.contains("Merge Join (FULL)"));
+    let explained = run(&mut database, &format!("EXPLAIN {tables}")).unwrap();
+    assert!(explained.contains("Hash Join (FULL)"));
+}
+
+#[test]
+fn scalar_and_derived_table_subqueries() {
+    let mut database = join_database();
+
+    let printed = run(&mut database, "SELECT name, (SELECT title FROM depts d WHERE d.dept_id = e.dept_id) AS title FROM employees e").unwrap();
+    assert!(printed.contains("| name <Text> | title <Text> |"));
+    assert_eq!(result_rows(&printed), vec!["| Alice | Sales |", "| Bob | NULL |", "| Carol | NULL |"]);
+
+    let printed = run(&mut database, "SELECT big.title FROM (SELECT title, dept_id FROM depts WHERE dept_id > 1) AS big").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Legal |"]);
+
+    let printed = run(&mut database, "SELECT n FROM (SELECT COUNT(*) AS n FROM employees) counts WHERE n > (SELECT COUNT(*) FROM depts)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 3 |"]);
+
+    let error = run(&mut database, "SELECT (SELECT title FROM depts) FROM employees").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidSubquery(_)));
+
+    let error = run(&mut database, "SELECT (SELECT dept_id, title FROM depts)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidSubquery(_)));
+
+    let error = run(&mut database, "SELECT * FROM (SELECT title FROM depts)").unwrap_err();
+    assert!(matches!(error, KirinError::Parse { .. }));
+}
+
+#[test]
+fn in_and_exists_subqueries() {
+    let mut database = join_database();
+
+    let printed = run(&mut database, "SELECT name FROM employees WHERE dept_id IN (SELECT dept_id FROM depts)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice |"]);
+
+    // NULL on either side makes NOT IN unknown rather than true
+    let printed = run(&mut database, "SELECT name FROM employees WHERE dept_id NOT IN (SELECT dept_id FROM depts)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Bob |"]);
+    let printed = run(&mut database, "SELECT title FROM depts WHERE dept_id NOT IN (SELECT dept_id FROM employees)").unwrap();
+    assert!(result_rows(&printed).is_empty());
+
+    let printed = run(&mut database, "SELECT name FROM employees WHERE dept_id IN (2, 3)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Bob |"]);
+
+    let printed = run(&mut database, "SELECT title FROM depts d WHERE EXISTS (SELECT * FROM employees e WHERE e.dept_id = d.dept_id)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Sales |"]);
+    let printed = run(&mut database, "SELECT title FROM depts d WHERE NOT EXISTS (SELECT * FROM employees e WHERE e.dept_id = d.dept_id)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Legal |"]);
+
+    // Subqueries not reading the enclosing row run once, and match by value like = does
+    let printed = run(&mut database, "SELECT name FROM employees WHERE dept_id IN (SELECT CAST(dept_id AS FLOAT) FROM depts)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice |"]);
+    let printed = run(&mut database, "
+        SELECT name FROM employees e
+        WHERE EXISTS (SELECT 1 FROM depts d WHERE d.dept_id = e.dept_id AND d.dept_id IN (SELECT dept_id FROM employees))
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice |"]);
+
+    // The same text names a different CTE in each scope
+    let printed = run(&mut database, "
+        WITH nums AS (SELECT 1 AS n)
+        SELECT (SELECT MAX(n) FROM nums), (WITH nums AS (SELECT 10 AS n) SELECT (SELECT MAX(n) FROM nums))
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 | 10 |"]);
+
+    // Literals that print alike are still different subqueries
+    let printed = run(&mut database, "SELECT (SELECT 1e0) / 2, (SELECT 1) / 2").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0.5 | 0 |"]);
+    let printed = run(&mut database, "SELECT (SELECT 1) / 2, (SELECT 1e0) / 2").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0 | 0.5 |"]);
+
+    run(&mut database, "DELETE FROM employees WHERE dept_id NOT IN (SELECT dept_id FROM depts)").unwrap();
+    let printed = run(&mut database, "SELECT name FROM employees").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Alice |", "| Carol |"]);
+
+    let error = run(&mut database, "SELECT name FROM employees WHERE dept_id IN (SELECT dept_id, title FROM depts)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidSubquery(_)));
+}
+
+#[test]
+fn common_table_expressions() {
+    let mut database = join_database();
+
+    let printed = run(&mut database, "
+        WITH staffed AS (SELECT dept_id AS id FROM employees),
+             named (id, label) AS (SELECT dept_id, title FROM depts WHERE dept_id IN (SELECT id FROM staffed))
+        SELECT label FROM named
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Sales |"]);
+
+    // A CTE shadows a table of the same name
+    let printed = run(&mut database, "WITH depts AS (SELECT 'Ops' AS title) SELECT title FROM depts").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Ops |"]);
+
+    let error = run(&mut database, "WITH named (id) AS (SELECT dept_id, title FROM depts) SELECT * FROM named").unwrap_err();
+    assert!(matches!(error, KirinError::ArityMismatch { expected: 1, actual: 2 }));
+}
+
+#[test]
+fn recursive_common_table_expressions() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE staff (id INT, name TEXT, manager_id INT);
+        INSERT INTO staff VALUES (1, 'Ada', NULL), (2, 'Ben', 1), (3, 'Cy', 2), (4, 'Di', 1), (5, 'Ed', 3);
+    ").unwrap();
+
+    let printed = run(&mut database, "
+        WITH RECURSIVE chain (id, name, level) AS (
+            SELECT id, name, 0 FROM staff WHERE manager_id IS NULL
+            UNION ALL
+            SELECT s.id, s.name, level + 1 FROM staff s JOIN chain c ON s.manager_id = c.id
+        )
+        SELECT name, level FROM chain ORDER BY level, name
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| Ada | 0 |", "| Ben | 1 |", "| Di | 1 |", "| Cy | 2 |", "| Ed | 3 |"]);
+
+    // UNION drops rows already produced, which ends the cycle
+    let printed = run(&mut database, "
+        WITH RECURSIVE cycle (n) AS (SELECT 1 UNION SELECT n % 3 + 1 FROM cycle)
+        SELECT n FROM cycle
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 |", "| 2 |", "| 3 |"]);
+
+    let error = run(&mut database, "WITH RECURSIVE forever (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM forever) SELECT COUNT(*) FROM forever").unwrap_err();
+    assert!(matches!(error, KirinError::RecursionLimit { limit: 1000, .. }));
+
+    let printed = run(&mut database, "EXPLAIN WITH RECURSIVE up (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM up WHERE n < 3) SELECT n FROM up").unwrap();
+    assert_eq!(printed.lines().collect::<Vec<_>>(), vec![
+        "Recursive CTE: up",
+        "  Project: 1",
+        "  Project: n + 1",
+        "    Filter: n < 3",
+        "      CTE Scan: up",
+        "Project: n",
+        "  CTE Scan: up",
+    ]);
+}
+
+#[test]
+fn set_operations() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE a (n INT);
+        CREATE TABLE b (n FLOAT, label TEXT);
+        INSERT INTO a VALUES (1), (2), (2), (3), (NULL);
+        INSERT INTO b VALUES (2.0, 'x'), (2.0, 'y'), (4.5, 'z'), (NULL, 'w');
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT n FROM a UNION SELECT n FROM b ORDER BY n").unwrap();
+    assert!(printed.contains("| n <Float> |"));
+    assert_eq!(result_rows(&printed), vec!["| NULL |", "| 1 |", "| 2 |", "| 3 |", "| 4.5 |"]);
+
+    let printed = run(&mut database, "SELECT n FROM a UNION ALL SELECT n FROM b ORDER BY 1 DESC LIMIT 3").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 4.5 |", "| 3 |", "| 2 |"]);
+
+    let printed = run(&mut database, "SELECT n FROM a INTERSECT SELECT n FROM b ORDER BY n").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| NULL |", "| 2 |"]);
+
+    let printed = run(&mut database, "SELECT n FROM a EXCEPT SELECT n FROM b ORDER BY n").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 |", "| 3 |"]);
+
+    // With ALL, each row on the right cancels or matches one row on the left
+    let printed = run(&mut database, "SELECT n FROM a EXCEPT ALL SELECT n FROM b WHERE label = 'x' ORDER BY n").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| NULL |", "| 1 |", "| 2 |", "| 3 |"]);
+    let printed = run(&mut database, "SELECT n FROM a INTERSECT ALL SELECT n FROM b").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2 |", "| 2 |", "| NULL |"]);
+
+    // Operators apply left to right
+    let printed = run(&mut database, "SELECT 1 UNION SELECT 2 EXCEPT SELECT 1").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2 |"]);
+
+    // Rows take on the widened column type, not just the header
+    let printed = run(&mut database, "SELECT 1 AS x UNION ALL SELECT CAST(1.5 AS DECIMAL(5, 2)) ORDER BY x").unwrap();
+    assert!(printed.contains("| x <Decimal(21,2)> |"));
+    assert_eq!(result_rows(&printed), vec!["| 1.00 |", "| 1.50 |"]);
+
+    let error = run(&mut database, "SELECT n FROM a UNION SELECT n, label FROM b").unwrap_err();
+    assert!(matches!(error, KirinError::ArityMismatch { expected: 1, actual: 2 }));
+
+    let error = run(&mut database, "SELECT n, n FROM a INTERSECT SELECT n, label FROM b").unwrap_err();
+    assert!(matches!(error, KirinError::IncompatibleColumn { position: 2, left: ValueType::Int, right: ValueType::Text, .. }));
+    assert_eq!(error.to_string(), "Column 2 of INTERSECT has incompatible types Integer and Text");
+
+    let printed = run(&mut database, "EXPLAIN SELECT n FROM a UNION ALL SELECT n FROM b EXCEPT SELECT 1 ORDER BY n").unwrap();
+    assert_eq!(printed.lines().collect::<Vec<_>>(), vec![
+        "Sort: n",
+        "  EXCEPT",
+        "    UNION ALL",
+        "      Project: n",
+        "        Scan: a",
+        "      Project: n",
+        "        Scan: b",
+        "    Project: 1",
+    ]);
+}
+
+#[test]
+fn select_distinct_and_distinct_on() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE readings (sensor TEXT, value FLOAT, taken INT);
+        INSERT INTO readings VALUES ('a', 1.0, 3), ('b', 0.0, 1), ('a', 1.0, 1), ('b', -0.0, 2), ('a', 2.5, 2), (NULL, NULL, 1), (NULL, NULL, 2);
+    ").unwrap();
+
+    // 0.0 and -0.0 are the same value
+    let printed = run(&mut database, "SELECT DISTINCT sensor, value FROM readings ORDER BY sensor, value").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| NULL | NULL |", "| a | 1 |", "| a | 2.5 |", "| b | 0 |"]);
+
+    let printed = run(&mut database, "SELECT COUNT(DISTINCT value), COUNT(*) FROM (SELECT DISTINCT value FROM readings) v").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 3 | 4 |"]);
+
+    // The latest reading of each sensor
+    let printed = run(&mut database, "SELECT DISTINCT ON (sensor) sensor, taken FROM readings ORDER BY sensor, taken DESC").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| NULL | 2 |", "| a | 3 |", "| b | 2 |"]);
+
+    let printed = run(&mut database, "SELECT DISTINCT ON (sensor) sensor FROM readings ORDER BY sensor LIMIT 1 OFFSET 1").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| a |"]);
+
+    let printed = run(&mut database, "EXPLAIN SELECT DISTINCT ON (sensor) sensor FROM readings").unwrap();
+    assert_eq!(printed.lines().collect::<Vec<_>>(), vec!["Distinct On: sensor", "  Project: sensor", "    Scan: readings"]);
+}
+
+#[test]
+fn ranking_and_offset_window_functions() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE staff (name TEXT, dept TEXT, salary INT);
+        INSERT INTO staff VALUES ('ann', 'eng', 300), ('bob', 'eng', 200), ('cid', 'eng', 300), ('dee', 'ops', 100), ('eve', 'ops', 150);
+    ").unwrap();
+
+    let printed = run(&mut database, "
+        SELECT name, ROW_NUMBER() OVER (ORDER BY salary DESC, name), RANK() OVER (ORDER BY salary DESC), DENSE_RANK() OVER (ORDER BY salary DESC)
+        FROM staff ORDER BY name
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| ann | 1 | 1 | 1 |",
+        "| bob | 3 | 3 | 2 |",
+        "| cid | 2 | 1 | 1 |",
+        "| dee | 5 | 5 | 4 |",
+        "| eve | 4 | 4 | 3 |",
+    ]);
+
+    let printed = run(&mut database, "
+        SELECT name, LAG(salary) OVER (PARTITION BY dept ORDER BY name), LEAD(salary, 1, 0) OVER (PARTITION BY dept ORDER BY name),
+            NTILE(2) OVER (ORDER BY name)
+        FROM staff ORDER BY name
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| ann | NULL | 200 | 1 |",
+        "| bob | 300 | 300 | 1 |",
+        "| cid | 200 | 0 | 1 |",
+        "| dee | NULL | 150 | 2 |",
+        "| eve | 100 | 0 | 2 |",
+    ]);
+
+    // Windows are computed after grouping and can be sorted by
+    let printed = run(&mut database, "
+        SELECT dept, SUM(salary), RANK() OVER (ORDER BY SUM(salary) DESC) FROM staff GROUP BY dept ORDER BY RANK() OVER (ORDER BY SUM(salary) DESC) DESC
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| ops | 250 | 2 |", "| eng | 800 | 1 |"]);
+
+    let error = run(&mut database, "SELECT name FROM staff WHERE ROW_NUMBER() OVER () = 1").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidWindow(_)));
+    let error = run(&mut database, "SELECT RANK() FROM staff").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidWindow(_)));
+    let error = run(&mut database, "SELECT NTILE('a') OVER () FROM staff").unwrap_err();
+    assert!(matches!(error, KirinError::TypeMismatch { .. }));
+
+    let printed = run(&mut database, "EXPLAIN SELECT name, RANK() OVER (PARTITION BY dept ORDER BY salary) FROM staff").unwrap();
+    assert_eq!(printed.lines().collect::<Vec<_>>(), vec![
+        "Project: name, RANK() OVER (PARTITION BY dept ORDER BY salary)",
+        "  Window: RANK() OVER (PARTITION BY dept ORDER BY salary)",
+        "    Scan: staff",
+    ]);
+}
+
+#[test]
+fn aggregate_window_functions_and_frames() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE sales (day INT, region TEXT, amount INT);
+        INSERT INTO sales VALUES (1, 'n', 10), (2, 'n', 20), (2, 's', 5), (3, 'n', 30), (5, 's', 15), (6, 'n', 40);
+    ").unwrap();
+
+    // The default frame ends at the last peer of the current row
+    let printed = run(&mut database, "
+        SELECT day, region, SUM(amount) OVER (PARTITION BY region ORDER BY day), SUM(amount) OVER (ORDER BY day), COUNT(*) OVER ()
+        FROM sales ORDER BY day, region
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| 1 | n | 10 | 10 | 6 |",
+        "| 2 | n | 30 | 35 | 6 |",
+        "| 2 | s | 5 | 35 | 6 |",
+        "| 3 | n | 60 | 65 | 6 |",
+        "| 5 | s | 20 | 80 | 6 |",
+        "| 6 | n | 100 | 120 | 6 |",
+    ]);
+
+    let printed = run(&mut database, "
+        SELECT day, AVG(amount) OVER (ORDER BY day, region ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING),
+            FIRST_VALUE(amount) OVER (ORDER BY day, region ROWS BETWEEN 2 PRECEDING AND CURRENT ROW),
+            LAST_VALUE(amount) OVER (ORDER BY day, region ROWS BETWEEN CURRENT ROW AND UNBOUNDED FOLLOWING),
+            SUM(amount) OVER (ORDER BY day, region ROWS BETWEEN 3 PRECEDING AND 2 PRECEDING)
+        FROM sales ORDER BY day, region
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| 1 | 15 | 10 | 40 | NULL |",
+        "| 2 | 11.666666666666666 | 10 | 40 | NULL |",
+        "| 2 | 18.333333333333332 | 10 | 40 | 10 |",
+        "| 3 | 16.666666666666668 | 20 | 40 | 30 |",
+        "| 5 | 28.333333333333332 | 5 | 40 | 25 |",
+        "| 6 | 27.5 | 30 | 40 | 35 |",
+    ]);
+
+    // RANGE offsets are measured in ORDER BY values rather than rows
+    let printed = run(&mut database, "
+        SELECT day, SUM(amount) OVER (ORDER BY day RANGE BETWEEN 1 PRECEDING AND CURRENT ROW) FROM sales ORDER BY day, region
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 | 10 |", "| 2 | 35 |", "| 2 | 35 |", "| 3 | 55 |", "| 5 | 15 |", "| 6 | 55 |"]);
+
+    let error = run(&mut database, "SELECT SUM(amount) OVER (RANGE BETWEEN 1 PRECEDING AND CURRENT ROW) FROM sales").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidWindow(_)));
+    let error = run(&mut database, "SELECT SUM(SUM(amount) OVER ()) OVER () FROM sales").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidWindow(_)));
+}
+
+#[test]
+fn scalar_text_functions() {
+    let mut database = Database::new();
+
+    let printed = run(&mut database, "
+        SELECT LOWER('MiXed'), UPPER('straße'), LENGTH('héllo'), SUBSTR('kirin', 2, 3), SUBSTR('kirin', 0, 2), SUBSTR('kirin', 4)
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| mixed | STRASSE | 5 | iri | k | in |"]);
+
+    let printed = run(&mut database, "
+        SELECT TRIM('  padded  '), TRIM('xxhixx', 'x'), REPLACE('a-b-c', '-', '+'), CONCAT('a', NULL, 1, TRUE), 'a' || NULL IS NULL
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| padded | hi | a+b+c | a1true | true |"]);
+
+    let printed = run(&mut database, "SELECT LENGTH(NULL), upper(NULL) IS NULL").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| NULL | true |"]);
+
+    let error = run(&mut database, "SELECT LENGTH(12)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { op, operands } if op == "LENGTH" && operands == vec![ValueType::Int]));
+    let error = run(&mut database, "SELECT SUBSTR('abc')").unwrap_err();
+    assert!(matches!(error, KirinError::ArityMismatch { expected: 2, actual: 1 }));
+    let error = run(&mut database, "SELECT SUBSTR('abc', 1, -1)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT SHOUT('abc')").unwrap_err();
+    assert!(matches!(error, KirinError::UnknownFunction(name) if name == "SHOUT"));
+}
+
+#[test]
+fn scalar_math_functions() {
+    let mut database = Database::new();
+
+    let printed = run(&mut database, "
+        SELECT ABS(-3), ABS(-2.5), ROUND(2.5), ROUND(-2.345, 2), ROUND(1250, -2), ROUND(-1249, -2), FLOOR(-1.5), CEIL(1.2), CEILING(7)
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 3 | 2.5 | 3 | -2.35 | 1300 | -1200 | -2 | 2 | 7 |"]);
+
+    let printed = run(&mut database, "SELECT POWER(2, 10), SQRT(16), MOD(17, 5), MOD(5.5, 2)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1024 | 4 | 2 | 1.5 |"]);
+
+    let error = run(&mut database, "SELECT ABS('x')").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+    let error = run(&mut database, "SELECT SQRT(-1)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT MOD(1, 0)").unwrap_err();
+    assert!(matches!(error, KirinError::DivisionByZero));
+    let error = run(&mut database, "SELECT ABS(-9223372036854775807 - 1)").unwrap_err();
+    assert!(matches!(error, KirinError::Overflow));
+    let error = run(&mut database, "SELECT ABS(-9223372036854775808)").unwrap_err();
+    assert!(matches!(error, KirinError::Overflow));
+
+    let printed = run(&mut database, "SELECT -9223372036854775808").unwrap();
+    assert!(printed.contains("<Integer>"));
+    assert_eq!(result_rows(&printed), vec!["| -9223372036854775808 |"]);
+}
+
+#[test]
+fn conditional_functions_and_case() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE scores (name TEXT, score INT);
+        INSERT INTO scores VALUES ('ann', 91), ('bob', 75), ('cid', NULL), ('dee', 40);
+    ").unwrap();
+
+    let printed = run(&mut database, "
+        SELECT name, CASE WHEN score >= 90 THEN 'A' WHEN score >= 70 THEN 'B' WHEN score IS NULL THEN '-' ELSE 'F' END,
+            CASE name WHEN 'ann' THEN 1 WHEN 'bob' THEN 2 END, IIF(score > 50, 'pass', 'fail'), COALESCE(score, 0)
+        FROM scores ORDER BY name
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| ann | A | 1 | pass | 91 |",
+        "| bob | B | 2 | pass | 75 |",
+        "| cid | - | NULL | fail | 0 |",
+        "| dee | F | NULL | fail | 40 |",
+    ]);
+
+    // Only the chosen branch is evaluated
+    let printed = run(&mut database, "SELECT IIF(TRUE, 1, 1 / 0), CASE WHEN FALSE THEN 1 / 0 ELSE 2 END, COALESCE(3, 1 / 0)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 | 2 | 3 |"]);
+
+    let printed = run(&mut database, "SELECT GREATEST(3, NULL, 7.5, 1), LEAST('pear', 'apple'), GREATEST(NULL), NULLIF(2, 2), NULLIF(2, 3)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 7.5 | apple | NULL | NULL | 2 |"]);
+
+    let error = run(&mut database, "SELECT CASE WHEN 1 THEN 'a' END").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+    let error = run(&mut database, "SELECT CASE WHEN TRUE THEN 'a' ELSE 1 END").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+    let error = run(&mut database, "SELECT GREATEST(1, 'a')").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+    let error = run(&mut database, "SELECT IIF('yes', 1, 2)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+}
+
+#[test]
+fn timestamp_literals_and_date_functions() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE events (name TEXT, at TIMESTAMP);
+        INSERT INTO events VALUES
+            ('launch', TIMESTAMP '2026-01-31T10:30:15Z'),
+            ('review', TIMESTAMP '2026-03-01 08:00:00'),
+            ('party', TIMESTAMP '2025-12-24T20:00:00+02:00');
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT name, at FROM events WHERE at > TIMESTAMP '2026-01-01' ORDER BY at").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| launch | 2026-01-31T10:30:15+00:00 |", "| review | 2026-03-01T08:00:00+00:00 |"]);
+
+    let printed = run(&mut database, "
+        SELECT EXTRACT(YEAR FROM at), EXTRACT(quarter FROM at), DATE_PART('dow', at), EXTRACT(HOUR FROM at), EXTRACT(EPOCH FROM at)
+        FROM events WHERE name = 'party'
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2025 | 4 | 3 | 18 | 1766599200 |"]);
+
+    let printed = run(&mut database, "
+        SELECT DATE_TRUNC('month', at), DATE_TRUNC('week', at), DATE_TRUNC('hour', at) FROM events WHERE name = 'launch'
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2026-01-01T00:00:00+00:00 | 2026-01-26T00:00:00+00:00 | 2026-01-31T10:00:00+00:00 |"]);
+
+    // Adding a month to January 31st lands on the last day of February
+    let printed = run(&mut database, "
+        SELECT DATE_ADD(at, 1, 'month'), DATE_ADD(at, -2, 'days'), DATE_ADD(at, 90, 'minutes') FROM events WHERE name = 'launch'
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2026-02-28T10:30:15+00:00 | 2026-01-29T10:30:15+00:00 | 2026-01-31T12:00:15+00:00 |"]);
+
+    let printed = run(&mut database, "
+        SELECT DATE_DIFF('day', TIMESTAMP '2026-01-31T10:30:15Z', at), DATE_DIFF('month', TIMESTAMP '2026-01-31T10:30:15Z', at),
+            DATE_DIFF('month', at, TIMESTAMP '2026-01-31T10:30:15Z')
+        FROM events WHERE name = 'review'
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 28 | 1 | -1 |"]);
+
+    let printed = run(&mut database, "
+        SELECT STRFTIME('%d/%m/%Y %H:%M', at), STRPTIME('31.01.2026', '%d.%m.%Y'), STRPTIME('2026-01-31 10:30 +0100', '%Y-%m-%d %H:%M %z')
+        FROM events WHERE name = 'launch'
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 31/01/2026 10:30 | 2026-01-31T00:00:00+00:00 | 2026-01-31T09:30:00+00:00 |"]);
+
+    let printed = run(&mut database, "SELECT NOW() > TIMESTAMP '2026-01-01', EXTRACT(DAY FROM NULL)").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| true | NULL |"]);
+
+    // NOW() is when the statement started, in every row and every call
+    let printed = run(&mut database, "
+        WITH RECURSIVE n (i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+        SELECT COUNT(DISTINCT NOW()), MIN(NOW() = NOW()), COUNT(*) FROM n WHERE NOW() IN (SELECT NOW())
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 | true | 1000 |"]);
+
+    let error = run(&mut database, "SELECT EXTRACT(FORTNIGHT FROM at) FROM events").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT EXTRACT(YEAR FROM name) FROM events").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+    let error = run(&mut database, "SELECT STRPTIME('soon', '%Y')").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT DATE_TRUNC('epoch', NOW())").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT TIMESTAMP 'yesterday'").unwrap_err();
+    assert!(matches!(error, KirinError::Parse { .. }));
+}
+
+#[test]
+fn like_ilike_glob_and_regexp() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE files (name TEXT);
+        INSERT INTO files VALUES ('report.txt'), ('Report_2026.TXT'), ('notes.md'), ('100%.txt'), ('a.b'), (NULL);
+    ").unwrap();
+
+    let matching = |database: &mut Database, condition: &str| {
+        let printed = run(database, &format!("SELECT name FROM files WHERE {condition} ORDER BY name")).unwrap();
+        result_rows(&printed).into_iter().map(str::to_string).collect::<Vec<_>>()
+    };
+
+    assert_eq!(matching(&mut database, "name LIKE '%.txt'"), vec!["| 100%.txt |", "| report.txt |"]);
+    assert_eq!(matching(&mut database, "name ILIKE 'report%.txt'"), vec!["| Report_2026.TXT |", "| report.txt |"]);
+    assert_eq!(matching(&mut database, "name LIKE '_._'"), vec!["| a.b |"]);
+    assert_eq!(matching(&mut database, "name LIKE '%!%%' ESCAPE '!'"), vec!["| 100%.txt |"]);
+    assert_eq!(matching(&mut database, "name NOT LIKE '%.%t'"), vec!["| Report_2026.TXT |", "| a.b |", "| notes.md |"]);
+    assert_eq!(matching(&mut database, "name GLOB '[a-n]*'"), vec!["| a.b |", "| notes.md |"]);
+    assert_eq!(matching(&mut database, "name GLOB '*.[!t]*'"), vec!["| Report_2026.TXT |", "| a.b |", "| notes.md |"]);
+    assert_eq!(matching(&mut database, "name GLOB '?.?'"), vec!["| a.b |"]);
+    assert_eq!(matching(&mut database, "name REGEXP '[0-9]{4}'"), vec!["| Report_2026.TXT |"]);
+    assert_eq!(matching(&mut database, "name ~ '^[a-z]+\\.(md|b)$'"), vec!["| a.b |", "| notes.md |"]);
+    assert_eq!(matching(&mut database, "name NOT REGEXP 't'"), vec!["| a.b |"]);
+
+    // Patterns may come from other expressions, and NULL matches nothing
+    let printed = run(&mut database, "SELECT 'abc' LIKE 'a' || '%', NULL LIKE '%', 'x' LIKE NULL, 'a.c' LIKE 'a.c', 'abc' LIKE 'a.c'").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| true | NULL | NULL | true | false |"]);
+
+    let printed = run(&mut database, "UPDATE files SET name = UPPER(name) WHERE name LIKE '%.md'").unwrap();
+    assert!(printed.contains('1'));
+
+    let error = run(&mut database, "SELECT name FROM files WHERE name REGEXP '('").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT name FROM files WHERE name LIKE 'a' ESCAPE 'ab'").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT name FROM files WHERE name LIKE 'a!' ESCAPE '!'").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT name FROM files WHERE 12 LIKE '1%'").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { op, .. } if op == "LIKE"));
+}
+
+#[test]
+fn insert_and_update_convert_values_to_column_types() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE people (name TEXT, height FLOAT, active BOOLEAN, joined TIMESTAMP);
+        INSERT INTO people VALUES ('Carol', 170, TRUE, '2026-02-01');
+        INSERT INTO people (name, height) VALUES ('Dave', 1 + 180);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT name, height, active, joined FROM people ORDER BY name").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| Carol | 170 | true | 2026-02-01T00:00:00+00:00 |",
+        "| Dave | 181 | NULL | NULL |",
+    ]);
+
+    run(&mut database, "UPDATE people SET joined = '2026-03-01T09:00:00Z', height = height + 1 WHERE name = 'Dave'").unwrap();
+    let printed = run(&mut database, "SELECT height / 2, joined FROM people WHERE name = 'Dave'").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 91 | 2026-03-01T09:00:00+00:00 |"]);
+
+    let error = run(&mut database, "CREATE TABLE counts (n INT); INSERT INTO counts VALUES (2.5)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidConversion { column, .. } if column == "n"));
+    let error = run(&mut database, "INSERT INTO people (name, joined) VALUES ('Erin', 'next week')").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidConversion { .. }));
+    let error = run(&mut database, "INSERT INTO people (name, active) VALUES ('Erin', 'yes')").unwrap_err();
+    assert!(matches!(error, KirinError::TypeMismatch { expected: ValueType::Bool, actual: ValueType::Text, .. }));
+}
+
+#[test]
+fn cast_expressions() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE readings (label TEXT, value FLOAT);
+        INSERT INTO readings VALUES ('12', 2.5), ('true', -0.4), (NULL, NULL);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT CAST(value AS INT), value::text, label::int + 1 FROM readings WHERE label = '12'").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 3 | 2.5 | 13 |"]);
+
+    let printed = run(&mut database, "SELECT label::boolean, CAST(label AS TEXT) FROM readings WHERE label <> '12' OR label IS NULL ORDER BY label").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| NULL | NULL |", "| true | true |"]);
+
+    let printed = run(&mut database, "SELECT EXTRACT(MONTH FROM '2026-03-01'::timestamp), TRUE::int, 1::bool").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 3 | 1 | true |"]);
+
+    // A value that does not convert fails when it is reached...
+    let error = run(&mut database, "SELECT label::int FROM readings").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidCast { value, from: ValueType::Text, to: ValueType::Int } if value == "true"));
+
+    // ...while types that never convert fail before any row is read
+    let error = run(&mut database, "SELECT CAST(value AS TIMESTAMP) FROM readings WHERE FALSE").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidCast { from: ValueType::Float, to: ValueType::DateTime, .. }));
+}
+
+#[test]
+fn decimal_columns_keep_sums_exact() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE payments (id INT, amount NUMERIC(10, 2), rate DECIMAL(5, 4));
+        INSERT INTO payments VALUES (1, 0.1, 0.0125), (2, 0.2, 1), (3, 19.999, NULL);
+    ").unwrap();
+
+    let table = database.table("payments").unwrap();
+    assert_eq!(table.columns[1].col_type, ValueType::Decimal { precision: 10, scale: 2 });
+
+    let printed = run(&mut database, "SELECT amount, rate FROM payments ORDER BY id").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0.10 | 0.0125 |", "| 0.20 | 1.0000 |", "| 20.00 | NULL |"]);
+
+    let printed = run(&mut database, "SELECT SUM(amount), SUM(amount) = 20.3, AVG(amount) FROM payments").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 20.30 | true | 6.7666666666666667 |"]);
+    assert!(printed.contains("SUM(amount) <Decimal(29,2)>"));
+    assert!(printed.contains("AVG(amount) <Decimal(24,16)>"));
+
+    // The argument's type decides how AVG adds up, whichever value comes first
+    let printed = run(&mut database, "
+        SELECT AVG(COALESCE(CASE WHEN id = 1 THEN 1 END, amount)), SUM(COALESCE(CASE WHEN id = 1 THEN 1 END, amount)) FROM payments
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 7.0666666666666667 | 21.20 |"]);
+
+    let printed = run(&mut database, "SELECT amount * rate, amount + 1, amount - rate, amount / 3, -amount, amount * 1.5 FROM payments WHERE id = 2").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0.200000 | 1.20 | -0.8000 | 0.0666666666666667 | -0.20 | 0.300 |"]);
+    assert!(printed.contains("amount * rate <Decimal(15,6)>"));
+
+    let printed = run(&mut database, "SELECT ROUND(rate, 2), FLOOR(-rate), CEIL(rate), ABS(-amount), CAST(amount AS INT), amount::decimal(3, 1) FROM payments WHERE id = 1").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0.01 | -1 | 1 | 0.10 | 0 | 0.1 |"]);
+
+    let printed = run(&mut database, "UPDATE payments SET amount = amount * 3 WHERE id = 1").unwrap();
+    assert!(printed.contains('1'));
+    let printed = run(&mut database, "SELECT amount FROM payments WHERE amount = 0.3").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0.30 |"]);
+
+    // Literals keep every digit on their way into DECIMAL columns, and only become floats in FLOAT ones
+    run(&mut database, "
+        CREATE TABLE wide (big DECIMAL(30, 0), small DECIMAL(30, 20), approx FLOAT);
+        INSERT INTO wide VALUES (123456789012345678901234567.0, 0.12345678901234567890, 0.12345678901234567890);
+    ").unwrap();
+    let printed = run(&mut database, "SELECT big, small, approx, small * 2 FROM wide").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 123456789012345678901234567 | 0.12345678901234567890 | 0.12345678901234568 | 0.24691357802469135780 |"]);
+
+    let error = run(&mut database, "INSERT INTO payments VALUES (4, 123456789.5, NULL)").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidConversion { column, .. } if column == "amount"));
+    let error = run(&mut database, "SELECT CAST('1e999999999' AS DECIMAL(10, 2))").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidCast { .. }));
+    let printed = run(&mut database, "SELECT CAST('-1e-999999999' AS DECIMAL(10, 2))").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 0.00 |"]);
+    let error = run(&mut database, "SELECT amount / 0 FROM payments").unwrap_err();
+    assert!(matches!(error, KirinError::DivisionByZero));
+    let error = run(&mut database, "CREATE TABLE bad (x DECIMAL(3, 4))").unwrap_err();
+    assert!(matches!(error, KirinError::Parse { .. }));
+}
+
+#[test]
+fn blob_columns_literals_and_functions() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE files (name TEXT, data BLOB);
+        INSERT INTO files VALUES ('magic', X'CAFEBABE'), ('empty', x''), ('text', CAST('héllo' AS BLOB)), ('none', NULL);
+    ").unwrap();
+
+    let printed = run(&mut database, "SELECT name, data, LENGTH(data), HEX(data) FROM files ORDER BY data").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| none | NULL | NULL | NULL |",
+        "| empty | X'' | 0 |  |",
+        "| text | X'68C3A96C6C6F' | 6 | 68C3A96C6C6F |",
+        "| magic | X'CAFEBABE' | 4 | CAFEBABE |",
+    ]);
+    assert!(printed.contains("data <Blob>"));
+
+    let printed = run(&mut database, "SELECT SUBSTR(data, 2, 2), SUBSTR(data, 3), data || X'00', data = UNHEX('cafebabe') FROM files WHERE name = 'magic'").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| X'FEBA' | X'BABE' | X'CAFEBABE00' | true |"]);
+
+    let printed = run(&mut database, "SELECT LENGTH('héllo'), HEX('hé'), CAST(data AS TEXT) FROM files WHERE name = 'text'").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 5 | 68C3A9 | héllo |"]);
+
+    let error = run(&mut database, "SELECT data || 'x' FROM files").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { op, .. } if op == "||"));
+    let error = run(&mut database, "SELECT UNHEX('xyz')").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidArgument(_)));
+    let error = run(&mut database, "SELECT CAST(data AS TEXT) FROM files").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidCast { from: ValueType::Blob, to: ValueType::Text, .. }));
+    let error = run(&mut database, "INSERT INTO files VALUES ('wrong', 'text')").unwrap_err();
+    assert!(matches!(error, KirinError::TypeMismatch { expected: ValueType::Blob, .. }));
+}
+
+#[test]
+fn date_time_and_interval_arithmetic_in_the_session_time_zone() {
+    let mut database = Database::new();
+    run(&mut database, "
+        CREATE TABLE trips (id INT, day DATE, leaves TIME, departs TIMESTAMP WITH TIME ZONE, arrives TIMESTAMPTZ, length INTERVAL);
+        SET TIME ZONE '+02:00';
+        INSERT INTO trips VALUES
+            (1, '2026-01-31', '08:15', '2026-01-31 08:15:00', '2026-01-31T10:45:00Z', '2 hours 30 minutes'),
+            (2, '2026-02-28', '23:30', '2026-02-28 23:30:00', '2026-03-01 09:00:00', '9 hours 30 mins');
+    ").unwrap();
+
+    let table = database.table("trips").unwrap();
+    let types: Vec<ValueType> = table.columns.iter().skip(1).map(|col| col.col_type).collect();
+    assert_eq!(types, vec![ValueType::Date, ValueType::Time, ValueType::DateTimeTz, ValueType::DateTimeTz, ValueType::Interval]);
+
+    // Text without an offset was read in the session's time zone, and is shown in it
+    let printed = run(&mut database, "SELECT departs, arrives - departs, arrives - departs = length FROM trips ORDER BY id").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| 2026-01-31T08:15:00+02:00 | 04:30:00 | false |",
+        "| 2026-02-28T23:30:00+02:00 | 09:30:00 | true |",
+    ]);
+    assert!(printed.contains("arrives - departs <Interval>"));
+
+    // So are column defaults and updates
+    run(&mut database, "
+        ALTER TABLE trips ADD COLUMN booked TIMESTAMPTZ DEFAULT '2026-01-01 09:00:00';
+        UPDATE trips SET arrives = '2026-03-01 10:00:00' WHERE id = 2;
+    ").unwrap();
+    let printed = run(&mut database, "SELECT booked, arrives FROM trips WHERE id = 2").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2026-01-01T09:00:00+02:00 | 2026-03-01T10:00:00+02:00 |"]);
+
+    // Date functions take dates, and timestamps with a time zone in their own zone
+    let printed = run(&mut database, "
+        SELECT DATE_TRUNC('day', departs), DATE_PART('hour', departs), DATE_ADD(day, 1, 'month'), DATE_DIFF('hour', day, departs),
+            STRFTIME('%H:%M %z', departs)
+        FROM trips WHERE id = 1
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| 2026-01-31T00:00:00+02:00 | 8 | 2026-02-28T00:00:00+00:00 | 6 | 08:15 +0200 |",
+    ]);
+    assert!(printed.contains("DATE_TRUNC('day', departs) <Date/Time with time zone>"));
+
+    // RANGE frames over dates and timestamps are measured with intervals
+    let printed = run(&mut database, "
+        SELECT COUNT(*) OVER (ORDER BY departs RANGE BETWEEN INTERVAL '7 days' PRECEDING AND CURRENT ROW),
+            COUNT(*) OVER (ORDER BY departs RANGE BETWEEN INTERVAL '1 mon' PRECEDING AND CURRENT ROW),
+            COUNT(*) OVER (ORDER BY day RANGE BETWEEN INTERVAL '28 days' PRECEDING AND CURRENT ROW)
+        FROM trips ORDER BY id
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 1 | 1 | 1 |", "| 1 | 2 | 2 |"]);
+
+    run(&mut database, "SET TIME ZONE 'UTC'").unwrap();
+    assert_eq!(run(&mut database, "SHOW TIME ZONE").unwrap(), "+00:00\n");
+    let printed = run(&mut database, "SELECT departs, EXTRACT(HOUR FROM departs), departs::date FROM trips WHERE id = 2").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2026-02-28T21:30:00+00:00 | 21 | 2026-02-28 |"]);
+
+    let printed = run(&mut database, "
+        SELECT day + INTERVAL '1 month', day + 1, DATE '2026-03-01' - day, day + leaves, leaves + length, length * 2, -length
+        FROM trips ORDER BY id
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec![
+        "| 2026-02-28T00:00:00+00:00 | 2026-02-01 | 29 | 2026-01-31T08:15:00+00:00 | 10:45:00 | 05:00:00 | -02:30:00 |",
+        "| 2026-03-28T00:00:00+00:00 | 2026-03-01 | 1 | 2026-02-28T23:30:00+00:00 | 09:00:00 | 19:00:00 | -09:30:00 |",
+    ]);
+    assert!(printed.contains("day + INTERVAL '1 mon' <Date/Time>"));
+
+    let printed = run(&mut database, "
+        SELECT TIMESTAMP '2026-03-01T00:00:00Z' - TIMESTAMP '2026-01-01T12:00:00Z', INTERVAL '1 day' / 4,
+            EXTRACT(EPOCH FROM INTERVAL '1 day 01:00'), EXTRACT(DOW FROM DATE '2026-10-18')
+    ").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 58 days 12:00:00 | 06:00:00 | 90000 | 0 |"]);
+
+    let printed = run(&mut database, "SELECT id FROM trips WHERE day > DATE '2026-02-01' AND length > INTERVAL '3 hours'").unwrap();
+    assert_eq!(result_rows(&printed), vec!["| 2 |"]);
+
+    let error = run(&mut database, "SELECT day + leaves + leaves FROM trips").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidOperands { .. }));
+    let error = run(&mut database, "SELECT length / 0 FROM trips").unwrap_err();
+    assert!(matches!(error, KirinError::DivisionByZero));
+    let error = run(&mut database, "INSERT INTO trips (id, day) VALUES (3, 'next tuesday')").unwrap_err();
+    assert!(matches!(error, KirinError::InvalidConversion { column, .. } if column == "day"));
+}
@@ -0,0 +1,300 @@
+use core::{KirinError, Value};
+
+use sql::ast::{BinaryOp, ColumnRef, Expr, SelectItem, Statement, TableExpr, TableRef, UnaryOp};
+use sql::lexer::{tokenize, Token};
+use sql::parse;
+
+
+#[test]
+fn tokenizer_handles_strings_comments_and_keywords() {
+    let tokens: Vec<Token> = tokenize("select 'it''s, here' -- trailing comment\n /* block */ 1.5")
+        .expect("Input should tokenize")
+        .into_iter()
+        .map(|spanned| spanned.token)
+        .collect();
+
+    assert_eq!(tokens, vec![
+        Token::Keyword(sql::lexer::Keyword::Select),
+        Token::String("it's, here".into()),
+        Token::Decimal("1.5".into()),
+        Token::Eof,
+    ]);
+}
+
+#[test]
+fn tokenizer_tracks_line_and_column() {
+    let tokens = tokenize("SELECT *\n  FROM users").unwrap();
+
+    let from = &tokens[2];
+    assert_eq!(from.span.line, 2);
+    assert_eq!(from.span.column, 3);
+    assert_eq!(from.span.len, 4);
+}
+
+#[test]
+fn tokenizer_spans_tokens_across_newlines() {
+    let tokens = tokenize("SELECT 'x\ny', \"a\nb\" FROM t").unwrap();
+
+    assert_eq!(tokens[1].token, Token::String("x\ny".into()));
+    assert_eq!((tokens[1].span.line, tokens[1].span.column, tokens[1].span.len), (1, 8, 5));
+    assert_eq!(tokens[3].token, Token::Ident("a\nb".into()));
+    assert_eq!((tokens[3].span.line, tokens[3].span.column, tokens[3].span.len), (2, 5, 5));
+}
+
+#[test]
+fn parse_insert_keeps_commas_inside_strings() {
+    let statements = parse("INSERT INTO t VALUES ('a, b', 1), (\"c\", -2.5)").expect("Should parse");
+
+    let [Statement::Insert(insert)] = statements.as_slice() else {
+        panic!("Expected a single INSERT statement");
+    };
+
+    assert_eq!(insert.table, "t");
+    assert_eq!(insert.rows.len(), 2);
+    assert_eq!(insert.rows[0], vec![
+        Expr::Literal(Value::Text("a, b".into())),
+        Expr::Literal(Value::Int(1)),
+    ]);
+    assert_eq!(insert.rows[1][1], Expr::Unary {
+        op: UnaryOp::Minus,
+        expr: Box::new(Expr::Literal(Value::Float(2.5))),
+    });
+}
+
+#[test]
+fn parse_select_respects_operator_precedence() {
+    let statements = parse("SELECT a + b * 2 AS total FROM t").expect("Should parse");
+
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+
+    let column = |name: &str| Box::new(Expr::Column(ColumnRef { table: None, name: name.into() }));
+    let expected = Expr::Binary {
+        left: column("a"),
+        op: BinaryOp::Add,
+        right: Box::new(Expr::Binary {
+            left: column("b"),
+            op: BinaryOp::Mul,
+            right: Box::new(Expr::Literal(Value::Int(2))),
+        }),
+    };
+
+    assert_eq!(select.projection, vec![SelectItem::Expr { expr: expected, alias: Some("total".into()) }]);
+    assert_eq!(select.from, Some(TableExpr::Table(TableRef { name: "t".into(), alias: None })));
+}
+
+#[test]
+fn parse_error_reports_position() {
+    let error = parse("SELECT * FROM\n  'users'").unwrap_err();
+
+    let KirinError::Parse { span, .. } = error else {
+        panic!("Expected a parse error, found {error:?}");
+    };
+    assert_eq!(span.line, 2);
+    assert_eq!(span.column, 3);
+    assert_eq!(span.len, 7);
+}
+
+#[test]
+fn parse_error_after_multiline_string_reports_position() {
+    let error = parse("SELECT 'x\ny' +").unwrap_err();
+
+    let KirinError::Parse { span, .. } = error else {
+        panic!("Expected a parse error, found {error:?}");
+    };
+    assert_eq!((span.line, span.column, span.len), (2, 5, 1));
+}
+
+#[test]
+fn parse_aggregate_calls() {
+    let expr = sql::parser::parse_expr("count(DISTINCT age) + COUNT(*)").expect("Should parse");
+
+    let Expr::Binary { left, op: BinaryOp::Add, right } = expr else {
+        panic!("Expected an addition");
+    };
+    assert_eq!(left.to_string(), "COUNT(DISTINCT age)");
+    assert_eq!(right.to_string(), "COUNT(*)");
+}
+
+#[test]
+fn parse_subqueries() {
+    let expr = sql::parser::parse_expr("id NOT IN (SELECT id FROM t WHERE EXISTS (SELECT * FROM u)) AND x IN (1, 2)").expect("Should parse");
+    assert_eq!(expr.to_string(), "id NOT IN (SELECT id FROM t WHERE EXISTS (SELECT * FROM u)) AND x IN (1, 2)");
+
+    let statements = parse("SELECT * FROM (SELECT a FROM t) AS sub").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert!(matches!(select.from, Some(TableExpr::Subquery { ref alias, .. }) if alias == "sub"));
+}
+
+#[test]
+fn parse_common_table_expressions() {
+    let sql = "WITH RECURSIVE r (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r), s AS (SELECT n FROM r) SELECT n FROM s ORDER BY n";
+    let statements = parse(sql).expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+
+    let with = select.with.as_ref().expect("Should have a WITH clause");
+    assert!(with.recursive);
+    assert_eq!(with.ctes.len(), 2);
+    assert_eq!(with.ctes[0].columns, vec!["n".to_string()]);
+    assert_eq!(with.ctes[0].query.compound.len(), 1);
+    assert_eq!(select.to_string(), sql);
+}
+
+#[test]
+fn parse_window_functions() {
+    let sql = "SELECT SUM(x) OVER (PARTITION BY a, b ORDER BY c DESC ROWS BETWEEN 2 PRECEDING AND UNBOUNDED FOLLOWING), ROW_NUMBER() OVER () FROM t";
+    let statements = parse(sql).expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), sql);
+
+    let statements = parse("SELECT SUM(x) OVER (ORDER BY c RANGE UNBOUNDED PRECEDING) FROM t").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), "SELECT SUM(x) OVER (ORDER BY c RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM t");
+
+    assert!(parse("SELECT SUM(x) OVER (ROWS BETWEEN CURRENT ROW AND UNBOUNDED PRECEDING) FROM t").is_err());
+
+    // Offsets are evaluated outside any row, so they cannot read columns
+    let error = parse("SELECT SUM(x) OVER (ROWS BETWEEN x PRECEDING AND CURRENT ROW) FROM t").unwrap_err();
+    assert!(matches!(error, KirinError::Parse { message, .. } if message.starts_with("Frame offset x must be a constant")));
+    assert!(parse("SELECT SUM(x) OVER (ROWS BETWEEN (SELECT 1) PRECEDING AND CURRENT ROW) FROM t").is_err());
+    assert!(parse("SELECT SUM(x) OVER (ROWS BETWEEN (1 + 1) PRECEDING AND CURRENT ROW) FROM t").is_ok());
+}
+
+#[test]
+fn parse_case_expressions() {
+    let sql = "SELECT CASE WHEN a > 1 THEN 'big' WHEN a IS NULL THEN NULL ELSE 'small' END, CASE b WHEN 1 THEN 2 END FROM t";
+    let statements = parse(sql).expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), sql);
+
+    assert!(parse("SELECT CASE ELSE 1 END").is_err());
+    assert!(parse("SELECT CASE WHEN TRUE THEN 1").is_err());
+}
+
+#[test]
+fn parse_extract_and_timestamps() {
+    let sql = "SELECT EXTRACT(YEAR FROM at), TIMESTAMP '2026-01-01T00:00:00+00:00' FROM t";
+    let statements = parse(sql).expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), sql);
+
+    assert!(parse("SELECT EXTRACT(YEAR at) FROM t").is_err());
+}
+
+#[test]
+fn parse_pattern_matching() {
+    let sql = "SELECT a FROM t WHERE a LIKE 'x!%%' ESCAPE '!' AND b NOT ILIKE 'y%' OR c GLOB '*.rs' AND NOT d REGEXP '^[0-9]+$'";
+    let statements = parse(sql).expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), sql);
+
+    let statements = parse("SELECT a ~ 'b' || 'c' AS escape FROM t").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), "SELECT a REGEXP 'b' || 'c' AS escape FROM t");
+}
+
+#[test]
+fn parse_casts() {
+    let statements = parse("SELECT CAST(a AS VARCHAR(10)), b::int + 1, -c::float, CAST(d AS BOOL) FROM t").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), "SELECT CAST(a AS TEXT), CAST(b AS INTEGER) + 1, -CAST(c AS FLOAT), CAST(d AS BOOLEAN) FROM t");
+
+    let statements = parse("SELECT CAST(a AS NUMERIC(12, 2)), b::decimal, c::dec(7) FROM t").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), "SELECT CAST(a AS DECIMAL(12, 2)), CAST(b AS DECIMAL(18, 0)), CAST(c AS DECIMAL(7, 0)) FROM t");
+
+    assert!(parse("SELECT CAST(a AS DECIMAL(0)) FROM t").is_err());
+    assert!(parse("SELECT CAST(a AS DECIMAL(1001, 2)) FROM t").is_err());
+    assert!(parse("SELECT CAST(a AS money) FROM t").is_err());
+    assert!(parse("SELECT CAST(a INT) FROM t").is_err());
+    assert!(parse("SELECT a: int FROM t").is_err());
+}
+
+#[test]
+fn parse_blob_literals() {
+    let statements = parse("SELECT x'00ff', X'', CAST(a AS BYTEA), b::varbinary(16) FROM t").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), "SELECT X'00FF', X'', CAST(a AS BLOB), CAST(b AS BLOB) FROM t");
+
+    assert!(parse("SELECT X'ABC'").is_err());
+    assert!(parse("SELECT X'GG'").is_err());
+    assert!(parse("SELECT X'AB").is_err());
+    // x on its own is still a name
+    assert!(parse("SELECT x FROM t").is_ok());
+}
+
+#[test]
+fn parse_date_time_and_interval_literals() {
+    let statements = parse("SELECT DATE '2026-01-31', time '08:15', INTERVAL '2' HOUR, TIMESTAMP WITH TIME ZONE '2026-01-01 12:00', \
+        CAST(a AS timestamptz), b::TIMESTAMP WITHOUT TIME ZONE FROM t").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(
+        select.to_string(),
+        "SELECT DATE '2026-01-31', TIME '08:15:00', INTERVAL '02:00:00', CAST('2026-01-01 12:00' AS TIMESTAMP WITH TIME ZONE), \
+        CAST(a AS TIMESTAMP WITH TIME ZONE), CAST(b AS TIMESTAMP) FROM t"
+    );
+
+    let statements = parse("SET TIME ZONE '+05:30'; SET TIME ZONE DEFAULT; SHOW TIME ZONE").expect("Should parse");
+    assert_eq!(statements.len(), 3);
+    assert!(matches!(&statements[0], Statement::SetTimeZone(zone) if zone.local_minus_utc() == 19_800));
+    assert!(matches!(&statements[1], Statement::SetTimeZone(zone) if zone.local_minus_utc() == 0));
+    assert!(matches!(&statements[2], Statement::ShowTimeZone));
+
+    // Only fixed offsets are known, so LOCAL and named zones are refused rather than taken as UTC
+    assert!(matches!(parse("SET TIME ZONE LOCAL"), Err(KirinError::Parse { message, .. }) if message.contains("LOCAL is not supported")));
+    assert!(parse("SET TIME ZONE 'Europe/Paris'").is_err());
+
+    assert!(matches!(parse("SELECT DATE '2026-02-30'"), Err(KirinError::Parse { message, .. }) if message == "Invalid date '2026-02-30'"));
+    assert!(parse("SELECT INTERVAL 'soon'").is_err());
+    assert!(parse("SET TIME ZONE 'Nowhere'").is_err());
+    // Date and time names are still columns when no text follows
+    assert!(parse("SELECT date, time FROM t").is_ok());
+}
+
+#[test]
+fn integer_literals_beyond_i64_stay_exact() {
+    let tokens: Vec<Token> = tokenize("9223372036854775807 9223372036854775808")
+        .expect("Input should tokenize")
+        .into_iter()
+        .map(|spanned| spanned.token)
+        .collect();
+    assert_eq!(tokens, vec![Token::Integer(i64::MAX), Token::Decimal("9223372036854775808".into()), Token::Eof]);
+
+    let statements = parse("SELECT 123456789012345678901234567").expect("Should parse");
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement");
+    };
+    assert_eq!(select.to_string(), "SELECT 123456789012345678901234567");
+    assert!(matches!(&select.projection[0], SelectItem::Expr { expr: Expr::Literal(Value::Decimal(_)), .. }));
+
+    assert_eq!(sql::parser::parse_expr("-9223372036854775808").unwrap(), Expr::Literal(Value::Int(i64::MIN)));
+    assert_eq!(sql::parser::parse_expr("- 9223372036854775809").unwrap(), Expr::Unary {
+        op: UnaryOp::Minus,
+        expr: Box::new(Expr::Literal(Value::Decimal("9223372036854775809".parse().unwrap()))),
+    });
+}
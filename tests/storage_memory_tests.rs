@@ -1,4 +1,5 @@
 use core::{Row, Value, Storage};
+use std::sync::Arc;
 
 use storage::MemoryStorage;
 
@@ -50,4 +51,31 @@ fn memory_storage_insert_and_iter() {
     // Iteration test
     let all_rows: Vec<_> = store.iter().collect();
     assert_eq!(all_rows.len(), 1);
-}
\ No newline at end of file
+}
+
+#[test]
+fn memory_storage_iterates_in_insertion_order() {
+    let mut store = MemoryStorage::new();
+
+    for n in 0..100 {
+        store.insert(Row { values: vec![Value::Int(n)] });
+    }
+
+    let values: Vec<_> = store.iter().map(|row| row.values[0].clone()).collect();
+    let expected: Vec<_> = (0..100).map(Value::Int).collect();
+    assert_eq!(values, expected);
+}
+
+#[test]
+fn memory_storage_scans_share_blob_bytes() {
+    let mut store = MemoryStorage::new();
+
+    let row_id = store.insert(Row { values: vec![Value::Blob(vec![7; 1000].into())] });
+    let scanned: Vec<_> = store.iter().map(|row| row.values.clone()).collect();
+
+    let (Value::Blob(stored), Value::Blob(copy)) = (&store.get(row_id).unwrap().values[0], &scanned[0][0]) else {
+        panic!("Expected a blob");
+    };
+    assert_eq!(&copy[..], &[7; 1000][..]);
+    assert!(Arc::ptr_eq(stored, copy));
+}